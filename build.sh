@@ -7,7 +7,12 @@ rustup target add wasm32-wasip1
 
 cargo clean
 
-cargo build --target wasm32-wasip1 --release
+# FEATURES=core ./build.sh 只保留 Condition / Switch 降级，其它值原样传给 --features
+case "${FEATURES:-full}" in
+  full) cargo build --target wasm32-wasip1 --release ;;
+  core) cargo build --target wasm32-wasip1 --release --no-default-features ;;
+  *) cargo build --target wasm32-wasip1 --release --no-default-features --features "$FEATURES" ;;
+esac
 
 cp target/wasm32-wasip1/release/swc_condition_plugin.wasm ./swc_condition_plugin.wasm
 
@@ -0,0 +1,150 @@
+use swc_core::common::{sync::Lrc, SourceMap, Span};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::codegen::{text_writer::JsWriter, Emitter, Node};
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::{Config, TransformVisitor};
+
+/// A compile-time branch point found by [`analyze`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlSite {
+    pub kind: ControlSiteKind,
+    pub span: Span,
+    /// Source of the test this site renders on, as the transform would
+    /// evaluate it (`is="a"` reads as `value === "a"`). `None` for sites
+    /// without a test, such as `For` or an else case.
+    pub condition: Option<String>,
+    /// Evaluation mode of a Switch and its cases.
+    pub mode: Option<SwitchMode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlSiteKind {
+    Condition,
+    Unless,
+    Show,
+    Switch,
+    Case,
+    Else,
+    For,
+    With,
+    Feature,
+    Platform,
+    /// `<Switch.Case onError>`, rendered when the Switch throws.
+    ErrorFallback,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchMode {
+    Parallel,
+    ShortCircuit,
+}
+
+/// Lists the control-flow sites of `program` in source order without
+/// transforming it, applying the same tag matching as the transform.
+pub fn analyze(program: &Program, config: Config) -> Vec<ControlSite> {
+    analyze_with(TransformVisitor::new(config), program)
+}
+
+pub(crate) fn analyze_with(mut visitor: TransformVisitor, program: &Program) -> Vec<ControlSite> {
+    match program {
+        Program::Module(module) => visitor.scan_module(module, visitor.scan_file(module)),
+        Program::Script(script) => visitor.scan_script(script, visitor.scan_file(script)),
+    }
+
+    let mut collector = SiteCollector { visitor: &visitor, sites: Vec::new() };
+    program.visit_with(&mut collector);
+    collector.sites
+}
+
+struct SiteCollector<'a> {
+    visitor: &'a TransformVisitor,
+    sites: Vec<ControlSite>,
+}
+
+impl SiteCollector<'_> {
+    fn push(&mut self, kind: ControlSiteKind, span: Span, condition: Option<Box<Expr>>, mode: Option<SwitchMode>) {
+        self.sites.push(ControlSite {
+            kind,
+            span,
+            condition: condition.map(|expr| print_expr(&expr)),
+            mode,
+        });
+    }
+
+    fn visit_switch(&mut self, element: &JSXElement, short_circuit: bool) {
+        let visitor = self.visitor;
+        let mode = Some(if short_circuit { SwitchMode::ShortCircuit } else { SwitchMode::Parallel });
+        self.push(ControlSiteKind::Switch, element.span, None, mode);
+
+        let value = visitor.extract_attr_value(&element.opening.attrs, &visitor.atoms.value_atom);
+        for child in &element.children {
+            let JSXElementChild::JSXElement(case) = child else {
+                continue;
+            };
+            if visitor.is_switch_case_element(case) && visitor.is_error_case(case) {
+                self.push(ControlSiteKind::ErrorFallback, case.span, None, mode);
+            } else if visitor.is_switch_case_element(case) {
+                match visitor.extract_case_condition(&case.opening.attrs, value.as_deref()) {
+                    Some(condition) => self.push(ControlSiteKind::Case, case.span, Some(condition), mode),
+                    None if visitor.has_else_attr(&case.opening.attrs) => self.push(ControlSiteKind::Else, case.span, None, mode),
+                    None => {}
+                }
+            } else if visitor.is_switch_else_alias(case) {
+                self.push(ControlSiteKind::Else, case.span, None, mode);
+            }
+            case.children.visit_with(self);
+        }
+    }
+}
+
+impl Visit for SiteCollector<'_> {
+    fn visit_jsx_element(&mut self, element: &JSXElement) {
+        let visitor = self.visitor;
+        let attrs = &element.opening.attrs;
+        if let Some((tag, binding)) = visitor.control_tag_name(&element.opening.name) {
+            if visitor.is_lowerable_tag(binding) && !visitor.is_raw_element(element) {
+                let compat = visitor.atoms.compat();
+                if *tag == visitor.atoms.condition_atom {
+                    self.push(ControlSiteKind::Condition, element.span, visitor.extract_condition_from_attrs(attrs), None);
+                } else if *tag == visitor.atoms.unless_atom {
+                    self.push(ControlSiteKind::Unless, element.span, visitor.extract_condition_from_attrs(attrs), None);
+                } else if *tag == visitor.atoms.switch_atom && visitor.has_switch_case_children(&element.children) {
+                    self.visit_switch(element, visitor.extract_short_circuit_attr(attrs));
+                    return;
+                } else if *tag == visitor.atoms.for_atom {
+                    self.push(ControlSiteKind::For, element.span, None, None);
+                } else if *tag == visitor.atoms.with_atom {
+                    self.push(ControlSiteKind::With, element.span, None, None);
+                } else if *tag == visitor.atoms.feature_atom {
+                    self.push(ControlSiteKind::Feature, element.span, visitor.feature_condition(attrs, element.span), None);
+                } else if *tag == visitor.atoms.platform_atom {
+                    self.push(ControlSiteKind::Platform, element.span, visitor.platform_condition(attrs, element.span), None);
+                } else if visitor.config.solid_show && *tag == visitor.atoms.show_atom {
+                    self.push(ControlSiteKind::Show, element.span, visitor.extract_condition_attr(attrs, &visitor.atoms.when_atom), None);
+                } else if let Some(compat) = compat.filter(|compat| *tag == compat.if_tag) {
+                    self.push(ControlSiteKind::Condition, element.span, visitor.extract_condition_attr(attrs, &compat.condition_attr), None);
+                } else if compat.is_some_and(|compat| *tag == compat.choose_tag) && visitor.has_switch_case_children(&element.children) {
+                    self.visit_switch(element, true);
+                    return;
+                }
+            }
+        }
+        element.visit_children_with(self);
+    }
+}
+
+fn print_expr(expr: &Expr) -> String {
+    let cm = Lrc::new(SourceMap::default());
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+        expr.emit_with(&mut emitter).expect("writing to a Vec cannot fail");
+    }
+    String::from_utf8(buf).expect("codegen emits UTF-8")
+}
@@ -0,0 +1,105 @@
+use swc_core::common::comments::{Comment, CommentKind, Comments};
+use std::cell::Cell;
+
+use swc_core::common::{errors::SourceMapperDyn, sync::Lrc, Span, Spanned, DUMMY_SP, GLOBALS};
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+static BRANCH_ANNOTATION: &str = "condition-switch:branch";
+
+/// Where `annotateBranches` comments are written, and the source map used to
+/// resolve each branch to `file:line`.
+pub(crate) struct BranchAnnotations {
+    comments: Lrc<dyn Comments>,
+    source_map: Lrc<SourceMapperDyn>,
+    synthetic_spans: bool,
+    /// Branches annotated so far; numbers them under `syntheticSpans`.
+    count: Cell<usize>,
+}
+
+impl BranchAnnotations {
+    pub(crate) fn new(comments: Lrc<dyn Comments>, source_map: Lrc<SourceMapperDyn>, synthetic_spans: bool) -> Self {
+        Self { comments, source_map, synthetic_spans, count: Cell::new(0) }
+    }
+
+    fn annotate(&self, branch: Expr) -> Expr {
+        let span = match &branch {
+            Expr::Paren(paren) => paren.expr.span(),
+            branch => branch.span(),
+        };
+        let (span, label) = if self.synthetic_spans {
+            // 生成代码的 span 不可信：注释挂在新分配的位置上，按序号标注
+            let Some(span) = comment_span() else {
+                return branch;
+            };
+            self.count.set(self.count.get() + 1);
+            (span, format!("#{}", self.count.get()))
+        } else {
+            // 同一位置只标注一次，否则几条注释会叠在最先输出的那个节点前
+            if span.is_dummy() || self.is_annotated(span) {
+                return branch;
+            }
+            let loc = self.source_map.lookup_char_pos(span.lo);
+            (span, format!("{}:{}", loc.file.name, loc.line))
+        };
+
+        self.comments.add_leading(span.lo, Comment {
+            kind: CommentKind::Block,
+            span: DUMMY_SP,
+            text: format!(" {BRANCH_ANNOTATION}({label}) ").into(),
+        });
+
+        // 注释挂在括号上：JSX 节点不输出前置注释，放进 JSX 子节点里又会变成文本
+        let expr = match branch {
+            Expr::Paren(paren) => paren.expr,
+            branch => Box::new(branch),
+        };
+        Expr::Paren(ParenExpr { span, expr })
+    }
+
+    fn is_annotated(&self, span: Span) -> bool {
+        self.comments.with_leading(span.lo, |comments| comments.iter().any(|comment| comment.text.contains(BRANCH_ANNOTATION)))
+    }
+}
+
+/// A fresh position that can carry comments, when the host can hand one out.
+fn comment_span() -> Option<Span> {
+    (cfg!(target_arch = "wasm32") || GLOBALS.is_set()).then(Span::dummy_with_cmt)
+}
+
+impl TransformVisitor {
+    /// Prefixes every rendered branch of a generated ternary chain with a
+    /// `/* condition-switch:branch(file:line) */` comment, so bundle analyzers
+    /// can attribute its bytes to the source branch.
+    pub(crate) fn annotate_conditional(&self, expr: Expr) -> Expr {
+        match &self.annotations {
+            Some(annotations) if cfg!(feature = "instrumentation") && self.config.annotate_branches => annotate_cond_chain(annotations, expr),
+            _ => expr,
+        }
+    }
+
+    /// Span covering a branch's children, so each branch has a distinct
+    /// position to annotate.
+    pub(crate) fn branch_span(children: &[JSXElementChild], fallback: Span) -> Span {
+        match (children.first(), children.last()) {
+            (Some(first), Some(last)) => first.span().with_hi(last.span().hi),
+            _ => fallback,
+        }
+    }
+}
+
+fn annotate_cond_chain(annotations: &BranchAnnotations, expr: Expr) -> Expr {
+    match expr {
+        Expr::Cond(mut cond) => {
+            cond.cons = Box::new(annotations.annotate(*cond.cons));
+            cond.alt = Box::new(match *cond.alt {
+                alt @ Expr::Cond(_) => annotate_cond_chain(annotations, alt),
+                alt @ Expr::Lit(Lit::Null(_)) => alt,
+                alt => annotations.annotate(alt),
+            });
+            Expr::Cond(cond)
+        }
+        expr => expr,
+    }
+}
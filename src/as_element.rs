@@ -0,0 +1,68 @@
+use swc_core::common::{util::take::Take, Span};
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Lowers a `<Condition>`-like tag. With `as="section"` the rendered
+    /// branch is a `<section>` carrying the tag's other attributes instead of
+    /// a fragment.
+    pub(crate) fn lower_condition_tag(&mut self, element: &mut JSXElement, condition: Box<Expr>, fallback: Option<Box<Expr>>, span: Span) -> JSXElement {
+        self.inline_render_prop(&mut element.children);
+        self.note_nullish_condition(&condition);
+        if fallback.is_none() && self.is_keep_mounted(&element.opening.attrs) {
+            return self.keep_mounted_element(element, condition);
+        }
+        match self.take_as_element(element) {
+            Some(wrapped) => {
+                let conditional = self.create_conditional_of(condition, Expr::JSXElement(Box::new(wrapped)), fallback, span);
+                self.wrap_expr_for_context(conditional, span)
+            }
+            None => self.create_conditional_jsx(condition, element.children.take(), fallback, span),
+        }
+    }
+
+    /// The `as` element of a control tag, holding its children and every
+    /// attribute the tag itself does not read.
+    pub(crate) fn take_as_element(&self, element: &mut JSXElement) -> Option<JSXElement> {
+        let tag = self.attr_str(&element.opening.attrs, &self.atoms.as_atom)?;
+        Some(self.take_into_element(element, &tag))
+    }
+
+    /// Moves the children and non-control attributes of `element` into a
+    /// new `tag` element.
+    pub(crate) fn take_into_element(&self, element: &mut JSXElement, tag: &str) -> JSXElement {
+        let span = element.span;
+        let name = Self::fragment_element_name(tag, span, self.syntax_context);
+        let attrs = element.opening.attrs.iter()
+            .filter(|attr| !self.is_control_attr(attr))
+            .cloned()
+            .collect();
+        JSXElement {
+            span,
+            opening: JSXOpeningElement { span, name: name.clone(), attrs, self_closing: false, type_args: None },
+            children: element.children.take(),
+            closing: Some(JSXClosingElement { span, name }),
+        }
+    }
+
+    pub(crate) fn attr_str(&self, attrs: &[JSXAttrOrSpread], atom: &swc_core::atoms::Atom) -> Option<String> {
+        attrs.iter().find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), value: Some(JSXAttrValue::Lit(Lit::Str(value))), .. })
+                if name.sym == *atom => Some(value.value.to_string()),
+            _ => None,
+        })
+    }
+
+    /// Attributes read by condition-like tags, plus `as`, `keepMounted` and `key`.
+    fn is_control_attr(&self, attr: &JSXAttrOrSpread) -> bool {
+        let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), .. }) = attr else {
+            return false;
+        };
+        let atoms = &self.atoms;
+        [&atoms.as_atom, &atoms.keep_mounted_atom, &atoms.key_atom, &atoms.if_atom, &atoms.when_atom, &atoms.fallback_atom, &atoms.name_atom, &atoms.not_atom]
+            .into_iter()
+            .chain(atoms.compat().map(|compat| &compat.condition_attr))
+            .any(|atom| name.sym == *atom)
+    }
+}
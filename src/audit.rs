@@ -0,0 +1,111 @@
+use swc_core::common::Span;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::analysis::analyze_with;
+use crate::{Config, ControlSiteKind, TransformFactory};
+
+/// Adoption of control tags across the files of an [`audit`] run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    /// Files with a runtime conditional or a control tag, most runtime
+    /// conditionals first.
+    pub files: Vec<FileAudit>,
+    pub control_sites: usize,
+    pub runtime_conditionals: usize,
+}
+
+impl AuditReport {
+    /// Share of conditional rendering already written as control tags, from
+    /// 0.0 to 1.0; 1.0 when there is none at all.
+    pub fn adoption(&self) -> f64 {
+        let total = self.control_sites + self.runtime_conditionals;
+        if total == 0 {
+            return 1.0;
+        }
+        self.control_sites as f64 / total as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileAudit {
+    pub path: String,
+    /// `Condition`, `Unless`, `Show`, `Switch`, `Feature` and `Platform` tags.
+    pub control_sites: usize,
+    /// `{a ? <A/> : <B/>}` and `{a && <A/>}` children that a control tag
+    /// could replace, in source order.
+    pub runtime_conditionals: Vec<Span>,
+}
+
+/// Counts, per file, the conditional JSX still written as ternaries or `&&`
+/// / `||` next to the control tags already in use, without transforming
+/// anything. Tags are matched as the transform would with `config`.
+pub fn audit(files: &[(String, Program)], config: Config) -> AuditReport {
+    let factory = TransformFactory::new(config);
+    let mut report = AuditReport::default();
+
+    for (path, program) in files {
+        let control_sites = analyze_with(factory.visitor(), program)
+            .iter()
+            .filter(|site| {
+                matches!(
+                    site.kind,
+                    ControlSiteKind::Condition
+                        | ControlSiteKind::Unless
+                        | ControlSiteKind::Show
+                        | ControlSiteKind::Switch
+                        | ControlSiteKind::Feature
+                        | ControlSiteKind::Platform
+                )
+            })
+            .count();
+        let mut finder = RuntimeConditionalFinder::default();
+        program.visit_with(&mut finder);
+
+        if control_sites == 0 && finder.spans.is_empty() {
+            continue;
+        }
+        report.control_sites += control_sites;
+        report.runtime_conditionals += finder.spans.len();
+        report.files.push(FileAudit { path: path.clone(), control_sites, runtime_conditionals: finder.spans });
+    }
+    // 稳定排序，数量相同的文件保持传入顺序
+    report.files.sort_by_key(|file| std::cmp::Reverse(file.runtime_conditionals.len()));
+    report
+}
+
+#[derive(Default)]
+struct RuntimeConditionalFinder {
+    spans: Vec<Span>,
+}
+
+impl Visit for RuntimeConditionalFinder {
+    fn visit_jsx_expr_container(&mut self, container: &JSXExprContainer) {
+        if let JSXExpr::Expr(expr) = &container.expr {
+            if is_runtime_conditional(expr) {
+                self.spans.push(container.span);
+            }
+        }
+        container.visit_children_with(self);
+    }
+}
+
+/// A ternary with a JSX branch, or `&&` / `||` rendering JSX on the right.
+fn is_runtime_conditional(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_runtime_conditional(&paren.expr),
+        Expr::Cond(cond) => is_jsx(&cond.cons) || is_jsx(&cond.alt),
+        Expr::Bin(bin) if matches!(bin.op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr) => is_jsx(&bin.right),
+        _ => false,
+    }
+}
+
+fn is_jsx(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_jsx(&paren.expr),
+        Expr::JSXElement(_) | Expr::JSXFragment(_) => true,
+        // a ? <A/> : b ? <B/> : null 这样的链也算
+        Expr::Cond(_) | Expr::Bin(_) => is_runtime_conditional(expr),
+        _ => false,
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use swc_core::ecma::ast::Program;
+
+use crate::analysis::analyze_with;
+use crate::{run_stages, Config, ControlSiteKind, TransformFactory, TransformHost};
+
+/// Totals over the files of a [`transform_batch`] run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    pub files: usize,
+    /// Files with at least one control site.
+    pub transformed_files: usize,
+    /// Control sites per kind, in the order kinds were first seen.
+    pub sites: Vec<(ControlSiteKind, usize)>,
+}
+
+impl BatchReport {
+    fn count(&mut self, kind: ControlSiteKind) {
+        match self.sites.iter_mut().find(|(seen, _)| *seen == kind) {
+            Some((_, count)) => *count += 1,
+            None => self.sites.push((kind, 1)),
+        }
+    }
+}
+
+/// Transforms several files (path → program) for codemods and build tools
+/// that drive the crate directly. The config, including its vocabulary
+/// file, is resolved once and `env` is shared by every file's `process.env`
+/// folding. Files keep their order; those `include` / `exclude` rule out are
+/// returned untouched. Relative globs match paths under `cwd`.
+pub fn transform_batch(
+    files: Vec<(String, Program)>,
+    config: Config,
+    env: HashMap<String, String>,
+    cwd: Option<String>,
+) -> (Vec<(String, Program)>, BatchReport) {
+    let factory = TransformFactory::new(config);
+    let mut report = BatchReport::default();
+
+    let files = files
+        .into_iter()
+        .map(|(path, program)| {
+            report.files += 1;
+            if !factory.config().applies_to(Some(&path), cwd.as_deref()) {
+                return (path, program);
+            }
+            let sites = analyze_with(factory.visitor(), &program);
+            if !sites.is_empty() {
+                report.transformed_files += 1;
+            }
+            sites.iter().for_each(|site| report.count(site.kind));
+
+            let host = TransformHost { env: env.clone(), filename: Some(path.clone()), cwd: cwd.clone(), ..Default::default() };
+            (path, run_stages(program, &factory, host))
+        })
+        .collect();
+    (files, report)
+}
@@ -0,0 +1,16 @@
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Whether `ident` refers to a local binding of a control tag name
+    /// (declaration, parameter or destructuring, at any depth) rather than
+    /// the control component. Imports are not bindings here: they are how
+    /// the intended tags are brought into scope.
+    ///
+    /// After SWC's resolver has run, the binding's syntax context tells the
+    /// two apart; without it, any local binding of the name matches.
+    pub(crate) fn is_shadowed_tag(&self, ident: &Ident) -> bool {
+        self.shadowed_tags.iter().any(|(sym, ctxt)| *sym == ident.sym && *ctxt == ident.ctxt)
+    }
+}
@@ -0,0 +1,100 @@
+use serde::Deserialize;
+use swc_core::common::Span;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+/// Element used instead of a fragment around multi-child branches, e.g.
+/// `branchWrapper: { element: "div", props: { "className": "branch" } }`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BranchWrapper {
+    /// Tag name, `"div"` or a member path such as `"UI.Group"`.
+    pub element: String,
+    /// Attributes of the wrapper; values are written as JSON literals.
+    #[serde(default)]
+    pub props: serde_json::Map<String, serde_json::Value>,
+}
+
+impl TransformVisitor {
+    /// The rendered side of a branch: a fragment, or the `branchWrapper`
+    /// element when the branch has more than one child.
+    pub(crate) fn branch_expr(&self, children: Vec<JSXElementChild>, span: Span) -> Expr {
+        // transformNested 在 return / 赋值上下文里降级出的分支直接作为表达式
+        let mut non_whitespace = children.iter().filter(|child| Self::is_non_whitespace_child(child));
+        if let (Some(JSXElementChild::JSXElement(element)), None) = (non_whitespace.next(), non_whitespace.next()) {
+            if self.is_placeholder(element) {
+                let element = children.into_iter().find_map(|child| match child {
+                    JSXElementChild::JSXElement(element) => Some(element),
+                    _ => None,
+                });
+                return Expr::JSXElement(element.expect("checked above"));
+            }
+        }
+        if let Some((key, children)) = self.take_keyed_case(&children) {
+            return self.keyed_branch_expr(children, key, span);
+        }
+        let branch_span = Self::branch_span(&children, span);
+        let wrapper = self.config.branch_wrapper.as_ref()
+            .filter(|_| children.iter().filter(|child| Self::is_non_whitespace_child(child)).count() > 1);
+        let Some(wrapper) = wrapper else {
+            return Expr::JSXFragment(JSXFragment {
+                span: branch_span,
+                opening: JSXOpeningFragment { span },
+                children,
+                closing: JSXClosingFragment { span },
+            });
+        };
+
+        let name = Self::fragment_element_name(&wrapper.element, span, self.syntax_context);
+        let attrs = wrapper.props.iter().map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => JSXAttrValue::Lit(Lit::Str(value.as_str().into())),
+                value => JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    span,
+                    expr: JSXExpr::Expr(Box::new(json_expr(value, span))),
+                }),
+            };
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span,
+                name: JSXAttrName::Ident(IdentName::new(key.as_str().into(), span)),
+                value: Some(value),
+            })
+        }).collect();
+        Expr::JSXElement(Box::new(JSXElement {
+            span: branch_span,
+            opening: JSXOpeningElement { span, name: name.clone(), attrs, self_closing: false, type_args: None },
+            children,
+            closing: Some(JSXClosingElement { span, name }),
+        }))
+    }
+}
+
+fn json_expr(value: &serde_json::Value, span: Span) -> Expr {
+    match value {
+        serde_json::Value::Null => Expr::Lit(Lit::Null(Null { span })),
+        serde_json::Value::Bool(value) => Expr::Lit(Lit::Bool(Bool { span, value: *value })),
+        serde_json::Value::Number(value) => Expr::Lit(Lit::Num(Number {
+            span,
+            value: value.as_f64().unwrap_or_default(),
+            raw: None,
+        })),
+        serde_json::Value::String(value) => Expr::Lit(Lit::Str(value.as_str().into())),
+        serde_json::Value::Array(items) => Expr::Array(ArrayLit {
+            span,
+            elems: items.iter()
+                .map(|item| Some(ExprOrSpread { spread: None, expr: Box::new(json_expr(item, span)) }))
+                .collect(),
+        }),
+        serde_json::Value::Object(entries) => Expr::Object(ObjectLit {
+            span,
+            props: entries.iter().map(|(key, value)| {
+                let key = if Ident::verify_symbol(key).is_ok() {
+                    PropName::Ident(IdentName::new(key.as_str().into(), span))
+                } else {
+                    PropName::Str(key.as_str().into())
+                };
+                PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp { key, value: Box::new(json_expr(value, span)) })))
+            }).collect(),
+        }),
+    }
+}
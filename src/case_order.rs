@@ -0,0 +1,32 @@
+use swc_core::common::Span;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// `order={10}` or `order="10"` on a Switch case, with the case's span.
+    pub(crate) fn case_order(&self, case: &JSXElement) -> Option<(f64, Span)> {
+        self.number_attr(case, &self.atoms.order_atom).map(|order| (order, case.span))
+    }
+
+    /// Sorts the cases of a short-circuit Switch by `order`, lowest first;
+    /// cases without one run after those with one, and equal orders keep
+    /// their source order, with a warning since that is rarely intended.
+    pub(crate) fn sort_cases_by_order(&mut self, cases: &mut Vec<(Box<Expr>, Vec<JSXElementChild>)>, orders: Vec<Option<(f64, Span)>>) {
+        if orders.iter().all(Option::is_none) {
+            return;
+        }
+        let mut seen: Vec<f64> = Vec::new();
+        for (order, span) in orders.iter().flatten() {
+            if seen.contains(order) {
+                self.report_warning(*span, "duplicate-case-order", format!("another case of this Switch also has `order={{{order}}}`; the two run in source order"));
+            } else {
+                seen.push(*order);
+            }
+        }
+
+        let mut ranked: Vec<_> = orders.into_iter().map(|order| order.map_or(f64::INFINITY, |(order, _)| order)).zip(cases.drain(..)).collect();
+        ranked.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        cases.extend(ranked.into_iter().map(|(_, case)| case));
+    }
+}
@@ -0,0 +1,90 @@
+use swc_core::common::{Span, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use crate::{FRAGMENT_EXPORT, FRAGMENT_SHORTHAND, REACT_FRAGMENT, REACT_MODULE};
+
+static PREACT_MODULE: &str = "preact";
+
+/// Builds the output nodes whose shape depends on the JSX runtime.
+/// [`TransformFactory::new`](crate::TransformFactory::new) picks one from
+/// `target`; embedders can supply their own through
+/// [`TransformFactory::with_codegen`](crate::TransformFactory::with_codegen).
+pub trait Codegen: Send + Sync {
+    /// Module the runtime's `Fragment` is exported from.
+    fn fragment_module(&self) -> &str;
+
+    /// Wrapper used while the `fragment` option is left at its default.
+    fn default_fragment(&self) -> &str;
+
+    /// Holds a lowered expression among JSX children: `<Fragment>{expr}</Fragment>`.
+    fn make_fragment(&self, name: JSXElementName, expr: Expr, span: Span) -> JSXElement {
+        let child = JSXElementChild::JSXExprContainer(JSXExprContainer { span, expr: JSXExpr::Expr(Box::new(expr)) });
+        self.make_fragment_children(name, vec![child], span)
+    }
+
+    /// Holds several lowered children, such as the cases of a parallel
+    /// Switch: `<Fragment>{a ? … : null}{b ? … : null}</Fragment>`.
+    fn make_fragment_children(&self, name: JSXElementName, children: Vec<JSXElementChild>, span: Span) -> JSXElement {
+        JSXElement {
+            span,
+            opening: JSXOpeningElement { span, name: name.clone(), attrs: vec![], self_closing: false, type_args: None },
+            children,
+            closing: Some(JSXClosingElement { span, name }),
+        }
+    }
+
+    /// `test ? rendered : fallback` for a lowered branch.
+    fn make_conditional_child(&self, test: Box<Expr>, rendered: Box<Expr>, fallback: Box<Expr>, span: Span) -> Expr {
+        Expr::Cond(CondExpr { span, test, cons: rendered, alt: fallback })
+    }
+
+    /// `callee(condition)`, e.g. `Boolean(x)`.
+    fn make_coercion_call(&self, callee: Ident, condition: Box<Expr>, span: Span, ctxt: SyntaxContext) -> Expr {
+        Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(Box::new(Expr::Ident(Ident { span, ..callee }))),
+            args: vec![ExprOrSpread { spread: None, expr: condition }],
+            type_args: None,
+            ctxt,
+        })
+    }
+}
+
+/// Classic React runtime: `React` is in scope, so `<React.Fragment>` works.
+pub struct ReactClassic;
+
+impl Codegen for ReactClassic {
+    fn fragment_module(&self) -> &str {
+        REACT_MODULE
+    }
+
+    fn default_fragment(&self) -> &str {
+        REACT_FRAGMENT
+    }
+}
+
+/// Automatic React runtime: `<>…</>` compiles without any identifier in scope.
+pub struct ReactAutomatic;
+
+impl Codegen for ReactAutomatic {
+    fn fragment_module(&self) -> &str {
+        REACT_MODULE
+    }
+
+    fn default_fragment(&self) -> &str {
+        FRAGMENT_SHORTHAND
+    }
+}
+
+/// Preact: `Fragment` from `preact`.
+pub struct Preact;
+
+impl Codegen for Preact {
+    fn fragment_module(&self) -> &str {
+        PREACT_MODULE
+    }
+
+    fn default_fragment(&self) -> &str {
+        FRAGMENT_EXPORT
+    }
+}
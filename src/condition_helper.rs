@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use swc_core::atoms::Atom;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::target::insert_named_import;
+use crate::TransformVisitor;
+
+/// Function called in place of `Boolean(…)`, e.g.
+/// `conditionHelper: { name: "isTruthy", from: "@acme/utils" }`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConditionHelper {
+    /// Named export of `from`, also used as the local name.
+    pub name: String,
+    /// Module the helper is imported from.
+    pub from: String,
+}
+
+impl TransformVisitor {
+    /// Imports the `conditionHelper` once a generated test calls it.
+    pub(crate) fn insert_condition_helper_import(&self, module: &mut Module) {
+        let Some(helper) = &self.config.condition_helper else {
+            return;
+        };
+        if calls(module, &self.boolean_ident.sym) {
+            insert_named_import(module, &self.boolean_ident, &helper.name, &helper.from);
+        }
+    }
+}
+
+fn calls(module: &Module, sym: &Atom) -> bool {
+    let mut finder = CallFinder { sym, found: false };
+    module.visit_with(&mut finder);
+    finder.found
+}
+
+struct CallFinder<'a> {
+    sym: &'a Atom,
+    found: bool,
+}
+
+impl Visit for CallFinder<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if matches!(&call.callee, Callee::Expr(callee) if matches!(&**callee, Expr::Ident(ident) if ident.sym == *self.sym)) {
+            self.found = true;
+        }
+        call.visit_children_with(self);
+    }
+}
@@ -0,0 +1,254 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use swc_core::atoms::Atom;
+use swc_core::common::{errors::HANDLER, Span, Spanned, DUMMY_SP};
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+static STDOUT_TARGET: &str = "-";
+
+/// One finding in the shape of an LSP `Diagnostic`, written one per line to
+/// `diagnosticsJson`. Lines and characters are zero-based.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct JsonDiagnostic {
+    file: String,
+    range: Range,
+    severity: Severity,
+    code: &'static str,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+    Information,
+}
+
+/// Severity a diagnostic code is reported at, set per code in `diagnostics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    Off,
+    Warn,
+    Error,
+}
+
+impl TransformVisitor {
+    /// Records a finding for `diagnosticsJson`. Without a source map or a
+    /// real span there is no range to give, so nothing is recorded.
+    pub(crate) fn record_json_diagnostic(&mut self, span: Span, severity: Severity, code: &'static str, message: String) {
+        let Some(source_map) = self.config.diagnostics_json.as_ref().and(self.source_map.as_ref()) else {
+            return;
+        };
+        let span = self.diagnostic_span(span);
+        if span.is_dummy() {
+            return;
+        }
+
+        let start = source_map.lookup_char_pos(span.lo);
+        let end = source_map.lookup_char_pos(span.hi);
+        self.json_diagnostics.push(JsonDiagnostic {
+            file: start.file.name.to_string(),
+            range: Range {
+                start: Position { line: start.line - 1, character: start.col.0 },
+                end: Position { line: end.line - 1, character: end.col.0 },
+            },
+            severity,
+            code,
+            message,
+        });
+    }
+
+    /// The severity `code` is reported at: its `diagnostics` entry if there
+    /// is one, else `default`. `None` when the rule is turned off.
+    pub(crate) fn rule_severity(&self, code: &str, default: Severity) -> Option<Severity> {
+        match self.config.diagnostics.get(code) {
+            Some(DiagnosticLevel::Off) => None,
+            Some(DiagnosticLevel::Warn) => Some(Severity::Warning),
+            Some(DiagnosticLevel::Error) => Some(Severity::Error),
+            None => Some(default),
+        }
+    }
+
+    /// Emits a warning through SWC and records it for `diagnosticsJson`.
+    pub(crate) fn report_warning(&mut self, span: Span, code: &'static str, message: String) {
+        self.report(span, code, Severity::Warning, message);
+    }
+
+    /// Emits an error through SWC and records it for `diagnosticsJson`.
+    pub(crate) fn report_error(&mut self, span: Span, code: &'static str, message: String) {
+        self.report(span, code, Severity::Error, message);
+    }
+
+    /// Reports a rule that stays silent until `diagnostics` gives it a level.
+    pub(crate) fn report_opt_in(&mut self, span: Span, code: &'static str, message: String) {
+        if self.config.diagnostics.contains_key(code) {
+            self.report(span, code, Severity::Warning, message);
+        }
+    }
+
+    /// Emits a note through SWC and records it for `diagnosticsJson`.
+    pub(crate) fn report_note(&mut self, span: Span, code: &'static str, message: String) {
+        self.report(span, code, Severity::Information, message);
+    }
+
+    /// `span`, or a dummy under `syntheticSpans`, where pointing at the
+    /// span would point at unrelated code.
+    pub(crate) fn diagnostic_span(&self, span: Span) -> Span {
+        if self.config.synthetic_spans {
+            DUMMY_SP
+        } else {
+            span
+        }
+    }
+
+    fn report(&mut self, span: Span, code: &'static str, default: Severity, message: String) {
+        let Some(severity) = self.rule_severity(code, default) else {
+            return;
+        };
+        let span = self.diagnostic_span(span);
+        self.record_json_diagnostic(span, severity, code, message.clone());
+        if !HANDLER.is_set() {
+            return;
+        }
+        HANDLER.with(|handler| match severity {
+            Severity::Error => handler.struct_span_err(span, &message).emit(),
+            Severity::Warning => handler.struct_span_warn(span, &message).emit(),
+            Severity::Information => handler.span_note_without_error(span, &message),
+        });
+    }
+
+    /// Appends the recorded findings as JSON Lines to the configured file, or
+    /// prints them when the target is `-`.
+    pub(crate) fn flush_json_diagnostics(&mut self) {
+        let Some(target) = self.config.diagnostics_json.as_deref() else {
+            return;
+        };
+        let diagnostics = std::mem::take(&mut self.json_diagnostics);
+        if diagnostics.is_empty() {
+            return;
+        }
+
+        let lines: String = diagnostics
+            .iter()
+            .map(|diagnostic| serde_json::to_string(diagnostic).expect("diagnostic is serializable") + "\n")
+            .collect();
+        if target == STDOUT_TARGET {
+            print!("{lines}");
+            return;
+        }
+
+        let path = resolve_diagnostics_path(target);
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()));
+        if let Err(err) = written {
+            if HANDLER.is_set() {
+                HANDLER.with(|handler| {
+                    handler.warn(&format!("cannot write diagnosticsJson `{}`: {err}", path.display()));
+                });
+            }
+        }
+    }
+}
+
+/// 与 vocabularyFile 一样，wasm 沙箱里的相对路径以 `/cwd` 为根
+fn resolve_diagnostics_path(file: &str) -> PathBuf {
+    let path = Path::new(file);
+    if cfg!(target_arch = "wasm32") && path.is_relative() {
+        Path::new("/cwd").join(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+impl TransformVisitor {
+    /// Explains why the `attr` of a condition-like tag or Switch case gave no
+    /// condition: it is missing, a string, or an empty `{}`.
+    pub(crate) fn report_invalid_condition(&mut self, element: &JSXElement, attr: &Atom) {
+        let tag = jsx_name(&element.opening.name);
+        let outcome = if self.is_switch_case_element(element) {
+            "this case never renders"
+        } else {
+            "the tag is left as a runtime component"
+        };
+        let found = element.opening.attrs.iter().find_map(|spread| match spread {
+            JSXAttrOrSpread::JSXAttr(jsx_attr) => match &jsx_attr.name {
+                JSXAttrName::Ident(name) if name.sym == *attr => Some(jsx_attr),
+                _ => None,
+            },
+            JSXAttrOrSpread::SpreadElement(_) => None,
+        });
+        match found.map(|jsx_attr| (&jsx_attr.value, jsx_attr.span)) {
+            None => self.report_warning(
+                element.opening.span,
+                "missing-condition",
+                format!("`<{tag}>` has no `{attr}` attribute; {outcome}"),
+            ),
+            Some((Some(JSXAttrValue::Lit(Lit::Str(_))), span)) => self.report_warning(
+                span,
+                "string-condition",
+                format!("`{attr}` takes an expression, `{attr}={{…}}`, not a string; {outcome}"),
+            ),
+            Some((Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::JSXEmptyExpr(_), .. })), span)) => self.report_warning(
+                span,
+                "empty-condition",
+                format!("`{attr}={{}}` has no expression; {outcome}"),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    /// Flags a condition that only combines literals (`if={true}`, `if={0}`),
+    /// which usually is debug code left behind. Opt-in as `constant-condition`.
+    pub(crate) fn lint_constant_condition(&mut self, condition: &Expr) {
+        if let Some(value) = self.literal_condition_value(condition) {
+            let message = format!("this condition is always {value}, which usually means leftover debug code");
+            self.report_opt_in(condition.span(), "constant-condition", message);
+        }
+    }
+
+    pub(crate) fn report_case_with_if_and_else(&mut self, element: &JSXElement) {
+        let message = format!(
+            "`<{}>` has both `{}` and `{}`; it is a conditional case and `{}` is ignored",
+            jsx_name(&element.opening.name), self.atoms.if_atom, self.atoms.else_atom, self.atoms.else_atom,
+        );
+        self.report_warning(element.opening.span, "case-if-and-else", message);
+    }
+}
+
+/// `Condition`, `Switch.Case`, `Flow.Condition`, … as written.
+pub(crate) fn jsx_name(name: &JSXElementName) -> String {
+    match name {
+        JSXElementName::Ident(ident) => ident.sym.to_string(),
+        JSXElementName::JSXMemberExpr(member) => format!("{}.{}", jsx_object_name(&member.obj), member.prop.sym),
+        JSXElementName::JSXNamespacedName(name) => format!("{}:{}", name.ns.sym, name.name.sym),
+    }
+}
+
+fn jsx_object_name(object: &JSXObject) -> String {
+    match object {
+        JSXObject::Ident(ident) => ident.sym.to_string(),
+        JSXObject::JSXMemberExpr(member) => format!("{}.{}", jsx_object_name(&member.obj), member.prop.sym),
+    }
+}
@@ -0,0 +1,40 @@
+use swc_core::atoms::Atom;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Picks the else case of a Switch with several: the lowest `priority`
+    /// wins, cases without one rank last, and ties go to the later case.
+    /// The others are dropped with a warning.
+    pub(crate) fn pick_else_case(&mut self, mut candidates: Vec<JSXElement>) -> Option<Vec<JSXElementChild>> {
+        let priorities: Vec<_> = candidates.iter().map(|element| self.else_priority(element)).collect();
+        let chosen = (0..candidates.len()).rev().min_by(|&a, &b| priorities[a].total_cmp(&priorities[b]))?;
+
+        for (index, element) in candidates.iter().enumerate() {
+            if index != chosen {
+                self.report_ignored_else(element);
+            }
+        }
+        Some(candidates.swap_remove(chosen).children)
+    }
+
+    /// `priority={1}` or `priority="1"`; anything else ranks last.
+    fn else_priority(&self, element: &JSXElement) -> f64 {
+        self.number_attr(element, &self.atoms.priority_atom).unwrap_or(f64::INFINITY)
+    }
+
+    /// A numeric attribute written as `name={1}` or `name="1"`.
+    pub(crate) fn number_attr(&self, element: &JSXElement, atom: &Atom) -> Option<f64> {
+        match self.extract_attr_value(&element.opening.attrs, atom).as_deref() {
+            Some(Expr::Lit(Lit::Num(num))) => Some(num.value),
+            Some(Expr::Lit(Lit::Str(str))) => str.value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn report_ignored_else(&mut self, element: &JSXElement) {
+        let message = "else case ignored: this Switch renders another one (the lowest `priority` wins, later cases win ties)".to_string();
+        self.report_warning(element.span, "ignored-else", message);
+    }
+}
@@ -0,0 +1,191 @@
+use swc_core::common::Spanned;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+static PROCESS_OBJECT: &str = "process";
+static ENV_MEMBER: &str = "env";
+
+/// Build-time value of a (sub)expression in a static condition.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Null,
+    Undefined,
+}
+
+impl Value {
+    fn is_truthy(&self) -> bool {
+        match self {
+            Value::Str(value) => !value.is_empty(),
+            Value::Num(value) => *value != 0.0 && !value.is_nan(),
+            Value::Bool(value) => *value,
+            Value::Null | Value::Undefined => false,
+        }
+    }
+
+    /// `==`; `None` where loose equality would coerce between types.
+    fn loose_eq(&self, other: &Value) -> Option<bool> {
+        match (self, other) {
+            (Value::Null | Value::Undefined, Value::Null | Value::Undefined) => Some(true),
+            (Value::Null | Value::Undefined, _) | (_, Value::Null | Value::Undefined) => Some(false),
+            _ if std::mem::discriminant(self) == std::mem::discriminant(other) => Some(self == other),
+            _ => None,
+        }
+    }
+}
+
+/// What a static condition read besides literals.
+#[derive(Default)]
+struct Reads {
+    env: bool,
+    define: bool,
+}
+
+impl TransformVisitor {
+    /// Evaluates a condition known at build time: literals such as
+    /// `if={false}`, and with the `defines` feature also `define` constants
+    /// and `process.env.NAME` (unless `foldEnv` is off). `None` unless it
+    /// only combines those with `!`, `&&`, `||` and (in)equality.
+    pub(crate) fn evaluate_static_condition(&self, condition: &Expr) -> Option<bool> {
+        let mut reads = Reads::default();
+        let value = self.evaluate(condition, &mut reads)?;
+        (!reads.env || self.config.fold_env).then(|| value.is_truthy())
+    }
+
+    /// The value of a condition built from literals alone, such as `true`,
+    /// `0` or `!1`; `define` and `env` reads are deliberate and do not count.
+    pub(crate) fn literal_condition_value(&self, condition: &Expr) -> Option<bool> {
+        let mut reads = Reads::default();
+        let value = self.evaluate(condition, &mut reads)?;
+        (!reads.env && !reads.define).then(|| value.is_truthy())
+    }
+
+    /// Drops Switch cases whose condition is always false. A case that is
+    /// always true ends a short-circuit chain as its else branch; in parallel
+    /// mode it renders unconditionally and the else branch can never show.
+    pub(crate) fn fold_env_cases(
+        &self,
+        cases: &mut Vec<(Box<Expr>, Vec<JSXElementChild>)>,
+        else_case: &mut Option<Vec<JSXElementChild>>,
+        short_circuit: bool,
+    ) {
+        let mut folded = Vec::with_capacity(cases.len());
+        for (condition, children) in cases.drain(..) {
+            match self.evaluate_static_condition(&condition) {
+                Some(false) => {}
+                Some(true) if short_circuit => {
+                    *else_case = Some(children);
+                    break;
+                }
+                Some(true) => {
+                    *else_case = None;
+                    folded.push((Box::new(Expr::Lit(Lit::Bool(Bool { span: condition.span(), value: true }))), children));
+                }
+                None => folded.push((condition, children)),
+            }
+        }
+        *cases = folded;
+    }
+
+    fn evaluate(&self, expr: &Expr, reads: &mut Reads) -> Option<Value> {
+        match expr {
+            Expr::Paren(paren) => self.evaluate(&paren.expr, reads),
+            Expr::Lit(Lit::Str(value)) => Some(Value::Str(value.value.to_string())),
+            Expr::Lit(Lit::Num(value)) => Some(Value::Num(value.value)),
+            Expr::Lit(Lit::Bool(value)) => Some(Value::Bool(value.value)),
+            Expr::Lit(Lit::Null(_)) => Some(Value::Null),
+            Expr::Ident(ident) if &*ident.sym == "undefined" => Some(Value::Undefined),
+            Expr::Member(member) if env_member_name(member).is_some() => {
+                reads.env = true;
+                self.lookup_env(env_member_name(member)?).map(|value| Value::Str(value.to_string()))
+            }
+            Expr::Ident(_) | Expr::Member(_) => {
+                reads.define = true;
+                self.lookup_define(&member_path(expr)?)
+            }
+            Expr::Unary(UnaryExpr { op: UnaryOp::Bang, arg, .. }) => {
+                Some(Value::Bool(!self.evaluate(arg, reads)?.is_truthy()))
+            }
+            Expr::Bin(bin) => {
+                let left = self.evaluate(&bin.left, reads)?;
+                let right = self.evaluate(&bin.right, reads)?;
+                match bin.op {
+                    BinaryOp::EqEqEq => Some(Value::Bool(left == right)),
+                    BinaryOp::NotEqEq => Some(Value::Bool(left != right)),
+                    BinaryOp::EqEq => left.loose_eq(&right).map(Value::Bool),
+                    BinaryOp::NotEq => left.loose_eq(&right).map(|equal| Value::Bool(!equal)),
+                    BinaryOp::LogicalAnd => Some(if left.is_truthy() { right } else { left }),
+                    BinaryOp::LogicalOr => Some(if left.is_truthy() { left } else { right }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// `define` entries with a primitive value.
+    fn lookup_define(&self, path: &str) -> Option<Value> {
+        if !cfg!(feature = "defines") {
+            return None;
+        }
+        match self.config.define.get(path)? {
+            serde_json::Value::String(value) => Some(Value::Str(value.clone())),
+            serde_json::Value::Number(value) => value.as_f64().map(Value::Num),
+            serde_json::Value::Bool(value) => Some(Value::Bool(*value)),
+            serde_json::Value::Null => Some(Value::Null),
+            serde_json::Value::Array(_) | serde_json::Value::Object(_) => None,
+        }
+    }
+
+    /// Inline `env` entries win over the host's variables.
+    fn lookup_env(&self, name: &str) -> Option<&str> {
+        if !cfg!(feature = "defines") {
+            return None;
+        }
+        self.config.env.get(name)
+            .or_else(|| self.host_env.get(name))
+            .map(String::as_str)
+    }
+}
+
+/// `process.env.NAME` or `process.env["NAME"]` → `NAME`.
+fn env_member_name(member: &MemberExpr) -> Option<&str> {
+    let Expr::Member(object) = &*member.obj else {
+        return None;
+    };
+    let is_process_env = matches!(&*object.obj, Expr::Ident(ident) if &*ident.sym == PROCESS_OBJECT)
+        && matches!(&object.prop, MemberProp::Ident(prop) if &*prop.sym == ENV_MEMBER);
+    if !is_process_env {
+        return None;
+    }
+    match &member.prop {
+        MemberProp::Ident(prop) => Some(&prop.sym),
+        MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+            Expr::Lit(Lit::Str(name)) => Some(&name.value),
+            _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+/// `FLAGS.newUI` or `FLAGS["newUI"]` → `"FLAGS.newUI"`.
+fn member_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(member) => {
+            let prop = match &member.prop {
+                MemberProp::Ident(prop) => &*prop.sym,
+                MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                    Expr::Lit(Lit::Str(name)) => &*name.value,
+                    _ => return None,
+                },
+                MemberProp::PrivateName(_) => return None,
+            };
+            Some(format!("{}.{prop}", member_path(&member.obj)?))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,40 @@
+use swc_core::common::Span;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    pub(crate) fn is_error_case(&self, case: &JSXElement) -> bool {
+        self.has_flag(&case.opening.attrs, &self.atoms.on_error_atom)
+    }
+
+    /// `<Switch.Case onError>` children become the `fallback` of the
+    /// configured `errorBoundary` around the lowered Switch:
+    /// `<ErrorBoundary fallback={<>…</>}>{…}</ErrorBoundary>`.
+    pub(crate) fn wrap_error_boundary(&mut self, lowered: JSXElement, fallback: Vec<JSXElementChild>, span: Span) -> JSXElement {
+        let Some(boundary) = &self.config.error_boundary else {
+            self.report_warning(span, "on-error-without-boundary", "`onError` case ignored: set the `errorBoundary` option to the component that catches errors".into());
+            return lowered;
+        };
+        let name = Self::fragment_element_name(boundary, span, self.syntax_context);
+        let fallback = self.branch_expr(fallback, span);
+        let fallback_attr = JSXAttrOrSpread::JSXAttr(JSXAttr {
+            span,
+            name: JSXAttrName::Ident(IdentName::new(self.atoms.fallback_atom.clone(), span)),
+            value: Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                span,
+                expr: JSXExpr::Expr(Box::new(fallback)),
+            })),
+        });
+        let child = match self.lowered_expr(lowered) {
+            Expr::JSXElement(element) => JSXElementChild::JSXElement(element),
+            expr => JSXElementChild::JSXExprContainer(JSXExprContainer { span, expr: JSXExpr::Expr(Box::new(expr)) }),
+        };
+        JSXElement {
+            span,
+            opening: JSXOpeningElement { span, name: name.clone(), attrs: vec![fallback_attr], self_closing: false, type_args: None },
+            children: vec![child],
+            closing: Some(JSXClosingElement { span, name }),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use swc_core::common::Spanned;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Checks a `<Switch value={…} values={[…]}>` against its cases: every
+    /// listed value needs a case matching it with `is` or `in`, unless the
+    /// Switch has an else case. Cases tested with `if` or `when` are opaque
+    /// and cover nothing.
+    pub(crate) fn check_exhaustive_switch(&mut self, element: &JSXElement) {
+        let Some(values) = self.extract_attr_value(&element.opening.attrs, &self.atoms.values_atom) else {
+            return;
+        };
+        let Expr::Array(values) = *values else {
+            return;
+        };
+
+        let mut covered = Vec::new();
+        for child in &element.children {
+            let JSXElementChild::JSXElement(case) = child else {
+                continue;
+            };
+            if self.is_switch_else_alias(case) {
+                return;
+            }
+            if !self.is_switch_case_element(case) || self.is_error_case(case) {
+                continue;
+            }
+            let attrs = &case.opening.attrs;
+            if self.extract_condition_from_attrs(attrs).is_some() {
+                continue;
+            }
+            if self.has_else_attr(attrs) {
+                return;
+            }
+            if let Some(value) = self.extract_attr_value(attrs, &self.atoms.is_atom) {
+                covered.extend(literal_key(&value));
+            } else if self.extract_attr_value(attrs, &self.atoms.when_atom).is_some() {
+                continue;
+            } else if let Some(list) = self.extract_attr_value(attrs, &self.atoms.in_atom) {
+                if let Expr::Array(list) = *list {
+                    covered.extend(list.elems.iter().flatten().filter_map(|elem| literal_key(&elem.expr)));
+                }
+            }
+        }
+
+        for value in values.elems.iter().flatten() {
+            let Some(key) = literal_key(&value.expr) else {
+                continue;
+            };
+            if !covered.contains(&key) {
+                let message = format!("no case of this Switch matches {key} from `{}`; add one or an else case", self.atoms.values_atom);
+                self.report_error(value.expr.span(), "non-exhaustive-switch", message);
+            }
+        }
+    }
+}
+
+/// `"idle"`, `1` or `true` as written in the source, for comparing the
+/// literals of `values` with those of the cases.
+fn literal_key(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(Lit::Str(str)) => Some(format!("{:?}", str.value.to_string())),
+        Expr::Lit(Lit::Num(num)) => Some(num.value.to_string()),
+        Expr::Lit(Lit::Bool(bool)) => Some(bool.value.to_string()),
+        Expr::Paren(paren) => literal_key(&paren.expr),
+        _ => None,
+    }
+}
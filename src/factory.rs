@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use swc_core::atoms::Atom;
+use swc_core::common::{SyntaxContext, DUMMY_SP};
+use swc_core::ecma::ast::*;
+
+use crate::codegen::Codegen;
+use crate::fallback::{parse_config_expr, parse_fallback};
+use crate::{
+    CompatAtoms, Config, PostTransformVisitor, TransformVisitor, WrapperType, BOOLEAN_FUNC, CONDITION_PLACEHOLDER, KEY_ATTR,
+    REACT_FRAGMENT,
+};
+
+/// Tag and attribute atoms of the configured vocabulary.
+#[derive(Clone)]
+pub(crate) struct TagAtoms {
+    pub(crate) condition_atom: Atom,
+    pub(crate) switch_atom: Atom,
+    pub(crate) if_atom: Atom,
+    pub(crate) else_atom: Atom,
+    pub(crate) short_circuit_atom: Atom,
+    pub(crate) value_atom: Atom,
+    pub(crate) is_atom: Atom,
+    pub(crate) in_atom: Atom,
+    pub(crate) when_atom: Atom,
+    pub(crate) case_atom: Atom,
+    pub(crate) for_atom: Atom,
+    pub(crate) each_atom: Atom,
+    pub(crate) key_atom: Atom,
+    pub(crate) show_atom: Atom,
+    pub(crate) fallback_atom: Atom,
+    pub(crate) priority_atom: Atom,
+    pub(crate) else_show_atom: Atom,
+    pub(crate) unless_atom: Atom,
+    pub(crate) with_atom: Atom,
+    pub(crate) feature_atom: Atom,
+    pub(crate) platform_atom: Atom,
+    pub(crate) name_atom: Atom,
+    pub(crate) not_atom: Atom,
+    pub(crate) as_atom: Atom,
+    pub(crate) keep_mounted_atom: Atom,
+    pub(crate) memo_atom: Atom,
+    pub(crate) deps_atom: Atom,
+    pub(crate) lazy_atom: Atom,
+    pub(crate) on_error_atom: Atom,
+    pub(crate) order_atom: Atom,
+    pub(crate) values_atom: Atom,
+    pub(crate) raw_atom: Atom,
+    pub(crate) inline_if_atom: Option<Atom>,
+    pub(crate) inline_else_atom: Option<Atom>,
+    pub(crate) else_alias_atoms: Vec<Atom>,
+    pub(crate) tag_namespace_atoms: Vec<Atom>,
+    pub(crate) compat: Option<CompatAtoms>,
+}
+
+impl TagAtoms {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            condition_atom: config.tags.condition.as_str().into(),
+            switch_atom: config.tags.switch.as_str().into(),
+            if_atom: config.attributes.r#if.as_str().into(),
+            else_atom: config.attributes.r#else.as_str().into(),
+            short_circuit_atom: config.attributes.short_circuit.as_str().into(),
+            value_atom: config.attributes.value.as_str().into(),
+            is_atom: config.attributes.is.as_str().into(),
+            in_atom: config.attributes.r#in.as_str().into(),
+            when_atom: config.attributes.when.as_str().into(),
+            case_atom: config.tags.case.as_str().into(),
+            for_atom: config.tags.r#for.as_str().into(),
+            each_atom: config.attributes.each.as_str().into(),
+            key_atom: KEY_ATTR.into(),
+            show_atom: config.tags.show.as_str().into(),
+            fallback_atom: config.attributes.fallback.as_str().into(),
+            priority_atom: config.attributes.priority.as_str().into(),
+            else_show_atom: config.attributes.else_show.as_str().into(),
+            unless_atom: config.unless_tag.as_str().into(),
+            with_atom: config.tags.with.as_str().into(),
+            feature_atom: config.tags.feature.as_str().into(),
+            platform_atom: config.tags.platform.as_str().into(),
+            name_atom: config.attributes.name.as_str().into(),
+            not_atom: config.attributes.not.as_str().into(),
+            as_atom: config.attributes.r#as.as_str().into(),
+            keep_mounted_atom: config.attributes.keep_mounted.as_str().into(),
+            memo_atom: config.attributes.memo.as_str().into(),
+            deps_atom: config.attributes.deps.as_str().into(),
+            lazy_atom: config.attributes.lazy.as_str().into(),
+            on_error_atom: config.attributes.on_error.as_str().into(),
+            order_atom: config.attributes.order.as_str().into(),
+            values_atom: config.attributes.values.as_str().into(),
+            raw_atom: config.attributes.raw.as_str().into(),
+            inline_if_atom: config.inline_if.as_deref().map(Atom::from),
+            inline_else_atom: config.inline_else.as_deref().map(Atom::from),
+            else_alias_atoms: config.else_aliases.iter().map(|alias| alias.as_str().into()).collect(),
+            tag_namespace_atoms: config.tag_namespaces.iter().map(|namespace| namespace.as_str().into()).collect(),
+            compat: config.compat.map(CompatAtoms::new),
+        }
+    }
+
+    /// The tags a local binding can shadow and `stripImports` can remove.
+    pub(crate) fn control_tags(&self) -> [&Atom; 7] {
+        [&self.condition_atom, &self.switch_atom, &self.for_atom, &self.unless_atom, &self.with_atom, &self.feature_atom, &self.platform_atom]
+    }
+
+    /// The `compat` vocabulary; always `None` without the `compat` feature,
+    /// so its branches compile away.
+    pub(crate) fn compat(&self) -> Option<&CompatAtoms> {
+        self.compat.as_ref().filter(|_| cfg!(feature = "compat"))
+    }
+}
+
+/// Builds per-file [`TransformVisitor`]s from one config. The config is
+/// parsed and its atoms interned once; each visitor only clones handles to
+/// them. The factory is `Send + Sync`, so parallel workers can share it.
+#[derive(Clone)]
+pub struct TransformFactory {
+    config: Arc<Config>,
+    atoms: TagAtoms,
+    fragment_name: JSXElementName,
+    codegen: Arc<dyn Codegen>,
+    condition_placeholder: Atom,
+    fallback: Expr,
+    feature_lookup: Option<Expr>,
+    memo_hook: Option<Expr>,
+}
+
+impl TransformFactory {
+    pub fn new(config: Config) -> Self {
+        let codegen = config.target.codegen();
+        // 非法表达式已在 Config::from_json 报错，这里退回 null
+        let fallback = parse_fallback(&config.fallback)
+            .unwrap_or_else(|_| Expr::Lit(Lit::Null(Null { span: DUMMY_SP })));
+        let feature_lookup = config.feature_lookup.as_deref().and_then(|source| parse_config_expr("featureLookup", source).ok());
+        let memo_hook = parse_config_expr("memoHook", &config.memo_hook).ok();
+        Self {
+            atoms: TagAtoms::new(&config),
+            fragment_name: default_fragment_name(&config, &*codegen),
+            codegen,
+            condition_placeholder: condition_placeholder(&config),
+            fallback,
+            feature_lookup,
+            memo_hook,
+            config: Arc::new(config),
+        }
+    }
+
+    /// Replaces the [`Codegen`] picked from `target`, e.g. with one for a
+    /// runtime the plugin does not ship.
+    pub fn with_codegen(mut self, codegen: Arc<dyn Codegen>) -> Self {
+        self.fragment_name = default_fragment_name(&self.config, &*codegen);
+        self.codegen = codegen;
+        self
+    }
+
+    /// The post pass that unwraps this factory's placeholders.
+    pub fn post_visitor(&self) -> PostTransformVisitor {
+        PostTransformVisitor::new(self.condition_placeholder.clone())
+    }
+
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// A fresh visitor for the next file.
+    pub fn visitor(&self) -> TransformVisitor {
+        let span = DUMMY_SP;
+        let syntax_context = SyntaxContext::empty();
+        TransformVisitor {
+            config: self.config.clone(),
+            current_context: WrapperType::Jsx,
+            boolean_ident: Ident::new(
+                self.config.condition_helper.as_ref().map_or(BOOLEAN_FUNC, |helper| helper.name.as_str()).into(),
+                span,
+                syntax_context,
+            ),
+            fragment_name: self.fragment_name.clone(),
+            codegen: self.codegen.clone(),
+            condition_placeholder_ident: Ident::new(self.condition_placeholder.clone(), span, syntax_context),
+            fallback: self.fallback.clone(),
+            feature_lookup: self.feature_lookup.clone(),
+            memo_hook: self.memo_hook.clone(),
+            syntax_context,
+            atoms: self.atoms.clone(),
+            runtime_components: Vec::new(),
+            reported_runtime_components: Vec::new(),
+            pending_hoists: Vec::new(),
+            pending_temps: Vec::new(),
+            pending_inline_else: None,
+            loop_vars: Vec::new(),
+            hook_scope: false,
+            pending_fn_name: None,
+            names: Default::default(),
+            annotations: None,
+            comments: None,
+            host_env: HashMap::new(),
+            source_map: None,
+            json_diagnostics: Vec::new(),
+            span_mappings: None,
+            imported_tags: Vec::new(),
+            shadowed_tags: Vec::new(),
+            ignored_elements: Vec::new(),
+            found_control_tags: false,
+        }
+    }
+}
+
+/// The configured `fragment`, or the codegen's own while it is left at the
+/// React default.
+fn default_fragment_name(config: &Config, codegen: &dyn Codegen) -> JSXElementName {
+    let fragment = if config.fragment == REACT_FRAGMENT { codegen.default_fragment() } else { &config.fragment };
+    TransformVisitor::fragment_element_name(fragment, DUMMY_SP, SyntaxContext::empty())
+}
+
+/// `__CONDITION_PLACEHOLDER__`, suffixed with a hash of the vocabulary when it
+/// is not the default one, so instances with different vocabularies in one
+/// pipeline never take each other's placeholders for their own.
+fn condition_placeholder(config: &Config) -> Atom {
+    let vocabulary = vocabulary_key(config);
+    if vocabulary == vocabulary_key(&Config::default()) {
+        return CONDITION_PLACEHOLDER.into();
+    }
+    // FNV-1a，与进程和平台无关，关闭 postTransform 时输出也稳定
+    let hash = vocabulary.bytes().fold(0x811c_9dc5_u32, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193));
+    format!("__CONDITION_PLACEHOLDER_{hash:08x}__").into()
+}
+
+/// The settings that decide which elements an instance lowers.
+fn vocabulary_key(config: &Config) -> String {
+    format!(
+        "{:?}",
+        (
+            &config.tags,
+            &config.attributes,
+            &config.unless_tag,
+            &config.else_aliases,
+            &config.tag_namespaces,
+            &config.compat,
+            &config.inline_if,
+            &config.inline_else,
+            config.solid_show,
+        )
+    )
+}
@@ -0,0 +1,52 @@
+use swc_core::common::{BytePos, Span, DUMMY_SP};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::parser::{EsSyntax, Parser, StringInput, Syntax};
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::TransformVisitor;
+
+pub(crate) static NULL_FALLBACK: &str = "null";
+
+/// Parses the `fallback` option, e.g. `"undefined"` or `"<EmptyState/>"`.
+pub(crate) fn parse_fallback(source: &str) -> Result<Expr, String> {
+    parse_config_expr("fallback", source)
+}
+
+/// Parses an expression given as the string value of `option`.
+pub(crate) fn parse_config_expr(option: &str, source: &str) -> Result<Expr, String> {
+    // 包一层括号，`null foo` 这类多余的尾部内容会报错而不是被忽略
+    let wrapped = format!("({source})");
+    let syntax = Syntax::Es(EsSyntax { jsx: true, ..Default::default() });
+    let mut parser = Parser::new(syntax, StringInput::new(&wrapped, BytePos(1), BytePos(1 + wrapped.len() as u32)), None);
+    let expr = parser
+        .parse_expr()
+        .map_err(|err| format!("invalid {option} {source:?}: {}", err.kind().msg()))?;
+    let (Expr::Paren(ParenExpr { mut expr, .. }), true) = (*expr, parser.take_errors().is_empty()) else {
+        return Err(format!("invalid {option} {source:?}"));
+    };
+    // 配置里的源码位置对当前文件没有意义
+    expr.visit_mut_with(&mut DropSpans);
+    Ok(*expr)
+}
+
+struct DropSpans;
+
+impl VisitMut for DropSpans {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = DUMMY_SP;
+    }
+}
+
+impl TransformVisitor {
+    /// What a branch renders when its test fails and it has no fallback of
+    /// its own. Literal fallbacks take the span of the markup they replace.
+    pub(crate) fn fallback_at(&self, span: Span) -> Expr {
+        let mut fallback = self.fallback.clone();
+        match &mut fallback {
+            Expr::Lit(Lit::Null(Null { span: lit_span }) | Lit::Bool(Bool { span: lit_span, .. })) => *lit_span = span,
+            Expr::Ident(ident) => ident.span = span,
+            _ => {}
+        }
+        fallback
+    }
+}
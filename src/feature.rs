@@ -0,0 +1,31 @@
+use swc_core::common::Span;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Test of `<Feature name="x">`: `true`/`false` for flags listed in
+    /// `features`, otherwise a `featureLookup("x")` call. `None` when the
+    /// name is not a string literal or the flag is unknown without a lookup.
+    pub(crate) fn feature_condition(&self, attrs: &[JSXAttrOrSpread], span: Span) -> Option<Box<Expr>> {
+        let name = match *self.extract_attr_value(attrs, &self.atoms.name_atom)? {
+            Expr::Lit(Lit::Str(name)) => name.value,
+            _ => return None,
+        };
+        let negate = attrs.iter().any(|attr| {
+            matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(attr_name), .. }) if attr_name.sym == self.atoms.not_atom)
+        });
+
+        if let Some(enabled) = self.config.features.get(&*name) {
+            return Some(Box::new(Expr::Lit(Lit::Bool(Bool { span, value: *enabled != negate }))));
+        }
+        let lookup = Box::new(Expr::Call(CallExpr {
+            span,
+            ctxt: self.syntax_context,
+            callee: Callee::Expr(Box::new(self.feature_lookup.clone()?)),
+            args: vec![ExprOrSpread { spread: None, expr: Box::new(Expr::Lit(Lit::Str(Str { span, value: name, raw: None }))) }],
+            type_args: None,
+        }));
+        Some(if negate { Self::negate_condition(lookup) } else { lookup })
+    }
+}
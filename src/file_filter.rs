@@ -0,0 +1,42 @@
+use crate::Config;
+
+impl Config {
+    /// Whether `include` and `exclude` let the transform run on `filename`.
+    /// A glob matches the path relative to `cwd`, when the host reports one,
+    /// or the path as given. Files of unknown name are always transformed.
+    pub(crate) fn applies_to(&self, filename: Option<&str>, cwd: Option<&str>) -> bool {
+        let Some(filename) = filename else {
+            return true;
+        };
+        // Windows 路径统一成 `/`，模式只需写一种分隔符
+        let path = filename.replace('\\', "/");
+        let relative = cwd
+            .map(|cwd| cwd.replace('\\', "/"))
+            .and_then(|cwd| path.strip_prefix(cwd.trim_end_matches('/')).and_then(|rest| rest.strip_prefix('/')).map(str::to_string));
+
+        let candidates: Vec<Vec<char>> = relative.iter().chain([&path]).map(|path| path.chars().collect()).collect();
+        let matches = |pattern: &String| {
+            let pattern: Vec<char> = pattern.chars().collect();
+            candidates.iter().any(|path| glob_match(&pattern, path))
+        };
+        (self.include.is_empty() || self.include.iter().any(matches)) && !self.exclude.iter().any(matches)
+    }
+}
+
+/// Matches `path` against a glob: `*` and `?` stay within one path segment,
+/// `**` spans segments, and `**/` also matches no segment at all.
+fn glob_match(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            glob_match(rest, path) || (0..path.len()).any(|i| path[i] == '/' && glob_match(rest, &path[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=path.len()).any(|i| glob_match(rest, &path[i..])),
+        ['*', rest @ ..] => {
+            let segment = path.iter().position(|c| *c == '/').unwrap_or(path.len());
+            (0..=segment).any(|i| glob_match(rest, &path[i..]))
+        }
+        ['?', rest @ ..] => matches!(path, [c, ..] if *c != '/') && glob_match(rest, &path[1..]),
+        [c, rest @ ..] => path.first() == Some(c) && glob_match(rest, &path[1..]),
+    }
+}
@@ -0,0 +1,82 @@
+use swc_core::common::{util::take::Take, Spanned};
+use swc_core::ecma::ast::*;
+
+/// Parenthesizes subexpressions that would regroup when printed, such as a
+/// ternary case test embedded in a generated conditional chain. Parsed
+/// code keeps its own parentheses as `ParenExpr`, so only generated (or
+/// post-processed) nodes are affected.
+pub(crate) fn fix_precedence(expr: &mut Expr) {
+    match expr {
+        Expr::Cond(cond) => {
+            paren_if(&mut cond.test, binds_looser_than_binary);
+            paren_if(&mut cond.cons, Expr::is_seq);
+            paren_if(&mut cond.alt, Expr::is_seq);
+        }
+        Expr::Bin(bin) => {
+            let op = bin.op;
+            paren_if(&mut bin.left, |operand| needs_operand_parens(op, operand, false));
+            paren_if(&mut bin.right, |operand| needs_operand_parens(op, operand, true));
+        }
+        Expr::Unary(unary) => paren_if(&mut unary.arg, |arg| arg.is_bin() || binds_looser_than_binary(arg)),
+        Expr::Await(await_expr) => paren_if(&mut await_expr.arg, |arg| arg.is_bin() || binds_looser_than_binary(arg)),
+        // `(a ? b : c).foo`、`(a ? b : c)()` 去掉括号会改变含义
+        Expr::Member(member) => paren_if(&mut member.obj, needs_callee_parens),
+        Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) => paren_if(callee, needs_callee_parens),
+        Expr::New(new) => paren_if(&mut new.callee, needs_callee_parens),
+        Expr::TaggedTpl(tagged) => paren_if(&mut tagged.tag, needs_callee_parens),
+        Expr::OptChain(chain) => match &mut *chain.base {
+            OptChainBase::Member(member) => paren_if(&mut member.obj, needs_callee_parens),
+            OptChainBase::Call(call) => paren_if(&mut call.callee, needs_callee_parens),
+        },
+        Expr::TsAs(TsAsExpr { expr: inner, .. })
+        | Expr::TsSatisfies(TsSatisfiesExpr { expr: inner, .. })
+        | Expr::TsNonNull(TsNonNullExpr { expr: inner, .. }) => paren_if(inner, needs_callee_parens),
+        _ => {}
+    }
+}
+
+/// Wraps a ternary test that would otherwise merge into the generated
+/// `?:`, e.g. a case `if={urgent ? p > 5 : p > 8}`.
+pub(crate) fn paren_test(test: Box<Expr>) -> Box<Expr> {
+    if binds_looser_than_binary(&test) {
+        let span = test.span();
+        return Box::new(Expr::Paren(ParenExpr { span, expr: test }));
+    }
+    test
+}
+
+fn paren_if(expr: &mut Box<Expr>, needs_parens: impl Fn(&Expr) -> bool) {
+    if needs_parens(expr) {
+        let span = expr.span();
+        let inner = expr.take();
+        **expr = Expr::Paren(ParenExpr { span, expr: inner });
+    }
+}
+
+/// Expressions below every binary operator: `?:`, assignment, `,`, arrows
+/// and `yield`.
+fn binds_looser_than_binary(expr: &Expr) -> bool {
+    matches!(expr, Expr::Cond(_) | Expr::Assign(_) | Expr::Seq(_) | Expr::Arrow(_) | Expr::Yield(_))
+}
+
+/// Operands of `.x`, `()`, `new`, a tagged template or a TS postfix, which
+/// bind tighter than any prefix or binary operator.
+fn needs_callee_parens(expr: &Expr) -> bool {
+    binds_looser_than_binary(expr) || matches!(expr, Expr::Bin(_) | Expr::Unary(_) | Expr::Update(_) | Expr::Await(_))
+}
+
+fn needs_operand_parens(op: BinaryOp, operand: &Expr, is_right: bool) -> bool {
+    let Expr::Bin(inner) = operand else {
+        return binds_looser_than_binary(operand);
+    };
+    // `??` 不能和 `&&` / `||` 直接混用
+    let is_logical = |op: BinaryOp| matches!(op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr);
+    if (op == BinaryOp::NullishCoalescing && is_logical(inner.op)) || (is_logical(op) && inner.op == BinaryOp::NullishCoalescing) {
+        return true;
+    }
+    match op {
+        // `**` 右结合
+        BinaryOp::Exp => inner.op.precedence() < op.precedence() || (!is_right && inner.op == BinaryOp::Exp),
+        _ => inner.op.precedence() < op.precedence() || (is_right && inner.op.precedence() == op.precedence()),
+    }
+}
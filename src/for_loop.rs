@@ -0,0 +1,125 @@
+use swc_core::common::{Span, Spanned};
+use swc_core::ecma::ast::*;
+
+use crate::diagnostics::jsx_name;
+use crate::{TransformVisitor, FOR_INDEX_PARAM, FOR_ITEM_PARAM, MAP_METHOD};
+
+impl TransformVisitor {
+    /// Lowers `<For each={items}>{item => <Row item={item} />}</For>` into
+    /// `items.map(item => <Row item={item} />)`.
+    ///
+    /// When the callback returns a single element without a `key`, the map
+    /// index is injected as its key, adding an index parameter if needed.
+    pub(crate) fn create_for_expr(&mut self, element: &JSXElement) -> Option<Expr> {
+        let each = self.extract_attr_value(&element.opening.attrs, &self.atoms.each_atom)?;
+        let Some(callback) = Self::extract_for_callback(&element.children) else {
+            let message = format!(
+                "`<{}>` expects `{{(item, index) => …}}` as its only child; the tag is left as a runtime component",
+                jsx_name(&element.opening.name),
+            );
+            self.report_warning(element.opening.span, "for-children", message);
+            return None;
+        };
+        let callback = self.inject_for_key(callback, element.span);
+
+        let obj = match *each {
+            Expr::Ident(_) | Expr::Member(_) | Expr::Call(_) | Expr::Array(_) | Expr::Paren(_) => each,
+            _ => Box::new(Expr::Paren(ParenExpr { span: each.span(), expr: each })),
+        };
+
+        Some(Expr::Call(CallExpr {
+            span: element.span,
+            callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                span: element.span,
+                obj,
+                prop: MemberProp::Ident(IdentName::new(MAP_METHOD.into(), element.span)),
+            }))),
+            args: vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(callback),
+            }],
+            type_args: None,
+            ctxt: self.syntax_context,
+        }))
+    }
+
+    /// The only non-whitespace child must be a `{callback}` container.
+    fn extract_for_callback(children: &[JSXElementChild]) -> Option<Expr> {
+        let mut non_whitespace = children.iter().filter(|child| Self::is_non_whitespace_child(child));
+        match (non_whitespace.next(), non_whitespace.next()) {
+            (Some(JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })), None) => {
+                match &**expr {
+                    Expr::Arrow(_) | Expr::Fn(_) | Expr::Ident(_) | Expr::Member(_) => Some((**expr).clone()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn inject_for_key(&self, callback: Expr, span: Span) -> Expr {
+        let Expr::Arrow(mut arrow) = callback else {
+            return callback;
+        };
+        let needs_key = match &*arrow.body {
+            BlockStmtOrExpr::Expr(body) => Self::returned_element(body).is_some_and(|returned| {
+                // 展开属性里可能已经带了 key，不做猜测
+                !returned.opening.attrs.iter().any(|attr| match attr {
+                    JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), .. }) => name.sym == self.atoms.key_atom,
+                    JSXAttrOrSpread::SpreadElement(_) => true,
+                    _ => false,
+                })
+            }),
+            BlockStmtOrExpr::BlockStmt(_) => false,
+        };
+        if !needs_key {
+            return Expr::Arrow(arrow);
+        }
+
+        let index = match arrow.params.get(1) {
+            Some(Pat::Ident(binding)) => binding.id.clone(),
+            Some(_) => return Expr::Arrow(arrow),
+            None => {
+                if arrow.params.is_empty() {
+                    arrow.params.push(Pat::Ident(BindingIdent {
+                        id: Ident::new(self.names.unused(FOR_ITEM_PARAM), span, self.syntax_context),
+                        type_ann: None,
+                    }));
+                }
+                let index = Ident::new(self.names.unused(FOR_INDEX_PARAM), span, self.syntax_context);
+                arrow.params.push(Pat::Ident(BindingIdent { id: index.clone(), type_ann: None }));
+                index
+            }
+        };
+
+        if let BlockStmtOrExpr::Expr(body) = &mut *arrow.body {
+            if let Some(returned) = Self::returned_element_mut(body) {
+                returned.opening.attrs.push(JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    span,
+                    name: JSXAttrName::Ident(IdentName::new(self.atoms.key_atom.clone(), span)),
+                    value: Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                        span,
+                        expr: JSXExpr::Expr(Box::new(Expr::Ident(index))),
+                    })),
+                }));
+            }
+        }
+        Expr::Arrow(arrow)
+    }
+
+    fn returned_element(expr: &Expr) -> Option<&JSXElement> {
+        match expr {
+            Expr::Paren(paren) => Self::returned_element(&paren.expr),
+            Expr::JSXElement(element) => Some(element),
+            _ => None,
+        }
+    }
+
+    fn returned_element_mut(expr: &mut Expr) -> Option<&mut JSXElement> {
+        match expr {
+            Expr::Paren(paren) => Self::returned_element_mut(&mut paren.expr),
+            Expr::JSXElement(element) => Some(element),
+            _ => None,
+        }
+    }
+}
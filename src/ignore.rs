@@ -0,0 +1,40 @@
+use swc_core::common::comments::Comments;
+use swc_core::common::BytePos;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+/// Comment that keeps the next control tag as written.
+pub(crate) static IGNORE_DIRECTIVE: &str = "@condition-ignore";
+
+impl TransformVisitor {
+    /// `<Condition raw …>`, or a tag a `@condition-ignore` comment applies to:
+    /// the element is left as a runtime component, while control tags among
+    /// its children are still lowered. The prescan records the latter by span.
+    pub(crate) fn is_raw_element(&self, element: &JSXElement) -> bool {
+        self.has_flag(&element.opening.attrs, &self.atoms.raw_atom)
+            || (!element.span.is_dummy() && self.ignored_elements.contains(&element.span))
+    }
+}
+
+/// `/* @condition-ignore */ <Condition …>`: the comment leads the element.
+pub(crate) fn has_leading_directive(comments: &dyn Comments, element: &JSXElement) -> bool {
+    !element.span.is_dummy() && has_directive(comments, Some(element.span.lo), None)
+}
+
+/// `{/* @condition-ignore */}` before a sibling; the lexer keeps the comment
+/// after `{`, or before `}` when it sits on a line of its own.
+pub(crate) fn is_directive_child(comments: &dyn Comments, child: &JSXElementChild) -> bool {
+    match child {
+        JSXElementChild::JSXExprContainer(JSXExprContainer { span, expr: JSXExpr::JSXEmptyExpr(empty) }) => {
+            has_directive(comments, Some(empty.span.lo), Some(span.lo + BytePos(1)))
+        }
+        _ => false,
+    }
+}
+
+fn has_directive(comments: &dyn Comments, leading: Option<BytePos>, trailing: Option<BytePos>) -> bool {
+    let leading = leading.and_then(|pos| comments.get_leading(pos)).unwrap_or_default();
+    let trailing = trailing.and_then(|pos| comments.get_trailing(pos)).unwrap_or_default();
+    leading.iter().chain(&trailing).any(|comment| comment.text.trim() == IGNORE_DIRECTIVE)
+}
@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+
+use swc_core::atoms::Atom;
+use swc_core::common::util::take::Take;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::VisitMut;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Index of the `inlineIf` attribute among `attrs`, if configured.
+    pub(crate) fn inline_if_attr(&self, attrs: &[JSXAttrOrSpread]) -> Option<usize> {
+        find_attr(attrs, self.atoms.inline_if_atom.as_ref()?)
+    }
+
+    /// `<div render-if={cond}>…</div>` → `cond ? <div>…</div> : null`, or the
+    /// paired `inlineElse` sibling instead of `null`.
+    pub(crate) fn lower_inline_if(&mut self, element: &mut JSXElement) -> Option<JSXElement> {
+        let index = self.inline_if_attr(&element.opening.attrs)?;
+        let atom = self.atoms.inline_if_atom.clone()?;
+        let condition = self.extract_condition_attr(&element.opening.attrs, &atom)?;
+        element.opening.attrs.remove(index);
+
+        // 元素本身（可能也是控制标签）先按 JSX 上下文处理
+        let span = element.span;
+        let else_element = self.pending_inline_else.take();
+        self.with_jsx_context(|visitor| visitor.visit_mut_jsx_element(element));
+        let fallback = else_element.map(|mut else_element| {
+            self.with_jsx_context(|visitor| visitor.visit_mut_jsx_element(&mut else_element));
+            Box::new(Expr::JSXElement(Box::new(else_element)))
+        });
+        let rendered = Expr::JSXElement(Box::new(element.take()));
+        let conditional = self.create_conditional_of(condition, rendered, fallback, span);
+        Some(self.wrap_expr_for_context(conditional, span))
+    }
+
+    /// Removes the `inlineElse` element following `element` from `rest`,
+    /// skipping whitespace between the two.
+    pub(crate) fn take_inline_else(&self, element: &JSXElement, rest: &mut VecDeque<JSXElementChild>) -> Option<JSXElement> {
+        let else_atom = self.atoms.inline_else_atom.as_ref()?;
+        let if_atom = self.atoms.inline_if_atom.as_ref()?;
+        if self.keeps_runtime_tags() || self.extract_condition_attr(&element.opening.attrs, if_atom).is_none() {
+            return None;
+        }
+        let position = rest.iter().position(|child| !is_whitespace(child))?;
+        let JSXElementChild::JSXElement(sibling) = &rest[position] else {
+            return None;
+        };
+        let index = find_attr(&sibling.opening.attrs, else_atom)?;
+        let Some(JSXElementChild::JSXElement(mut sibling)) = rest.remove(position) else {
+            unreachable!();
+        };
+        sibling.opening.attrs.remove(index);
+        Some(*sibling)
+    }
+}
+
+fn find_attr(attrs: &[JSXAttrOrSpread], atom: &Atom) -> Option<usize> {
+    attrs.iter().position(|attr| {
+        matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), .. }) if name.sym == *atom)
+    })
+}
+
+fn is_whitespace(child: &JSXElementChild) -> bool {
+    matches!(child, JSXElementChild::JSXText(text) if text.value.trim().is_empty())
+}
@@ -0,0 +1,57 @@
+use swc_core::ecma::ast::*;
+
+use crate::{fixer, TransformVisitor};
+
+static STYLE_ATTR: &str = "style";
+
+impl TransformVisitor {
+    pub(crate) fn is_keep_mounted(&self, attrs: &[JSXAttrOrSpread]) -> bool {
+        attrs.iter().any(|attr| {
+            matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), .. }) if name.sym == self.atoms.keep_mounted_atom)
+        })
+    }
+
+    /// `<Condition if={x} keepMounted>` always renders its children and
+    /// hides them while `x` is falsy:
+    /// `<div style={{ display: x ? undefined : "none" }}>…</div>`.
+    pub(crate) fn keep_mounted_element(&self, element: &mut JSXElement, condition: Box<Expr>) -> JSXElement {
+        let tag = self.attr_str(&element.opening.attrs, &self.atoms.as_atom)
+            .unwrap_or_else(|| self.config.keep_mounted_element.clone());
+        let mut wrapper = self.take_into_element(element, &tag);
+        let span = wrapper.span;
+
+        let display = Expr::Cond(CondExpr {
+            span,
+            test: fixer::paren_test(condition),
+            cons: Box::new(Expr::Ident(Ident { span, sym: "undefined".into(), ..self.boolean_ident.clone() })),
+            alt: Box::new(Expr::Lit(Lit::Str("none".into()))),
+        });
+        let mut props = vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Ident(IdentName::new("display".into(), span)),
+            value: Box::new(display),
+        })))];
+
+        // 已有的 style 对象展开在前，display 覆盖它
+        let style = wrapper.opening.attrs.iter().position(|attr| matches!(attr,
+            JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), value: Some(JSXAttrValue::JSXExprContainer(_)), .. })
+                if &*name.sym == STYLE_ATTR));
+        if let Some(index) = style {
+            if let JSXAttrOrSpread::JSXAttr(JSXAttr { value: Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })), .. }) = wrapper.opening.attrs.remove(index) {
+                let existing = match *expr {
+                    Expr::Object(object) => object.props,
+                    expr => vec![PropOrSpread::Spread(SpreadElement { dot3_token: span, expr: Box::new(expr) })],
+                };
+                props.splice(0..0, existing);
+            }
+        }
+        wrapper.opening.attrs.push(JSXAttrOrSpread::JSXAttr(JSXAttr {
+            span,
+            name: JSXAttrName::Ident(IdentName::new(STYLE_ATTR.into(), span)),
+            value: Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                span,
+                expr: JSXExpr::Expr(Box::new(Expr::Object(ObjectLit { span, props }))),
+            })),
+        }));
+        wrapper
+    }
+}
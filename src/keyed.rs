@@ -0,0 +1,131 @@
+use swc_core::common::{EqIgnoreSpan, Span};
+use swc_core::ecma::ast::*;
+
+use crate::{TransformVisitor, FRAGMENT_SHORTHAND_PLACEHOLDER, REACT_FRAGMENT};
+
+impl TransformVisitor {
+    /// The `key` of a control tag, e.g. `<Switch key={user.id}>` in a list.
+    pub(crate) fn key_attr(&self, element: &JSXElement) -> Option<JSXAttrOrSpread> {
+        element.opening.attrs.iter().find(|attr| {
+            matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), .. }) if name.sym == self.atoms.key_atom)
+        }).cloned()
+    }
+
+    /// Puts `key` on the element that replaces a control tag, so React can
+    /// still reconcile it in a list. Return and assignment results get a
+    /// keyed fragment around their expression; an element that already has
+    /// a key of its own is wrapped in one.
+    pub(crate) fn apply_key(&self, lowered: &mut JSXElement, key: JSXAttrOrSpread) {
+        let span = lowered.span;
+        if let Some(inner) = self.placeholder_element(lowered) {
+            *lowered = inner;
+        }
+        if self.key_attr(lowered).is_some() {
+            let name = self.keyed_fragment_name(span);
+            let holder = JSXElement {
+                span,
+                opening: JSXOpeningElement { span, name: name.clone(), attrs: vec![], self_closing: false, type_args: None },
+                children: vec![],
+                closing: Some(JSXClosingElement { span, name }),
+            };
+            let inner = std::mem::replace(lowered, holder);
+            lowered.children.push(JSXElementChild::JSXElement(Box::new(inner)));
+        } else if self.is_placeholder(lowered) || is_shorthand(lowered) {
+            let name = self.keyed_fragment_name(span);
+            lowered.opening.name = name.clone();
+            if let Some(closing) = &mut lowered.closing {
+                closing.name = name;
+            }
+        }
+        lowered.opening.attrs.push(key);
+    }
+
+    /// `<>` 不能带 key，改用 `React.Fragment`
+    fn keyed_fragment_name(&self, span: Span) -> JSXElementName {
+        match &self.fragment_name {
+            JSXElementName::Ident(ident) if &*ident.sym == FRAGMENT_SHORTHAND_PLACEHOLDER => {
+                Self::fragment_element_name(REACT_FRAGMENT, span, self.syntax_context)
+            }
+            _ => self.fragment_name_at(span),
+        }
+    }
+}
+
+fn is_shorthand(element: &JSXElement) -> bool {
+    matches!(&element.opening.name, JSXElementName::Ident(ident) if &*ident.sym == FRAGMENT_SHORTHAND_PLACEHOLDER)
+}
+
+impl TransformVisitor {
+    /// The element inside `<__CONDITION_PLACEHOLDER__>{<Row/>}</…>`, which can
+    /// carry the key itself.
+    fn placeholder_element(&self, element: &JSXElement) -> Option<JSXElement> {
+        if !self.is_placeholder(element) {
+            return None;
+        }
+        match element.children.as_slice() {
+            [JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })] => match &**expr {
+                Expr::JSXElement(inner) => Some((**inner).clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl TransformVisitor {
+    /// The key of a Switch case: its own `key`, or `case-<index>` with
+    /// `caseKeys` when the cases render in parallel.
+    pub(crate) fn case_key(&self, case: &JSXElement, index: usize, short_circuit: bool) -> Option<JSXAttrOrSpread> {
+        self.key_attr(case).or_else(|| {
+            (self.config.case_keys && !short_circuit).then(|| {
+                let span = case.span;
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    span,
+                    name: JSXAttrName::Ident(IdentName::new(self.atoms.key_atom.clone(), span)),
+                    value: Some(JSXAttrValue::Lit(Lit::Str(format!("case-{index}").into()))),
+                })
+            })
+        })
+    }
+
+    /// Holds a case's children in a keyed fragment until its branch is
+    /// built, so the key survives folding and reordering of the cases.
+    pub(crate) fn keyed_case_children(&self, children: Vec<JSXElementChild>, key: JSXAttrOrSpread, span: Span) -> Vec<JSXElementChild> {
+        vec![JSXElementChild::JSXElement(Box::new(self.keyed_fragment(children, key, span)))]
+    }
+
+    fn keyed_fragment(&self, children: Vec<JSXElementChild>, key: JSXAttrOrSpread, span: Span) -> JSXElement {
+        let name = self.keyed_fragment_name(span);
+        JSXElement {
+            span,
+            opening: JSXOpeningElement { span, name: name.clone(), attrs: vec![key], self_closing: false, type_args: None },
+            children,
+            closing: Some(JSXClosingElement { span, name }),
+        }
+    }
+
+    /// The key and children of a branch made by `keyed_case_children`.
+    pub(crate) fn take_keyed_case(&self, children: &[JSXElementChild]) -> Option<(JSXAttrOrSpread, Vec<JSXElementChild>)> {
+        let mut non_whitespace = children.iter().filter(|child| Self::is_non_whitespace_child(child));
+        let (Some(JSXElementChild::JSXElement(holder)), None) = (non_whitespace.next(), non_whitespace.next()) else {
+            return None;
+        };
+        let [key] = holder.opening.attrs.as_slice() else {
+            return None;
+        };
+        let is_holder = holder.opening.name.eq_ignore_span(&self.keyed_fragment_name(holder.span)) && self.key_attr(holder).is_some();
+        is_holder.then(|| (key.clone(), holder.children.clone()))
+    }
+
+    /// The branch for `children`, with `key` on the element it renders.
+    pub(crate) fn keyed_branch_expr(&self, children: Vec<JSXElementChild>, key: JSXAttrOrSpread, span: Span) -> Expr {
+        match self.branch_expr(children, span) {
+            Expr::JSXElement(mut element) => {
+                self.apply_key(&mut element, key);
+                Expr::JSXElement(element)
+            }
+            Expr::JSXFragment(fragment) => Expr::JSXElement(Box::new(self.keyed_fragment(fragment.children, key, fragment.span))),
+            expr => expr,
+        }
+    }
+}
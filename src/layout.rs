@@ -0,0 +1,151 @@
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+static INDENT: &str = "  ";
+
+impl TransformVisitor {
+    /// Breaks a generated ternary chain over several lines when its estimated
+    /// width exceeds `lineWidth`: JSX branches are parenthesized and their
+    /// children put on indented lines.
+    ///
+    /// Only whitespace containing a newline is inserted, which JSX discards, so
+    /// the rendered output is unchanged.
+    pub(crate) fn layout_conditional(&self, expr: Expr) -> Expr {
+        match self.config.line_width {
+            Some(width) if estimate_width(&expr) > width => break_cond_chain(expr, 1),
+            _ => expr,
+        }
+    }
+}
+
+fn break_cond_chain(expr: Expr, depth: usize) -> Expr {
+    match expr {
+        Expr::Cond(mut cond) => {
+            cond.cons = Box::new(break_branch(*cond.cons, depth));
+            cond.alt = Box::new(match *cond.alt {
+                alt @ Expr::Cond(_) => break_cond_chain(alt, depth),
+                alt => break_branch(alt, depth),
+            });
+            Expr::Cond(cond)
+        }
+        expr => expr,
+    }
+}
+
+fn break_branch(expr: Expr, depth: usize) -> Expr {
+    let expr = match expr {
+        Expr::JSXFragment(mut fragment) => {
+            fragment.children = indent_children(fragment.children, depth);
+            Expr::JSXFragment(fragment)
+        }
+        Expr::JSXElement(mut element) if element.closing.is_some() => {
+            element.children = indent_children(element.children, depth);
+            Expr::JSXElement(element)
+        }
+        expr => return expr,
+    };
+    Expr::Paren(ParenExpr {
+        span: DUMMY_SP,
+        expr: Box::new(expr),
+    })
+}
+
+/// Puts every child on its own line. Children containing meaningful text are
+/// left alone, since JSX trims whitespace around line breaks inside text.
+fn indent_children(children: Vec<JSXElementChild>, depth: usize) -> Vec<JSXElementChild> {
+    let has_text = children.iter().any(|child| matches!(child,
+        JSXElementChild::JSXText(text) if !text.value.trim().is_empty() || !text.value.contains('\n')));
+    if has_text || children.is_empty() {
+        return children;
+    }
+
+    let child_indent = format!("\n{}", INDENT.repeat(depth + 1));
+    let closing_indent = format!("\n{}", INDENT.repeat(depth));
+    let mut result = Vec::with_capacity(children.len() * 2 + 1);
+    for child in children.into_iter().filter(|child| !matches!(child, JSXElementChild::JSXText(_))) {
+        result.push(newline_text(&child_indent));
+        result.push(child);
+    }
+    result.push(newline_text(&closing_indent));
+    result
+}
+
+fn newline_text(value: &str) -> JSXElementChild {
+    JSXElementChild::JSXText(JSXText {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: value.into(),
+    })
+}
+
+/// Rough single-line width of `expr` as the code generator would print it.
+pub(crate) fn estimate_width(expr: &Expr) -> usize {
+    match expr {
+        Expr::Ident(ident) => ident.sym.len(),
+        Expr::This(_) => 4,
+        Expr::Lit(Lit::Str(str)) => str.value.len() + 2,
+        Expr::Lit(Lit::Num(num)) => num.raw.as_ref().map_or(4, |raw| raw.len()),
+        Expr::Lit(Lit::Bool(value)) => if value.value { 4 } else { 5 },
+        Expr::Lit(Lit::Null(_)) => 4,
+        Expr::Member(member) => estimate_width(&member.obj) + 1 + match &member.prop {
+            MemberProp::Ident(prop) => prop.sym.len(),
+            MemberProp::PrivateName(name) => name.name.len() + 1,
+            MemberProp::Computed(computed) => estimate_width(&computed.expr) + 2,
+        },
+        Expr::Call(call) => {
+            let callee = match &call.callee {
+                Callee::Expr(callee) => estimate_width(callee),
+                _ => 6,
+            };
+            callee + 2 + call.args.iter().map(|arg| estimate_width(&arg.expr) + 2).sum::<usize>()
+        }
+        Expr::Bin(bin) => estimate_width(&bin.left) + estimate_width(&bin.right) + bin.op.as_str().len() + 2,
+        Expr::Unary(unary) => estimate_width(&unary.arg) + 1,
+        Expr::Cond(cond) => estimate_width(&cond.test) + estimate_width(&cond.cons) + estimate_width(&cond.alt) + 6,
+        Expr::Paren(paren) => estimate_width(&paren.expr) + 2,
+        Expr::JSXElement(element) => estimate_element_width(element),
+        Expr::JSXFragment(fragment) => 5 + estimate_children_width(&fragment.children),
+        _ => 10,
+    }
+}
+
+fn estimate_element_width(element: &JSXElement) -> usize {
+    let name = match &element.opening.name {
+        JSXElementName::Ident(ident) => ident.sym.len(),
+        JSXElementName::JSXMemberExpr(member) => member.prop.sym.len() + 8,
+        JSXElementName::JSXNamespacedName(name) => name.ns.sym.len() + name.name.sym.len() + 1,
+    };
+    let attrs: usize = element.opening.attrs.iter()
+        .map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(attr) => 1 + match &attr.name {
+                JSXAttrName::Ident(name) => name.sym.len(),
+                JSXAttrName::JSXNamespacedName(name) => name.ns.sym.len() + name.name.sym.len() + 1,
+            } + match &attr.value {
+                None => 0,
+                Some(JSXAttrValue::Lit(Lit::Str(str))) => str.value.len() + 3,
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })) => estimate_width(expr) + 3,
+                Some(_) => 10,
+            },
+            JSXAttrOrSpread::SpreadElement(spread) => estimate_width(&spread.expr) + 6,
+        })
+        .sum();
+    match element.closing {
+        Some(_) => name * 2 + 5 + attrs + estimate_children_width(&element.children),
+        None => name + 3 + attrs,
+    }
+}
+
+fn estimate_children_width(children: &[JSXElementChild]) -> usize {
+    children.iter()
+        .map(|child| match child {
+            JSXElementChild::JSXText(text) => text.value.trim().len(),
+            JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. }) => estimate_width(expr) + 2,
+            JSXElementChild::JSXExprContainer(_) => 2,
+            JSXElementChild::JSXSpreadChild(spread) => estimate_width(&spread.expr) + 5,
+            JSXElementChild::JSXElement(element) => estimate_element_width(element),
+            JSXElementChild::JSXFragment(fragment) => 5 + estimate_children_width(&fragment.children),
+        })
+        .sum()
+}
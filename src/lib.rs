@@ -1,26 +1,451 @@
 use swc_core::ecma::{
     ast::*,
-    visit::{Fold, FoldWith},
+    visit::{VisitMut, VisitMutWith},
+};
+use swc_core::common::{
+    comments::Comments,
+    errors::{SourceMapperDyn, HANDLER},
+    sync::Lrc,
+    util::take::Take,
+    EqIgnoreSpan, Mark, Span, Spanned, SyntaxContext,
 };
-use swc_core::common::SyntaxContext;
 use swc_core::atoms::Atom;
-use swc_core::plugin::{plugin_transform, proxies::TransformPluginProgramMetadata};
+use swc_core::plugin::{
+    metadata::TransformPluginMetadataContextKind, plugin_transform, proxies::TransformPluginProgramMetadata,
+};
 use serde::Deserialize;
-// removed Arc usage after switching to by-value caching of frequently used nodes
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+#[cfg(feature = "cli")]
+pub use analysis::{analyze, ControlSite, ControlSiteKind, SwitchMode};
+#[cfg(feature = "cli")]
+pub use audit::{audit, AuditReport, FileAudit};
+#[cfg(feature = "cli")]
+pub use batch::{transform_batch, BatchReport};
+pub use branch_wrapper::BranchWrapper;
+pub use diagnostics::DiagnosticLevel;
+pub use codegen::{Codegen, Preact, ReactAutomatic, ReactClassic};
+pub use factory::TransformFactory;
+#[cfg(feature = "cli")]
+pub use pass::{condition_pass, recommended_chain, ConditionPass};
+use annotate::BranchAnnotations;
+use diagnostics::{JsonDiagnostic, Severity};
+use factory::TagAtoms;
+use loop_capture::loop_var_names;
+use names::NameAllocator;
+use prescan::FileScan;
+use fallback::NULL_FALLBACK;
+pub use span_map::SpanMapping;
+pub use strip_imports::StripImportsVisitor;
+pub use condition_helper::ConditionHelper;
+pub use preset::Preset;
+pub use target::Target;
+pub use vocabulary::{AttributeNames, TagNames};
+
+#[cfg(feature = "cli")]
+mod analysis;
+mod annotate;
+mod as_element;
+#[cfg(feature = "cli")]
+mod audit;
+#[cfg(feature = "cli")]
+mod batch;
+mod bindings;
+mod branch_wrapper;
+mod codegen;
+mod case_order;
+mod condition_helper;
+mod diagnostics;
+mod else_priority;
+mod env;
+mod error_case;
+mod exhaustive;
+mod factory;
+mod fallback;
+mod feature;
+mod file_filter;
+mod fixer;
+mod ignore;
+mod for_loop;
+mod inline_if;
+mod keep_mounted;
+mod keyed;
+mod layout;
+mod loop_capture;
+mod memo;
+mod names;
+mod nullish;
+mod nested;
+#[cfg(feature = "cli")]
+mod pass;
+mod platform;
+mod pragma;
+mod preset;
+mod prescan;
+mod render_prop;
+mod span_map;
+mod static_fold;
+mod strict_attributes;
+mod strip_imports;
+mod suspend;
+mod target;
+mod verify;
+mod vocabulary;
+mod with_scope;
 
 static CONDITION_TAG: &str = "Condition";
 static SWITCH_TAG: &str = "Switch";
+static CASE_MEMBER: &str = "Case";
+static DEFAULT_ALIAS: &str = "Default";
+static ELSE_ALIAS: &str = "Else";
 static IF_ATTR: &str = "if";
 static ELSE_ATTR: &str = "else";
 static SHORT_CIRCUIT_ATTR: &str = "shortCircuit";
-static BOOLEAN_FUNC: &str = "Boolean";
-static REACT_FRAGMENT: &str = "React.Fragment";
-static CONDITION_PLACEHOLDER: &str = "__CONDITION_PLACEHOLDER__";
-static SWITCH_PLACEHOLDER: &str = "__SWITCH_PLACEHOLDER__";
+static VALUE_ATTR: &str = "value";
+static FOR_TAG: &str = "For";
+static EACH_ATTR: &str = "each";
+pub(crate) static KEY_ATTR: &str = "key";
+static MAP_METHOD: &str = "map";
+static FOR_ITEM_PARAM: &str = "_";
+static FOR_INDEX_PARAM: &str = "__index";
+static SHOW_TAG: &str = "Show";
+static UNLESS_TAG: &str = "Unless";
+static WITH_TAG: &str = "With";
+static FEATURE_TAG: &str = "Feature";
+static PLATFORM_TAG: &str = "Platform";
+static NAME_ATTR: &str = "name";
+static NOT_ATTR: &str = "not";
+static FALLBACK_ATTR: &str = "fallback";
+static PRIORITY_ATTR: &str = "priority";
+static ELSE_SHOW_ATTR: &str = "elseShow";
+static AS_ATTR: &str = "as";
+static KEEP_MOUNTED_ATTR: &str = "keepMounted";
+static KEEP_MOUNTED_ELEMENT: &str = "div";
+static MEMO_ATTR: &str = "memo";
+static DEPS_ATTR: &str = "deps";
+static LAZY_ATTR: &str = "lazy";
+static ON_ERROR_ATTR: &str = "onError";
+static ORDER_ATTR: &str = "order";
+static VALUES_ATTR: &str = "values";
+static RAW_ATTR: &str = "raw";
+static MEMO_HOOK: &str = "React.useMemo";
+static IS_ATTR: &str = "is";
+static IN_ATTR: &str = "in";
+static WHEN_ATTR: &str = "when";
+static INCLUDES_METHOD: &str = "includes";
+pub(crate) static BOOLEAN_FUNC: &str = "Boolean";
+pub(crate) static REACT_FRAGMENT: &str = "React.Fragment";
+// `fragment: "auto"` without a React import emits `<>…</>`; the post pass swaps this marker for a real fragment
+pub(crate) static FRAGMENT_SHORTHAND: &str = "auto";
+pub(crate) static FRAGMENT_SHORTHAND_PLACEHOLDER: &str = "__FRAGMENT_SHORTHAND__";
+static REACT_NAMESPACE: &str = "React";
+pub(crate) static REACT_MODULE: &str = "react";
+pub(crate) static FRAGMENT_EXPORT: &str = "Fragment";
+static USE_HOOK: &str = "use";
+pub(crate) static CONDITION_PLACEHOLDER: &str = "__CONDITION_PLACEHOLDER__";
+pub(crate) static SWITCH_PLACEHOLDER: &str = "__SWITCH_PLACEHOLDER__";
+static SWITCH_VALUE_TEMP: &str = "__switchValue";
+static CASE_CONDITION_TEMP: &str = "__case";
+static NODE_ENV: &str = "NODE_ENV";
+static BRANCH_SHARED_PREFIX: &str = "__branchShared";
+// Subtrees smaller than this (elements plus non-whitespace text) aren't worth a hoisted const
+const SHARED_BRANCH_MIN_NODES: usize = 3;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    /// Merge a logical-AND guard in front of a `<Condition>` into its test,
+    /// so `cond && <Condition if={x}>` lowers to `cond && x ? <>…</> : null`.
+    pub merge_guards: bool,
+    /// Fuse a `Switch.Case` whose only child is a `<Condition>` into a single
+    /// test (`case && inner`), where doing so cannot change which branch renders.
+    pub fuse_case_conditions: bool,
+    /// Evaluate each parallel Switch case condition once, binding it to a
+    /// temporary reused by the else test, instead of repeating it there.
+    pub hoist_case_conditions: bool,
+    /// Lower control tags nested inside a lowered tag's branches too, instead
+    /// of leaving them to the runtime components.
+    pub transform_nested: bool,
+    /// Give each parallel Switch case without a `key` an index-based one
+    /// (`key="case-0"`) on the element it renders.
+    pub case_keys: bool,
+    /// Toggles for the individual stages of the pipeline.
+    pub stages: StagesConfig,
+    /// `Switch.<Name>` members treated like `<Switch.Case else>`.
+    pub else_aliases: Vec<String>,
+    /// Additionally recognize the vocabulary of another control-flow plugin.
+    pub compat: Option<Compat>,
+    /// Hoist static JSX subtrees repeated across Switch branches into
+    /// `const __branchSharedN = …` declarations referenced from each branch.
+    pub hoist_shared_branches: bool,
+    /// Treat every Switch as `lazy`: no branch markup is built before its
+    /// case matches.
+    pub lazy_branches: bool,
+    /// Recognize Solid-style `<Show when={cond} fallback={…}>` as a Condition
+    /// with an else branch.
+    pub solid_show: bool,
+    /// Break generated ternaries over several lines once their estimated
+    /// single-line width exceeds this many characters.
+    pub line_width: Option<usize>,
+    /// Tag of the negated Condition, `<Unless if={x}>` → `!x ? <>…</> : null`.
+    pub unless_tag: String,
+    /// Prefix each generated branch with a `/* condition-switch:branch(file:line) */`
+    /// comment for bundle analyzers.
+    pub annotate_branches: bool,
+    /// The file comes from a macro or code generator whose spans are zero or
+    /// shared between nodes: branch annotations get their own comment
+    /// positions and are numbered instead of located, and diagnostics are
+    /// reported without a location.
+    pub synthetic_spans: bool,
+    /// Module specifiers whose control-component imports are removed once the
+    /// transform leaves them unreferenced.
+    pub strip_imports: Vec<String>,
+    /// Report attributes that Condition, Unless, Show, Feature, Switch and
+    /// its cases do not read, instead of silently dropping them.
+    pub strict_attributes: bool,
+    /// Fail the build when a control tag or placeholder is still in the
+    /// output after the transform and postTransform stages.
+    pub verify_output: bool,
+    /// When non-empty, only tags imported from one of these packages are
+    /// lowered; identically-named local components are left untouched.
+    pub import_sources: Vec<String>,
+    /// Globs of the files to transform, matched against the filename relative
+    /// to the project directory; empty means every file.
+    pub include: Vec<String>,
+    /// Globs of files left as written, even when `include` matches them.
+    pub exclude: Vec<String>,
+    /// Names of the control tags.
+    pub tags: TagNames,
+    /// Names of the attributes read from the control tags.
+    pub attributes: AttributeNames,
+    /// JSON file with shared `tags`, `attributes`, `elseAliases`, `unlessTag`
+    /// and `compat` settings, merged under the inline config.
+    pub vocabulary_file: Option<String>,
+    /// Namespaces the control tags are re-exported under, so `<Flow.Condition>`
+    /// and `<Flow.Switch>` are lowered like `<Condition>` and `<Switch>`.
+    pub tag_namespaces: Vec<String>,
+    /// File that diagnostics are appended to as JSON Lines with LSP-style
+    /// ranges, or `-` for stdout.
+    pub diagnostics_json: Option<String>,
+    /// Severity per diagnostic code, e.g. `{ "missing-condition": "error" }`;
+    /// `off` silences a rule.
+    pub diagnostics: HashMap<String, DiagnosticLevel>,
+    /// Wrapper emitted around lowered markup in JSX: a (dotted) component
+    /// name such as `"React.Fragment"` or `"Fragment"`, or `"auto"` to pick
+    /// one from the file's `react` imports, falling back to `<>…</>`.
+    pub fragment: String,
+    /// JSX runtime to emit for; `preact` defaults the wrapper to `Fragment`
+    /// and makes `fragment: "auto"` read `preact` imports.
+    pub target: Target,
+    /// Import the fragment from the target's module when the output uses a
+    /// bare name such as `Fragment` that the file does not import yet.
+    pub import_fragment: bool,
+    /// Emit lowered tags in JSX children as bare `{…}` expression containers
+    /// instead of holding them in a fragment.
+    pub expression_containers: bool,
+    /// Values for `process.env.NAME` in control conditions, taking precedence
+    /// over the variables the host exposes.
+    pub env: HashMap<String, String>,
+    /// Fold conditions over known `process.env` variables at build time.
+    /// Turn off when env is read dynamically at runtime.
+    pub fold_env: bool,
+    /// Compile-time constants for control conditions, keyed by identifier or
+    /// dotted member path, e.g. `{ "FLAGS.newUI": true }`.
+    pub define: HashMap<String, serde_json::Value>,
+    /// Function called instead of `Boolean` to coerce conditions, imported
+    /// from its module when the output uses it.
+    pub condition_helper: Option<ConditionHelper>,
+    /// How conditions are coerced where the output needs a boolean test.
+    pub boolean_strategy: BooleanStrategy,
+    /// Expression a `<Condition>` without fallback lowers to.
+    pub output: OutputMode,
+    /// `jest` leaves control tags to the runtime shim and only folds their
+    /// `process.env` conditions, so test builds share the production config.
+    pub preset: Option<Preset>,
+    /// Expression rendered when a branch's test fails: `"null"`,
+    /// `"undefined"`, `"false"` or any expression such as `"<EmptyState/>"`.
+    pub fallback: String,
+    /// Attribute that renders any element conditionally, e.g. `"render-if"`
+    /// for `<div render-if={cond}>`. Off by default.
+    pub inline_if: Option<String>,
+    /// Attribute marking the `inlineIf` element's else branch on the next
+    /// sibling, e.g. `"render-else"`.
+    pub inline_else: Option<String>,
+    /// Element wrapping branches with several children, for JSX runtimes
+    /// without fragments.
+    pub branch_wrapper: Option<BranchWrapper>,
+    /// Flags for `<Feature name="…">`, resolved at build time.
+    pub features: HashMap<String, bool>,
+    /// Function called with the name of a flag missing from `features`,
+    /// e.g. `"flags.isEnabled"`. Such `<Feature>` tags are left as written
+    /// when unset.
+    pub feature_lookup: Option<String>,
+    /// Target of this build for `<Platform>`, e.g. `"web"` or `"native"`.
+    pub platform: Option<String>,
+    /// Element that hides the children of a `keepMounted` tag.
+    pub keep_mounted_element: String,
+    /// Hook called for `<Switch memo deps={…}>`, e.g. `"useMemo"` with a
+    /// named import.
+    pub memo_hook: String,
+    /// Component rendered around a Switch with a `<Switch.Case onError>`,
+    /// which becomes its `fallback`, e.g. `"ErrorBoundary"`.
+    pub error_boundary: Option<String>,
+}
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Compat {
+    /// `<If condition>`, `<Choose>`, `<When condition>` and `<Otherwise>` from
+    /// babel-plugin-jsx-control-statements.
+    #[serde(rename = "jsx-control-statements")]
+    JsxControlStatements,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum BooleanStrategy {
+    /// `Boolean(x)`, or the `conditionHelper` when one is configured.
+    #[default]
+    Boolean,
+    /// `!!x`, shorter once minified.
+    #[serde(rename = "bang-bang")]
+    BangBang,
+    /// `x` as written.
+    #[serde(rename = "none")]
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Config {}
+pub enum OutputMode {
+    /// `x ? <>…</> : null`
+    #[default]
+    Ternary,
+    /// `x && <>…</>`
+    LogicalAnd,
+}
+
+/// Tag and attribute atoms of a compat vocabulary.
+#[derive(Clone)]
+struct CompatAtoms {
+    if_tag: Atom,
+    choose_tag: Atom,
+    when_tag: Atom,
+    otherwise_tag: Atom,
+    condition_attr: Atom,
+}
+
+impl CompatAtoms {
+    fn new(compat: Compat) -> Self {
+        match compat {
+            Compat::JsxControlStatements => Self {
+                if_tag: "If".into(),
+                choose_tag: "Choose".into(),
+                when_tag: "When".into(),
+                otherwise_tag: "Otherwise".into(),
+                condition_attr: "condition".into(),
+            },
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            merge_guards: false,
+            fuse_case_conditions: false,
+            hoist_case_conditions: false,
+            transform_nested: false,
+            case_keys: false,
+            stages: StagesConfig::default(),
+            else_aliases: vec![DEFAULT_ALIAS.into(), ELSE_ALIAS.into()],
+            compat: None,
+            hoist_shared_branches: false,
+            lazy_branches: false,
+            solid_show: false,
+            line_width: None,
+            unless_tag: UNLESS_TAG.into(),
+            annotate_branches: false,
+            synthetic_spans: false,
+            strip_imports: Vec::new(),
+            strict_attributes: false,
+            verify_output: false,
+            import_sources: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            tags: TagNames::default(),
+            attributes: AttributeNames::default(),
+            vocabulary_file: None,
+            tag_namespaces: Vec::new(),
+            diagnostics_json: None,
+            diagnostics: HashMap::new(),
+            fragment: REACT_FRAGMENT.into(),
+            target: Target::default(),
+            import_fragment: false,
+            expression_containers: false,
+            env: HashMap::new(),
+            fold_env: true,
+            define: HashMap::new(),
+            condition_helper: None,
+            boolean_strategy: BooleanStrategy::default(),
+            output: OutputMode::default(),
+            preset: None,
+            fallback: NULL_FALLBACK.into(),
+            inline_if: None,
+            inline_else: None,
+            branch_wrapper: None,
+            features: HashMap::new(),
+            feature_lookup: None,
+            platform: None,
+            keep_mounted_element: KEEP_MOUNTED_ELEMENT.into(),
+            memo_hook: MEMO_HOOK.into(),
+            error_boundary: None,
+        }
+    }
+}
+
+/// Enables or disables named pipeline stages, e.g. `stages: { postTransform: false }`.
+/// Every stage is enabled unless switched off.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StagesConfig {
+    pub transform: bool,
+    pub post_transform: bool,
+    pub strip_imports: bool,
+}
+
+impl Default for StagesConfig {
+    fn default() -> Self {
+        Self {
+            transform: true,
+            post_transform: true,
+            strip_imports: true,
+        }
+    }
+}
+
+/// A named stage of the pipeline; stages always run in [`Stage::ORDER`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Transform,
+    PostTransform,
+    Verify,
+    StripImports,
+}
+
+impl Stage {
+    const ORDER: &'static [Stage] = &[Stage::Transform, Stage::PostTransform, Stage::Verify, Stage::StripImports];
+
+    fn is_enabled(self, config: &Config) -> bool {
+        let stages = &config.stages;
+        match self {
+            Stage::Transform => stages.transform,
+            Stage::PostTransform => stages.post_transform,
+            // 跳过任一阶段时控制标签或占位元素本来就会留下
+            Stage::Verify => config.verify_output && stages.transform && stages.post_transform,
+            Stage::StripImports => stages.strip_imports,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 enum WrapperType {
@@ -30,136 +455,832 @@ enum WrapperType {
 }
 
 pub struct TransformVisitor {
+    // Shared with the `TransformFactory` (hence `Arc`, which keeps it `Send + Sync`);
+    // each visitor only clones the handle
+    config: Arc<Config>,
     current_context: WrapperType,
-    // Cache frequently used small nodes directly; `Arc` adds atomic ref-counting overhead that
-    // isn’t required because the visitor lives on a single thread. Storing the values by
-    // value keeps them in the same cache line and makes `clone()` just a cheap `Copy` of a
-    // few bytes instead of an atomic ref-count update.
-    null_expr: Expr,
+    // Small nodes cloned into every lowering are built once per visitor and kept by value
     boolean_ident: Ident,
-    react_fragment_ident: Ident,
+    fragment_name: JSXElementName,
+    codegen: Arc<dyn Codegen>,
     condition_placeholder_ident: Ident,
+    fallback: Expr,
+    feature_lookup: Option<Expr>,
+    memo_hook: Option<Expr>,
     syntax_context: SyntaxContext,
     // Pre-computed atoms for fast string comparison
-    condition_atom: Atom,
-    switch_atom: Atom,
-    if_atom: Atom,
-    else_atom: Atom,
-    short_circuit_atom: Atom,
+    atoms: TagAtoms,
+    // Control tags declared as runtime components in the current file
+    runtime_components: Vec<(Atom, Span)>,
+    reported_runtime_components: Vec<Atom>,
+    // Shared branch subtrees waiting to be declared before the enclosing statement
+    pending_hoists: Vec<(Ident, JSXElement)>,
+    // Temporaries assigned in place, declared with `let` before the enclosing statement
+    pending_temps: Vec<Ident>,
+    // `inlineElse` sibling paired with the inline-if element being visited
+    pending_inline_else: Option<JSXElement>,
+    // Names declared by enclosing `for (var …)` heads
+    loop_vars: Vec<Atom>,
+    // Whether the innermost function may call hooks, and the name the next
+    // visited function is declared under
+    hook_scope: bool,
+    pending_fn_name: Option<Atom>,
+    // Generated temporaries of the current file
+    names: NameAllocator,
+    annotations: Option<BranchAnnotations>,
+    comments: Option<Lrc<dyn Comments>>,
+    host_env: HashMap<String, String>,
+    source_map: Option<Lrc<SourceMapperDyn>>,
+    json_diagnostics: Vec<JsonDiagnostic>,
+    span_mappings: Option<Vec<SpanMapping>>,
+    // Control tags imported from `importSources` in the current file
+    imported_tags: Vec<(Atom, SyntaxContext)>,
+    // Local bindings in the current file that reuse a control tag name
+    shadowed_tags: Vec<(Atom, SyntaxContext)>,
+    // Control tags a `@condition-ignore` comment keeps as written
+    ignored_elements: Vec<Span>,
+    // Whether the prescan found control tags, so the later stages have work
+    found_control_tags: bool,
 }
 
 impl Default for TransformVisitor {
     fn default() -> Self {
-        let span = swc_core::common::DUMMY_SP;
-        let syntax_context = SyntaxContext::empty();
-        Self {
-            current_context: WrapperType::Jsx,
-            null_expr: Expr::Lit(Lit::Null(Null { span })),
-            boolean_ident: Ident::new(BOOLEAN_FUNC.into(), span, syntax_context),
-            react_fragment_ident: Ident::new(REACT_FRAGMENT.into(), span, syntax_context),
-            condition_placeholder_ident: Ident::new(CONDITION_PLACEHOLDER.into(), span, syntax_context),
-            syntax_context,
-            condition_atom: CONDITION_TAG.into(),
-            switch_atom: SWITCH_TAG.into(),
-            if_atom: IF_ATTR.into(),
-            else_atom: ELSE_ATTR.into(),
-            short_circuit_atom: SHORT_CIRCUIT_ATTR.into(),
+        Self::new(Config::default())
+    }
+}
+
+impl TransformVisitor {
+    pub fn new(config: Config) -> Self {
+        TransformFactory::new(config).visitor()
+    }
+
+    /// Hands the visitor the host facilities it can use: `annotateBranches`
+    /// needs both comments and a source map, JSON diagnostics use the source
+    /// map for their ranges, JSX pragmas are read from the comments.
+    pub fn with_host(mut self, host: TransformHost) -> Self {
+        let comments: Option<Lrc<dyn Comments>> = host.comments.map(Lrc::from);
+        self.annotations = match (&comments, &host.source_map) {
+            (Some(comments), Some(source_map)) if cfg!(feature = "instrumentation") && self.config.annotate_branches => {
+                Some(BranchAnnotations::new(comments.clone(), source_map.clone(), self.config.synthetic_spans))
+            }
+            _ => None,
+        };
+        self.comments = comments;
+        self.source_map = host.source_map;
+        self.host_env = host.env;
+        if let (Some(mark), None) = (host.unresolved_mark, &self.config.condition_helper) {
+            self.boolean_ident.ctxt = SyntaxContext::empty().apply_mark(mark);
         }
+        self
     }
 }
 
-impl Fold for TransformVisitor {
-    fn fold_jsx_element(&mut self, element: JSXElement) -> JSXElement {
-        if let JSXElementName::Ident(ident) = &element.opening.name {
-            if ident.sym == self.condition_atom {
-                if let Some(condition_expr) = self.extract_condition_from_attrs(&element.opening.attrs) {
-                    return self.create_conditional_jsx(condition_expr, element.children, element.span);
-                }
-            } else if ident.sym == self.switch_atom {
-                if self.has_switch_case_children(&element.children) {
-                    let short_circuit = self.extract_short_circuit_attr(&element.opening.attrs);
-                    return self.create_switch_transformation(element.children, short_circuit, element.span);
+impl VisitMut for TransformVisitor {
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        if self.is_disabled_file(module.span, module.body.first().map(|item| item.span())) {
+            return;
+        }
+        let scan = self.scan_file(module);
+        if !scan.has_control_tags {
+            return;
+        }
+        self.found_control_tags = true;
+        self.scan_module(module, scan);
+        module.visit_mut_children_with(self);
+        self.insert_fragment_import(module);
+        self.insert_condition_helper_import(module);
+    }
+
+    fn visit_mut_script(&mut self, script: &mut Script) {
+        if self.is_disabled_file(script.span, script.body.first().map(|stmt| stmt.span())) {
+            return;
+        }
+        let scan = self.scan_file(script);
+        if !scan.has_control_tags {
+            return;
+        }
+        self.found_control_tags = true;
+        self.scan_script(script, scan);
+        script.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        let mut result = Vec::with_capacity(items.len());
+        for mut item in items.drain(..) {
+            item.visit_mut_with(self);
+            result.extend(self.take_hoisted_decls().into_iter().map(ModuleItem::Stmt));
+            result.push(item);
+        }
+        *items = result;
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        let mut result = Vec::with_capacity(stmts.len());
+        for mut stmt in stmts.drain(..) {
+            stmt.visit_mut_with(self);
+            result.extend(self.take_hoisted_decls());
+            result.push(stmt);
+        }
+        *stmts = result;
+    }
+
+    fn visit_mut_for_stmt(&mut self, stmt: &mut ForStmt) {
+        let declared = match &stmt.init {
+            Some(VarDeclOrExpr::VarDecl(decl)) => loop_var_names(decl),
+            _ => Vec::new(),
+        };
+        self.with_loop_vars(declared, |visitor| stmt.visit_mut_children_with(visitor));
+    }
+
+    fn visit_mut_for_in_stmt(&mut self, stmt: &mut ForInStmt) {
+        let declared = match &stmt.left {
+            ForHead::VarDecl(decl) => loop_var_names(decl),
+            _ => Vec::new(),
+        };
+        self.with_loop_vars(declared, |visitor| stmt.visit_mut_children_with(visitor));
+    }
+
+    fn visit_mut_for_of_stmt(&mut self, stmt: &mut ForOfStmt) {
+        let declared = match &stmt.left {
+            ForHead::VarDecl(decl) => loop_var_names(decl),
+            _ => Vec::new(),
+        };
+        self.with_loop_vars(declared, |visitor| stmt.visit_mut_children_with(visitor));
+    }
+
+    fn visit_mut_fn_decl(&mut self, decl: &mut FnDecl) {
+        self.pending_fn_name = Some(decl.ident.sym.clone());
+        decl.function.visit_mut_with(self);
+    }
+
+    fn visit_mut_fn_expr(&mut self, expr: &mut FnExpr) {
+        if let Some(ident) = &expr.ident {
+            self.pending_fn_name = Some(ident.sym.clone());
+        }
+        expr.function.visit_mut_with(self);
+    }
+
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        self.with_fn_scope(|visitor| function.visit_mut_children_with(visitor));
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        // 简写箭头函数没有语句列表，提升的常量需要放进改写后的块体中
+        let outer_hoists = std::mem::take(&mut self.pending_hoists);
+        let outer_temps = std::mem::take(&mut self.pending_temps);
+        self.with_fn_scope(|visitor| {
+            arrow.params.visit_mut_with(visitor);
+            match &mut *arrow.body {
+                // 表达式体等同于 return，和 `return <Condition>` 一样按表达式降级
+                BlockStmtOrExpr::Expr(body) => {
+                    let prev_context = std::mem::replace(&mut visitor.current_context, WrapperType::Return);
+                    body.visit_mut_with(visitor);
+                    visitor.current_context = prev_context;
                 }
+                body => body.visit_mut_with(visitor),
+            }
+        });
+        let hoisted = self.take_hoisted_decls();
+        self.pending_hoists = outer_hoists;
+        self.pending_temps = outer_temps;
+
+        if !hoisted.is_empty() {
+            if let BlockStmtOrExpr::Expr(body) = &mut *arrow.body {
+                let body = body.take();
+                let span = body.span();
+                let mut stmts = hoisted;
+                stmts.push(Stmt::Return(ReturnStmt { span, arg: Some(body) }));
+                *arrow.body = BlockStmtOrExpr::BlockStmt(BlockStmt {
+                    span,
+                    ctxt: self.syntax_context,
+                    stmts,
+                });
             }
         }
+    }
 
-        let mut new_element = element;
-        new_element.children = new_element.children.fold_with(self);
-        new_element
+    fn visit_mut_jsx_element(&mut self, element: &mut JSXElement) {
+        if self.is_raw_element(element) {
+            // raw 或 @condition-ignore：标签保持原样，只处理子节点
+        } else if self.keeps_runtime_tags() {
+            self.fold_env_attrs(element);
+        } else if let Some(lowered) = self.lower_inline_if(element) {
+            self.record_span_mapping(element.span, &lowered);
+            *element = lowered;
+            return;
+        } else if self.config.transform_nested && self.is_lowerable_root(element) {
+            self.lower_nested_branches(element);
+            if let Some(lowered) = self.lower_control_element(element) {
+                self.replace_lowered(element, lowered);
+            }
+            return;
+        } else if let Some(lowered) = self.lower_control_element(element) {
+            self.replace_lowered(element, lowered);
+            return;
+        }
+        element.opening.attrs.visit_mut_with(self);
+        element.children.visit_mut_with(self);
     }
 
-    fn fold_jsx_element_child(&mut self, child: JSXElementChild) -> JSXElementChild {
+    fn visit_mut_jsx_element_childs(&mut self, children: &mut Vec<JSXElementChild>) {
+        let mut result = Vec::with_capacity(children.len());
+        let mut rest = children.drain(..).collect::<VecDeque<_>>();
+        while let Some(mut child) = rest.pop_front() {
+            if let JSXElementChild::JSXElement(element) = &child {
+                self.pending_inline_else = self.take_inline_else(element, &mut rest);
+            }
+            if let JSXElementChild::JSXElement(element) = &mut child {
+                if let Some(mut inlined) = self.fold_static_child(element) {
+                    self.visit_mut_jsx_element_childs(&mut inlined);
+                    result.extend(inlined);
+                    continue;
+                }
+            }
+            child.visit_mut_with(self);
+            result.push(child);
+        }
+        *children = result;
+    }
+
+    fn visit_mut_jsx_element_child(&mut self, child: &mut JSXElementChild) {
         match child {
             JSXElementChild::JSXElement(element) => {
-                JSXElementChild::JSXElement(Box::new(self.with_jsx_context(|visitor| visitor.fold_jsx_element(*element))))
+                let lowered = !element.opening.name.eq_ignore_span(&self.fragment_name);
+                self.with_jsx_context(|visitor| visitor.visit_mut_jsx_element(element));
+                if self.config.expression_containers {
+                    if let Some(inner) = self.take_fragment_holder(element, lowered) {
+                        *child = inner;
+                    }
+                }
             }
             JSXElementChild::JSXFragment(fragment) => {
-                JSXElementChild::JSXFragment(self.with_jsx_context(|visitor| visitor.fold_jsx_fragment(fragment)))
+                self.with_jsx_context(|visitor| visitor.visit_mut_jsx_fragment(fragment));
             }
-            JSXElementChild::JSXExprContainer(container) => {
-                JSXElementChild::JSXExprContainer(self.fold_jsx_expr_container(container))
-            }
-            _ => child,
+            JSXElementChild::JSXExprContainer(container) => self.visit_mut_jsx_expr_container(container),
+            _ => {}
         }
     }
 
-    fn fold_jsx_fragment(&mut self, mut fragment: JSXFragment) -> JSXFragment {
-        fragment.children = fragment.children.fold_with(self);
-        fragment
+    fn visit_mut_jsx_fragment(&mut self, fragment: &mut JSXFragment) {
+        fragment.children.visit_mut_with(self);
     }
 
-    fn fold_jsx_expr_container(&mut self, mut container: JSXExprContainer) -> JSXExprContainer {
-        container.expr = self.with_jsx_context(|visitor| container.expr.fold_with(visitor));
-        container
+    fn visit_mut_jsx_expr_container(&mut self, container: &mut JSXExprContainer) {
+        self.with_jsx_context(|visitor| container.expr.visit_mut_with(visitor));
     }
 
-    fn fold_jsx_expr(&mut self, expr: JSXExpr) -> JSXExpr {
-        match expr {
-            JSXExpr::Expr(e) => JSXExpr::Expr(e.fold_with(self)),
-            _ => expr,
+    // `sidebar={<Condition>…}` 这类属性值和 return 一样按表达式降级，不插入 Fragment
+    fn visit_mut_jsx_attr_value(&mut self, value: &mut JSXAttrValue) {
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Return);
+        match value {
+            JSXAttrValue::JSXExprContainer(container) => container.expr.visit_mut_with(self),
+            JSXAttrValue::JSXElement(element) => {
+                self.visit_mut_jsx_element(element);
+                // 属性值不能直接是表达式，放进 `{…}` 交给后处理展开
+                if self.is_placeholder(element) {
+                    let span = element.span;
+                    *value = JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                        span,
+                        expr: JSXExpr::Expr(Box::new(Expr::JSXElement(element.take()))),
+                    });
+                }
+            }
+            _ => value.visit_mut_children_with(self),
+        }
+        self.current_context = prev_context;
+    }
+
+    fn visit_mut_jsx_expr(&mut self, expr: &mut JSXExpr) {
+        if let JSXExpr::Expr(e) = expr {
+            e.visit_mut_with(self);
         }
     }
 
-    fn fold_return_stmt(&mut self, mut stmt: ReturnStmt) -> ReturnStmt {
+    fn visit_mut_return_stmt(&mut self, stmt: &mut ReturnStmt) {
         let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Return);
-        stmt.arg = stmt.arg.fold_with(self);
+        stmt.arg.visit_mut_with(self);
+        self.current_context = prev_context;
+    }
+
+    // `export default <Condition>…` 和 return 一样按表达式降级
+    fn visit_mut_export_default_expr(&mut self, export: &mut ExportDefaultExpr) {
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Return);
+        export.expr.visit_mut_with(self);
+        self.current_context = prev_context;
+    }
+
+    fn visit_mut_var_declarator(&mut self, declarator: &mut VarDeclarator) {
+        // `const App = () => …`、`const App = memo((props) => …)` 里的函数以变量名为准
+        self.pending_fn_name = declarator.name.as_ident().map(|ident| ident.sym.clone());
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+        declarator.init.visit_mut_with(self);
+        self.current_context = prev_context;
+        self.pending_fn_name = None;
+    }
+
+    fn visit_mut_class_prop(&mut self, prop: &mut ClassProp) {
+        prop.key.visit_mut_with(self);
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+        prop.value.visit_mut_with(self);
+        self.current_context = prev_context;
+    }
+
+    fn visit_mut_private_prop(&mut self, prop: &mut PrivateProp) {
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+        prop.value.visit_mut_with(self);
+        self.current_context = prev_context;
+    }
+
+    // `[{ element: <Condition>… }]` 这类对象属性和数组元素也是值，按赋值上下文降级
+    fn visit_mut_key_value_prop(&mut self, prop: &mut KeyValueProp) {
+        prop.key.visit_mut_with(self);
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+        prop.value.visit_mut_with(self);
+        self.current_context = prev_context;
+    }
+
+    fn visit_mut_array_lit(&mut self, array: &mut ArrayLit) {
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+        array.elems.visit_mut_with(self);
+        self.current_context = prev_context;
+    }
+
+    fn visit_mut_assign_expr(&mut self, expr: &mut AssignExpr) {
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+        expr.right.visit_mut_with(self);
+        self.current_context = prev_context;
+    }
+
+    // `createPortal(<Condition>…, node)`、`cloneElement(<Switch>…)` 等调用参数按表达式降级，
+    // 不再多包一层 React.Fragment
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        call.callee.visit_mut_with(self);
+        let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+        call.args.visit_mut_with(self);
         self.current_context = prev_context;
-        stmt
     }
 
-    fn fold_var_declarator(&mut self, mut declarator: VarDeclarator) -> VarDeclarator {
+    fn visit_mut_new_expr(&mut self, expr: &mut NewExpr) {
+        expr.callee.visit_mut_with(self);
         let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
-        declarator.init = declarator.init.fold_with(self);
+        expr.args.visit_mut_with(self);
         self.current_context = prev_context;
-        declarator
     }
 
-    fn fold_assign_expr(&mut self, mut expr: AssignExpr) -> AssignExpr {
+    fn visit_mut_opt_call(&mut self, call: &mut OptCall) {
+        call.callee.visit_mut_with(self);
         let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
-        expr.right = expr.right.fold_with(self);
+        call.args.visit_mut_with(self);
         self.current_context = prev_context;
-        expr
     }
 
-    fn fold_expr(&mut self, expr: Expr) -> Expr {
+    // `cond && <Condition>`、`a ? <Switch> : null` 的操作数按表达式降级，不包 Fragment
+    fn visit_mut_bin_expr(&mut self, bin: &mut BinExpr) {
+        if matches!(bin.op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing) {
+            self.with_expression_context(|visitor| bin.visit_mut_children_with(visitor));
+        } else {
+            bin.visit_mut_children_with(self);
+        }
+    }
+
+    fn visit_mut_cond_expr(&mut self, cond: &mut CondExpr) {
+        cond.test.visit_mut_with(self);
+        self.with_expression_context(|visitor| {
+            cond.cons.visit_mut_with(visitor);
+            cond.alt.visit_mut_with(visitor);
+        });
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
         match expr {
-            Expr::JSXElement(element) => {
-                Expr::JSXElement(Box::new(self.fold_jsx_element(*element)))
+            Expr::JSXElement(element) => self.visit_mut_jsx_element(element),
+            Expr::JSXFragment(fragment) => self.visit_mut_jsx_fragment(fragment),
+            Expr::Bin(bin) if self.config.merge_guards && !self.keeps_runtime_tags() && bin.op == BinaryOp::LogicalAnd => {
+                if let Some(lowered) = self.with_expression_context(|visitor| visitor.lower_guarded_condition(bin)) {
+                    *expr = lowered;
+                }
+            }
+            _ => expr.visit_mut_children_with(self),
+        }
+    }
+}
+
+impl TransformVisitor {
+    fn replace_lowered(&mut self, element: &mut JSXElement, mut lowered: JSXElement) {
+        if let Some(key) = self.key_attr(element) {
+            self.apply_key(&mut lowered, key);
+        }
+        self.record_span_mapping(element.span, &lowered);
+        *element = lowered;
+    }
+
+    /// The expression or placeholder element a control tag lowers to, or
+    /// `None` when `element` is left as written. Lowering moves the children
+    /// out of `element`.
+    fn lower_control_element(&mut self, element: &mut JSXElement) -> Option<JSXElement> {
+        let (tag, binding) = self.control_tag_name(&element.opening.name)?;
+        let span = element.span;
+        if self.config.strict_attributes && self.is_lowerable_tag(binding) {
+            self.report_unknown_attributes(element, &tag.clone());
+        }
+        if !self.is_imported_tag(binding) {
+            // 不是从 importSources 导入的同名组件，保持原样
+        } else if self.is_runtime_component(&binding.sym) {
+            let sym = binding.sym.clone();
+            self.report_runtime_component(&sym, span);
+        } else if self.is_shadowed_tag(binding) {
+            // 局部变量遮蔽了同名标签，按普通组件处理
+        } else if *tag == self.atoms.condition_atom {
+            if let Some(condition_expr) = self.extract_condition_from_attrs(&element.opening.attrs) {
+                self.lint_constant_condition(&condition_expr);
+                return Some(self.lower_condition_tag(element, condition_expr, None, span));
+            }
+            self.report_invalid_condition(element, &self.atoms.if_atom.clone());
+        } else if *tag == self.atoms.unless_atom {
+            if let Some(condition_expr) = self.extract_condition_from_attrs(&element.opening.attrs) {
+                self.lint_constant_condition(&condition_expr);
+                let negated = Self::negate_condition(condition_expr);
+                return Some(self.lower_condition_tag(element, negated, None, span));
+            }
+            self.report_invalid_condition(element, &self.atoms.if_atom.clone());
+        } else if *tag == self.atoms.switch_atom && self.has_switch_case_children(&element.children) {
+            self.check_exhaustive_switch(element);
+            let short_circuit = self.extract_short_circuit_attr(&element.opening.attrs);
+            let value = self.extract_attr_value(&element.opening.attrs, &self.atoms.value_atom);
+            let lazy = self.config.lazy_branches || self.has_flag(&element.opening.attrs, &self.atoms.lazy_atom);
+            let lowered = self.create_switch_transformation(element.children.take(), short_circuit, lazy, value, span);
+            return Some(self.memoize_switch(&element.opening.attrs, lowered, span));
+        } else if *tag == self.atoms.for_atom {
+            if let Some(loop_expr) = self.create_for_expr(element) {
+                return Some(self.wrap_expr_for_context(loop_expr, span));
+            }
+        } else if *tag == self.atoms.with_atom {
+            if let Some(scoped_expr) = self.create_with_expr(element) {
+                return Some(self.wrap_expr_for_context(scoped_expr, span));
+            }
+        } else if *tag == self.atoms.feature_atom {
+            if let Some(condition_expr) = self.feature_condition(&element.opening.attrs, span) {
+                return Some(self.lower_condition_tag(element, condition_expr, None, span));
             }
-            Expr::JSXFragment(fragment) => {
-                Expr::JSXFragment(self.fold_jsx_fragment(fragment))
+        } else if *tag == self.atoms.platform_atom {
+            if let Some(condition_expr) = self.platform_condition(&element.opening.attrs, span) {
+                return Some(self.lower_condition_tag(element, condition_expr, None, span));
+            }
+        } else if self.config.solid_show && *tag == self.atoms.show_atom {
+            if let Some(condition_expr) = self.extract_condition_attr(&element.opening.attrs, &self.atoms.when_atom) {
+                self.lint_constant_condition(&condition_expr);
+                let fallback = self.extract_attr_value(&element.opening.attrs, &self.atoms.fallback_atom);
+                return Some(self.lower_condition_tag(element, condition_expr, fallback, span));
+            }
+            self.report_invalid_condition(element, &self.atoms.when_atom.clone());
+        } else if let Some(compat) = self.atoms.compat() {
+            if *tag == compat.if_tag {
+                if let Some(condition_expr) = self.extract_condition_attr(&element.opening.attrs, &compat.condition_attr) {
+                    self.lint_constant_condition(&condition_expr);
+                    return Some(self.lower_condition_tag(element, condition_expr, None, span));
+                }
+                self.report_invalid_condition(element, &compat.condition_attr.clone());
+            } else if *tag == compat.choose_tag && self.has_switch_case_children(&element.children) {
+                // <Choose> 语义上就是 if / else if 链，总是短路
+                return Some(self.create_switch_transformation(element.children.take(), true, self.config.lazy_branches, None, span));
             }
-            _ => expr.fold_children_with(self),
         }
+        None
     }
 }
 
 impl TransformVisitor {
+    fn take_hoisted_decls(&mut self) -> Vec<Stmt> {
+        let temps = (!self.pending_temps.is_empty()).then(|| {
+            let span = self.pending_temps[0].span;
+            Stmt::Decl(Decl::Var(Box::new(VarDecl {
+                span,
+                ctxt: self.syntax_context,
+                kind: VarDeclKind::Let,
+                declare: false,
+                decls: self.pending_temps.drain(..)
+                    .map(|ident| VarDeclarator {
+                        span: ident.span,
+                        name: Pat::Ident(BindingIdent { id: ident, type_ann: None }),
+                        init: None,
+                        definite: false,
+                    })
+                    .collect(),
+            })))
+        });
+        let hoists = self.pending_hoists.drain(..)
+            .map(|(ident, element)| {
+                Stmt::Decl(Decl::Var(Box::new(VarDecl {
+                    span: element.span,
+                    ctxt: self.syntax_context,
+                    kind: VarDeclKind::Const,
+                    declare: false,
+                    decls: vec![VarDeclarator {
+                        span: element.span,
+                        name: Pat::Ident(BindingIdent { id: ident, type_ann: None }),
+                        init: Some(Box::new(Expr::JSXElement(Box::new(element)))),
+                        definite: false,
+                    }],
+                })))
+            });
+        temps.into_iter().chain(hoists).collect()
+    }
+
+    /// A subtree without expression containers or spreads can be evaluated
+    /// ahead of the branch without observable differences.
+    fn is_static_jsx(element: &JSXElement) -> bool {
+        element.opening.attrs.iter().all(|attr| matches!(attr,
+            JSXAttrOrSpread::JSXAttr(JSXAttr { value: None | Some(JSXAttrValue::Lit(_)), .. })))
+            && element.children.iter().all(|child| match child {
+                JSXElementChild::JSXText(_) => true,
+                JSXElementChild::JSXElement(child) => Self::is_static_jsx(child),
+                JSXElementChild::JSXFragment(fragment) => fragment.children.iter().all(|child| match child {
+                    JSXElementChild::JSXText(_) => true,
+                    JSXElementChild::JSXElement(child) => Self::is_static_jsx(child),
+                    _ => false,
+                }),
+                _ => false,
+            })
+    }
+
+    fn count_jsx_nodes(children: &[JSXElementChild]) -> usize {
+        children.iter()
+            .map(|child| match child {
+                JSXElementChild::JSXElement(element) => 1 + Self::count_jsx_nodes(&element.children),
+                JSXElementChild::JSXFragment(fragment) => Self::count_jsx_nodes(&fragment.children),
+                child => usize::from(Self::is_non_whitespace_child(child)),
+            })
+            .sum()
+    }
+
+    /// Replaces static subtrees that appear in more than one branch with a
+    /// reference to a hoisted const.
+    fn hoist_shared_branch_children(&mut self, branches: &mut [&mut Vec<JSXElementChild>]) {
+        for i in 0..branches.len() {
+            for k in 0..branches[i].len() {
+                let candidate = match &branches[i][k] {
+                    JSXElementChild::JSXElement(element)
+                        if Self::is_static_jsx(element)
+                            && 1 + Self::count_jsx_nodes(&element.children) >= SHARED_BRANCH_MIN_NODES => element.clone(),
+                    _ => continue,
+                };
+                let is_candidate = |child: &JSXElementChild| {
+                    matches!(child, JSXElementChild::JSXElement(element) if element.eq_ignore_span(&candidate))
+                };
+                let shared = branches.iter().enumerate()
+                    .any(|(j, branch)| j != i && branch.iter().any(is_candidate));
+                if !shared {
+                    continue;
+                }
+
+                let ident = Ident::new(self.names.numbered(BRANCH_SHARED_PREFIX), candidate.span, self.syntax_context);
+                for branch in branches.iter_mut() {
+                    for child in branch.iter_mut() {
+                        if is_candidate(child) {
+                            *child = JSXElementChild::JSXExprContainer(JSXExprContainer {
+                                span: candidate.span,
+                                expr: JSXExpr::Expr(Box::new(Expr::Ident(ident.clone()))),
+                            });
+                        }
+                    }
+                }
+                self.pending_hoists.push((ident, *candidate));
+            }
+        }
+    }
+
+    fn is_control_tag_name(&self, sym: &Atom) -> bool {
+        self.atoms.control_tags().contains(&sym)
+    }
+
+    /// Top-level bindings in `decl` that reuse a control tag name.
+    fn control_tag_decls(&self, decl: &Decl) -> Vec<(Atom, Span)> {
+        let idents: Vec<&Ident> = match decl {
+            Decl::Fn(fn_decl) => vec![&fn_decl.ident],
+            Decl::Class(class_decl) => vec![&class_decl.ident],
+            Decl::Var(var_decl) => var_decl.decls.iter()
+                .filter_map(|declarator| match &declarator.name {
+                    Pat::Ident(binding) => Some(&binding.id),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        idents.into_iter()
+            .filter(|ident| self.is_control_tag_name(&ident.sym))
+            .map(|ident| (ident.sym.clone(), ident.span))
+            .collect()
+    }
+
+    /// Collects the per-file facts tag matching depends on: runtime
+    /// components, imported and shadowed tags, and the fragment wrapper.
+    fn scan_module(&mut self, module: &Module, scan: FileScan) {
+        let mut runtime_components = Vec::new();
+        for item in &module.body {
+            match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => runtime_components.extend(self.control_tag_decls(decl)),
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                    runtime_components.extend(self.control_tag_decls(&export.decl))
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => match &export.decl {
+                    DefaultDecl::Fn(FnExpr { ident: Some(ident), .. })
+                    | DefaultDecl::Class(ClassExpr { ident: Some(ident), .. })
+                        if self.is_control_tag_name(&ident.sym) =>
+                    {
+                        runtime_components.push((ident.sym.clone(), ident.span))
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        self.runtime_components = runtime_components;
+        self.names = NameAllocator::new(scan.idents);
+        self.imported_tags = scan.imported_tags;
+        self.shadowed_tags = scan.shadowed_tags;
+        self.ignored_elements = scan.ignored_elements;
+        if self.config.fragment == FRAGMENT_SHORTHAND {
+            self.fragment_name = self.detect_fragment_name(module);
+        }
+        let first_item = module.body.first().map(|item| item.span());
+        if let Some(fragment_name) = self.pragma_fragment_name(module.span, first_item) {
+            self.fragment_name = fragment_name;
+        }
+    }
+
+    fn scan_script(&mut self, script: &Script, scan: FileScan) {
+        self.runtime_components = script.body.iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Decl(decl) => Some(decl),
+                _ => None,
+            })
+            .flat_map(|decl| self.control_tag_decls(decl))
+            .collect();
+        self.names = NameAllocator::new(scan.idents);
+        self.imported_tags = Vec::new();
+        self.shadowed_tags = scan.shadowed_tags;
+        self.ignored_elements = scan.ignored_elements;
+        let first_stmt = script.body.first().map(|stmt| stmt.span());
+        if let Some(fragment_name) = self.pragma_fragment_name(script.span, first_stmt) {
+            self.fragment_name = fragment_name;
+        }
+    }
+
+    /// Named imports of `import` when it is from a configured
+    /// `importSources` package, keyed by their binding's syntax context.
+    fn imported_tags_of<'a>(&self, import: &'a ImportDecl) -> impl Iterator<Item = (Atom, SyntaxContext)> + 'a {
+        let from_source = self.config.import_sources.iter().any(|source| import.src.value == *source);
+        import.specifiers.iter()
+            .filter(move |_| from_source)
+            .filter_map(|specifier| match specifier {
+                // 只认原名导入，`Condition as If` 之类的别名不处理
+                ImportSpecifier::Named(named) if named.imported.as_ref().is_none_or(|imported| match imported {
+                    ModuleExportName::Ident(imported) => imported.sym == named.local.sym,
+                    ModuleExportName::Str(imported) => imported.value == named.local.sym,
+                }) => Some(&named.local),
+                // `import * as Flow` 配合 tagNamespaces 使用
+                ImportSpecifier::Namespace(namespace) => Some(&namespace.local),
+                _ => None,
+            })
+            .map(|local| (local.sym.clone(), local.ctxt))
+    }
+
+    /// Without `importSources` every tag qualifies; otherwise the tag must
+    /// resolve to one of the collected imports.
+    fn is_imported_tag(&self, ident: &Ident) -> bool {
+        self.config.import_sources.is_empty()
+            || self.imported_tags.iter().any(|(sym, ctxt)| *sym == ident.sym && *ctxt == ident.ctxt)
+    }
+
+    fn is_lowerable_tag(&self, ident: &Ident) -> bool {
+        self.is_imported_tag(ident) && !self.is_runtime_component(&ident.sym) && !self.is_shadowed_tag(ident)
+    }
+
+    fn is_runtime_component(&self, sym: &Atom) -> bool {
+        self.runtime_components.iter().any(|(name, _)| name == sym)
+    }
+
+    /// Markup for a tag that the file also defines as a component is left
+    /// untouched; lowering it would silently bypass the local component.
+    fn report_runtime_component(&mut self, sym: &Atom, usage_span: Span) {
+        if self.reported_runtime_components.contains(sym) {
+            return;
+        }
+        self.reported_runtime_components.push(sym.clone());
+
+        let Some(severity) = self.rule_severity("runtime-component", Severity::Error) else {
+            return;
+        };
+        let message = format!("`<{sym}>` is also defined in this file as a runtime component, so it cannot be compiled away");
+        self.record_json_diagnostic(usage_span, severity, "runtime-component", message.clone());
+        if !HANDLER.is_set() {
+            return;
+        }
+
+        let usage_span = self.diagnostic_span(usage_span);
+        let decl_span = self.runtime_components.iter()
+            .find(|(name, _)| name == sym)
+            .map(|(_, span)| self.diagnostic_span(*span))
+            .filter(|span| !span.is_dummy())
+            .unwrap_or(usage_span);
+        HANDLER.with(|handler| {
+            let mut diagnostic = match severity {
+                Severity::Error => handler.struct_span_err(usage_span, &message),
+                _ => handler.struct_span_warn(usage_span, &message),
+            };
+            diagnostic
+                .span_note(decl_span, &format!("`{sym}` is declared here; rename it or import it under another name to use the compile-time `<{sym}>`"))
+                .emit();
+        });
+    }
+
+    /// Lowers `guard && <Condition if={x}>…</Condition>` into a single ternary
+    /// whose test is `guard && x`, instead of nesting the Condition output
+    /// behind the guard. Other `&&` operands are visited in place.
+    fn lower_guarded_condition(&mut self, bin: &mut BinExpr) -> Option<Expr> {
+        bin.left.visit_mut_with(self);
+
+        if let Some(element) = self.as_condition_element(&bin.right) {
+            if let Some(condition_expr) = self.extract_condition_from_attrs(&element.opening.attrs) {
+                let element_span = element.span;
+                let children = element.children.clone();
+                let test = Box::new(Expr::Bin(BinExpr {
+                    span: bin.span,
+                    op: BinaryOp::LogicalAnd,
+                    left: bin.left.take(),
+                    right: Self::paren_low_precedence(condition_expr),
+                }));
+                return Some(self.create_conditional_expr(test, children, None, element_span));
+            }
+        }
+
+        bin.right.visit_mut_with(self);
+        None
+    }
+
+    fn as_condition_element<'a>(&self, expr: &'a Expr) -> Option<&'a JSXElement> {
+        match expr {
+            Expr::Paren(paren) => self.as_condition_element(&paren.expr),
+            Expr::JSXElement(element) => match self.control_tag_name(&element.opening.name) {
+                Some((tag, binding)) if *tag == self.atoms.condition_atom && self.is_lowerable_tag(binding) && !self.is_raw_element(element) => Some(element),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Wraps expressions that bind looser than `&&` so they keep their
+    /// grouping when embedded as an operand.
+    fn paren_low_precedence(expr: Box<Expr>) -> Box<Expr> {
+        match *expr {
+            Expr::Bin(BinExpr { op: BinaryOp::LogicalOr | BinaryOp::NullishCoalescing, .. })
+            | Expr::Cond(_)
+            | Expr::Assign(_)
+            | Expr::Seq(_)
+            | Expr::Arrow(_)
+            | Expr::Yield(_) => Box::new(Expr::Paren(ParenExpr {
+                span: expr.span(),
+                expr,
+            })),
+            _ => expr,
+        }
+    }
+
+    /// `!condition`, parenthesizing operands that `!` would otherwise bind into.
+    fn negate_condition(condition: Box<Expr>) -> Box<Expr> {
+        let span = condition.span();
+        let arg = match *condition {
+            Expr::Bin(_)
+            | Expr::Cond(_)
+            | Expr::Assign(_)
+            | Expr::Seq(_)
+            | Expr::Arrow(_)
+            | Expr::Yield(_) => Box::new(Expr::Paren(ParenExpr {
+                span,
+                expr: condition,
+            })),
+            _ => condition,
+        };
+        Box::new(Expr::Unary(UnaryExpr {
+            span,
+            op: UnaryOp::Bang,
+            arg,
+        }))
+    }
+
     fn extract_condition_from_attrs(&self, attrs: &[JSXAttrOrSpread]) -> Option<Box<Expr>> {
+        self.extract_condition_attr(attrs, &self.atoms.if_atom)
+    }
+
+    fn extract_condition_attr(&self, attrs: &[JSXAttrOrSpread], atom: &Atom) -> Option<Box<Expr>> {
         for attr in attrs {
             if let JSXAttrOrSpread::JSXAttr(jsx_attr) = attr {
                 if let JSXAttrName::Ident(name) = &jsx_attr.name {
-                    if name.sym == self.if_atom {
+                    if name.sym == *atom {
+                        // JSX 布尔简写：<Condition if> 等同于 if={true}
+                        if jsx_attr.value.is_none() {
+                            return Some(Box::new(Expr::Lit(Lit::Bool(Bool { span: jsx_attr.span, value: true }))));
+                        }
                         if let Some(JSXAttrValue::JSXExprContainer(expr_container)) = &jsx_attr.value {
                             if let JSXExpr::Expr(condition_expr) = &expr_container.expr {
                                 return Some(condition_expr.clone());
@@ -172,11 +1293,190 @@ impl TransformVisitor {
         None
     }
 
+    /// Reads an attribute that accepts either a string literal (`is="a"`) or an
+    /// expression container (`is={a}`).
+    fn extract_attr_value(&self, attrs: &[JSXAttrOrSpread], atom: &Atom) -> Option<Box<Expr>> {
+        attrs.iter().find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(jsx_attr) => match (&jsx_attr.name, &jsx_attr.value) {
+                (JSXAttrName::Ident(name), Some(JSXAttrValue::Lit(lit))) if name.sym == *atom => {
+                    Some(Box::new(Expr::Lit(lit.clone())))
+                }
+                (JSXAttrName::Ident(name), Some(JSXAttrValue::JSXExprContainer(container))) if name.sym == *atom => {
+                    match &container.expr {
+                        JSXExpr::Expr(expr) => Some(expr.clone()),
+                        JSXExpr::JSXEmptyExpr(_) => None,
+                    }
+                }
+                (JSXAttrName::Ident(name), Some(JSXAttrValue::JSXElement(element))) if name.sym == *atom => {
+                    Some(Box::new(Expr::JSXElement(element.clone())))
+                }
+                (JSXAttrName::Ident(name), Some(JSXAttrValue::JSXFragment(fragment))) if name.sym == *atom => {
+                    Some(Box::new(Expr::JSXFragment(fragment.clone())))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// Resolves the test of a `Switch.Case`: an explicit `if` wins, otherwise in
+    /// value mode `is={x}` becomes `subject === x`, `in={xs}` becomes
+    /// `xs.includes(subject)` and `when={f}` becomes `f(subject)`.
+    fn extract_case_condition(&self, attrs: &[JSXAttrOrSpread], subject: Option<&Expr>) -> Option<Box<Expr>> {
+        if let Some(condition_expr) = self.extract_condition_from_attrs(attrs) {
+            return Some(condition_expr);
+        }
+        if let Some(compat) = self.atoms.compat() {
+            if let Some(condition_expr) = self.extract_condition_attr(attrs, &compat.condition_attr) {
+                return Some(condition_expr);
+            }
+        }
+
+        let subject = subject?;
+        if let Some(case_value) = self.extract_attr_value(attrs, &self.atoms.is_atom) {
+            let span = case_value.span();
+            return Some(Box::new(Expr::Bin(BinExpr {
+                span,
+                op: BinaryOp::EqEqEq,
+                left: Box::new(subject.clone()),
+                right: case_value,
+            })));
+        }
+
+        if let Some(predicate) = self.extract_attr_value(attrs, &self.atoms.when_atom) {
+            let span = predicate.span();
+            let callee = match *predicate {
+                Expr::Ident(_) | Expr::Member(_) | Expr::Call(_) | Expr::Paren(_) => predicate,
+                _ => Box::new(Expr::Paren(ParenExpr { span, expr: predicate })),
+            };
+            return Some(Box::new(Expr::Call(CallExpr {
+                span,
+                callee: Callee::Expr(callee),
+                args: vec![ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(subject.clone()),
+                }],
+                type_args: None,
+                ctxt: self.syntax_context,
+            })));
+        }
+
+        let case_values = self.extract_attr_value(attrs, &self.atoms.in_atom)?;
+        let span = case_values.span();
+        let obj = match *case_values {
+            Expr::Array(_) | Expr::Ident(_) | Expr::Member(_) | Expr::Call(_) | Expr::Paren(_) => case_values,
+            _ => Box::new(Expr::Paren(ParenExpr { span, expr: case_values })),
+        };
+        Some(Box::new(Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                span,
+                obj,
+                prop: MemberProp::Ident(IdentName::new(INCLUDES_METHOD.into(), span)),
+            }))),
+            args: vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(subject.clone()),
+            }],
+            type_args: None,
+            ctxt: self.syntax_context,
+        })))
+    }
+
+    /// Switch values that can be re-read without observable side effects are
+    /// referenced directly; anything else is hoisted into a temp.
+    fn is_simple_subject(expr: &Expr) -> bool {
+        match expr {
+            Expr::Ident(_) | Expr::This(_) | Expr::Lit(_) => true,
+            Expr::Member(member) => {
+                matches!(member.prop, MemberProp::Ident(_) | MemberProp::PrivateName(_))
+                    && Self::is_simple_subject(&member.obj)
+            }
+            _ => false,
+        }
+    }
+
+    /// Evaluates expressions once by binding them as the parameters of an
+    /// immediately invoked arrow: `(__switchValue => …)(value)`. A body that
+    /// awaits or yields cannot move into the arrow; the temporaries are then
+    /// declared before the statement and assigned in place:
+    /// `(__switchValue = value, …)`.
+    fn hoist_bindings(&mut self, mut element: JSXElement, bindings: Vec<(Ident, Box<Expr>)>, span: swc_core::common::Span) -> JSXElement {
+        if bindings.is_empty() {
+            return element;
+        }
+        let body = match element.children.as_mut_slice() {
+            [JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })] => {
+                std::mem::take(expr)
+            }
+            _ => Box::new(Expr::JSXFragment(JSXFragment {
+                span,
+                opening: JSXOpeningFragment { span },
+                children: std::mem::take(&mut element.children),
+                closing: JSXClosingFragment { span },
+            })),
+        };
+
+        if suspend::suspends(&body) {
+            let mut exprs: Vec<_> = bindings.into_iter()
+                .map(|(temp, value)| {
+                    self.pending_temps.push(temp.clone());
+                    Box::new(Expr::Assign(AssignExpr {
+                        span,
+                        op: AssignOp::Assign,
+                        left: AssignTarget::Simple(SimpleAssignTarget::Ident(BindingIdent { id: temp, type_ann: None })),
+                        right: value,
+                    }))
+                })
+                .collect();
+            exprs.push(body);
+            element.children = vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
+                span,
+                expr: JSXExpr::Expr(Box::new(Expr::Paren(ParenExpr {
+                    span,
+                    expr: Box::new(Expr::Seq(SeqExpr { span, exprs })),
+                }))),
+            })];
+            return element;
+        }
+
+        let (params, args): (Vec<_>, Vec<_>) = bindings.into_iter()
+            .map(|(temp, value)| (Pat::Ident(BindingIdent { id: temp, type_ann: None }), ExprOrSpread { spread: None, expr: value }))
+            .unzip();
+        let arrow = Expr::Arrow(ArrowExpr {
+            span,
+            ctxt: self.syntax_context,
+            params,
+            body: Box::new(BlockStmtOrExpr::Expr(body)),
+            is_async: false,
+            is_generator: false,
+            type_params: None,
+            return_type: None,
+        });
+
+        let call = Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(Box::new(Expr::Paren(ParenExpr {
+                span,
+                expr: Box::new(arrow),
+            }))),
+            args,
+            type_args: None,
+            ctxt: self.syntax_context,
+        });
+
+        element.children = vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
+            span,
+            expr: JSXExpr::Expr(Box::new(call)),
+        })];
+        element
+    }
+
     fn has_else_attr(&self, attrs: &[JSXAttrOrSpread]) -> bool {
         attrs.iter().any(|attr| {
             matches!(attr, JSXAttrOrSpread::JSXAttr(jsx_attr) 
                 if matches!(&jsx_attr.name, JSXAttrName::Ident(name) 
-                    if name.sym == self.else_atom))
+                    if name.sym == self.atoms.else_atom))
         })
     }
 
@@ -184,66 +1484,160 @@ impl TransformVisitor {
         &self.current_context
     }
 
-    fn create_conditional_jsx(&self, condition: Box<Expr>, children: Vec<JSXElementChild>, span: swc_core::common::Span) -> JSXElement {
-        let fragment = JSXFragment {
-            span,
-            opening: JSXOpeningFragment { span },
-            children,
-            closing: JSXClosingFragment { span },
-        };
-
-        let current_context = self.get_current_context();
-        
-        let test_expr = match current_context {
+    fn create_test_expr(&self, condition: Box<Expr>, span: swc_core::common::Span) -> Expr {
+        match self.get_current_context() {
             WrapperType::Return => *condition,
-            WrapperType::Assignment | WrapperType::Jsx => {
-                Expr::Call(CallExpr {
-                    span,
-                    callee: Callee::Expr(Box::new(Expr::Ident(self.boolean_ident.clone()))),
-                    args: vec![ExprOrSpread {
-                        spread: None,
-                        expr: condition,
-                    }],
-                    type_args: None,
-                    ctxt: self.syntax_context,
-                })
-            }
+            WrapperType::Assignment | WrapperType::Jsx => self.coerce_to_boolean(condition, span),
+        }
+    }
+
+    /// Wraps a ternary test in `Boolean(…)` or `!!…` per `booleanStrategy`.
+    /// The ternary only looks at truthiness, so tests that must keep their
+    /// call shape are left bare.
+    fn coerce_to_boolean(&self, condition: Box<Expr>, span: swc_core::common::Span) -> Expr {
+        if Self::is_react_use_call(&condition) || Self::is_boolean_expr(&condition) {
+            return *condition;
+        }
+
+        match self.config.boolean_strategy {
+            BooleanStrategy::Boolean => {}
+            BooleanStrategy::BangBang => return *Self::negate_condition(Self::negate_condition(condition)),
+            BooleanStrategy::None => return *condition,
+        }
+        self.codegen.make_coercion_call(self.boolean_ident.clone(), condition, span, self.syntax_context)
+    }
+
+    /// Expressions that always evaluate to a boolean: comparisons, `!x`,
+    /// boolean literals, and `&&` / `||` / `?:` over those.
+    fn is_boolean_expr(expr: &Expr) -> bool {
+        match expr {
+            Expr::Paren(paren) => Self::is_boolean_expr(&paren.expr),
+            Expr::Lit(Lit::Bool(_)) => true,
+            Expr::Unary(UnaryExpr { op: UnaryOp::Bang, .. }) => true,
+            Expr::Bin(bin) => match bin.op {
+                BinaryOp::EqEq
+                | BinaryOp::NotEq
+                | BinaryOp::EqEqEq
+                | BinaryOp::NotEqEq
+                | BinaryOp::Lt
+                | BinaryOp::LtEq
+                | BinaryOp::Gt
+                | BinaryOp::GtEq
+                | BinaryOp::InstanceOf
+                | BinaryOp::In => true,
+                BinaryOp::LogicalAnd | BinaryOp::LogicalOr => {
+                    Self::is_boolean_expr(&bin.left) && Self::is_boolean_expr(&bin.right)
+                }
+                _ => false,
+            },
+            Expr::Cond(cond) => Self::is_boolean_expr(&cond.cons) && Self::is_boolean_expr(&cond.alt),
+            _ => false,
+        }
+    }
+
+    /// React 19 `use(resource)` / `React.use(resource)`. Keeping it as the
+    /// direct test means tooling that tracks `use` call sites (e.g. the React
+    /// Compiler) sees it exactly as written.
+    fn is_react_use_call(expr: &Expr) -> bool {
+        let Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) = expr else {
+            return false;
         };
+        match &**callee {
+            Expr::Ident(ident) => ident.sym == *USE_HOOK,
+            Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(prop), .. }) => {
+                prop.sym == *USE_HOOK && matches!(&**obj, Expr::Ident(obj) if obj.sym == *REACT_NAMESPACE)
+            }
+            _ => false,
+        }
+    }
+
+    fn create_conditional_expr(&self, condition: Box<Expr>, children: Vec<JSXElementChild>, fallback: Option<Box<Expr>>, span: swc_core::common::Span) -> Expr {
+        let rendered = self.branch_expr(children, span);
+        self.create_conditional_of(condition, rendered, fallback, span)
+    }
 
-        let conditional_expr = Expr::Cond(CondExpr {
+    /// `condition ? rendered : fallback` in the configured output shape.
+    fn create_conditional_of(&self, condition: Box<Expr>, rendered: Expr, fallback: Option<Box<Expr>>, span: Span) -> Expr {
+        match self.evaluate_static_condition(&condition) {
+            Some(true) => return rendered,
+            Some(false) => return *fallback.unwrap_or_else(|| Box::new(self.fallback_at(span))),
+            None => {}
+        }
+
+        if self.config.output == OutputMode::LogicalAnd && fallback.is_none() {
+            // `&&` 会把假值本身渲染出来（如 0），所以任何上下文都要转成布尔值
+            let test = self.coerce_to_boolean(condition, span);
+            return Expr::Bin(BinExpr {
+                span,
+                op: BinaryOp::LogicalAnd,
+                left: Self::paren_low_precedence(Box::new(test)),
+                right: Box::new(rendered),
+            });
+        }
+
+        self.annotate_conditional(self.layout_conditional(self.codegen.make_conditional_child(
+            fixer::paren_test(Box::new(self.create_test_expr(condition, span))),
+            Box::new(rendered),
+            fallback.unwrap_or_else(|| Box::new(self.fallback_at(span))),
             span,
-            test: Box::new(test_expr),
-            cons: Box::new(Expr::JSXFragment(fragment)),
-            alt: Box::new(self.null_expr.clone()),
-        });
+        )))
+    }
 
-        match current_context {
-            WrapperType::Jsx => {
-                JSXElement {
-                    span,
-                    opening: JSXOpeningElement {
-                        span,
-                        name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                        attrs: vec![],
-                        self_closing: false,
-                        type_args: None,
+    fn create_conditional_jsx(&self, condition: Box<Expr>, children: Vec<JSXElementChild>, fallback: Option<Box<Expr>>, span: swc_core::common::Span) -> JSXElement {
+        let conditional_expr = self.create_conditional_expr(condition, children, fallback, span);
+        self.wrap_expr_for_context(conditional_expr, span)
+    }
+
+    /// With `expressionContainers`, takes the single child out of a fragment
+    /// that only holds the result of lowering a control tag, so `<Condition>`
+    /// becomes `{cond ? … : null}` instead of a wrapped one. `lowered` is
+    /// false when the source already wrote that fragment.
+    fn take_fragment_holder(&self, element: &mut JSXElement, lowered: bool) -> Option<JSXElementChild> {
+        let is_holder = lowered
+            && element.opening.attrs.is_empty()
+            && element.children.len() == 1
+            && element.opening.name.eq_ignore_span(&self.fragment_name);
+        is_holder.then(|| element.children.pop()).flatten()
+    }
+
+    /// Generated nodes carry the span of the markup they replace, so source
+    /// maps and devtools point back at the original tag.
+    fn fragment_name_at(&self, span: Span) -> JSXElementName {
+        Self::respan_element_name(self.fragment_name.clone(), span)
+    }
+
+    fn placeholder_name_at(&self, span: Span) -> JSXElementName {
+        JSXElementName::Ident(Ident { span, ..self.condition_placeholder_ident.clone() })
+    }
+
+    fn respan_element_name(name: JSXElementName, span: Span) -> JSXElementName {
+        match name {
+            JSXElementName::Ident(ident) => JSXElementName::Ident(Ident { span, ..ident }),
+            JSXElementName::JSXMemberExpr(member) => {
+                let obj = match member.obj {
+                    JSXObject::Ident(ident) => JSXObject::Ident(Ident { span, ..ident }),
+                    JSXObject::JSXMemberExpr(inner) => match Self::respan_element_name(JSXElementName::JSXMemberExpr(*inner), span) {
+                        JSXElementName::JSXMemberExpr(inner) => JSXObject::JSXMemberExpr(Box::new(inner)),
+                        _ => unreachable!(),
                     },
-                    children: vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
-                        span,
-                        expr: JSXExpr::Expr(Box::new(conditional_expr)),
-                    })],
-                    closing: Some(JSXClosingElement {
-                        span,
-                        name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                    }),
-                }
+                };
+                JSXElementName::JSXMemberExpr(JSXMemberExpr { span, obj, prop: IdentName { span, ..member.prop } })
             }
+            name => name,
+        }
+    }
+
+    /// Wraps a lowered expression so it can stand in for the original element:
+    /// a `React.Fragment` holder in JSX, or a placeholder the post pass unwraps.
+    fn wrap_expr_for_context(&self, conditional_expr: Expr, span: swc_core::common::Span) -> JSXElement {
+        match self.get_current_context() {
+            WrapperType::Jsx => self.codegen.make_fragment(self.fragment_name_at(span), conditional_expr, span),
             WrapperType::Return | WrapperType::Assignment => {
                 JSXElement {
                     span,
                     opening: JSXOpeningElement {
                         span,
-                        name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
+                        name: self.placeholder_name_at(span),
                         attrs: vec![],
                         self_closing: false,
                         type_args: None,
@@ -254,33 +1648,149 @@ impl TransformVisitor {
                     })],
                     closing: Some(JSXClosingElement {
                         span,
-                        name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
+                        name: self.placeholder_name_at(span),
                     }),
                 }
             }
         }
     }
 
+    /// Fragment reachable through the file's `react` (or target) imports: a named
+    /// `Fragment` import, else `<Default>.Fragment` / `<Namespace>.Fragment`,
+    /// else the shorthand marker.
+    fn detect_fragment_name(&self, module: &Module) -> JSXElementName {
+        let specifiers = module.body.iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if import.src.value == *self.codegen.fragment_module() && !import.type_only => {
+                    Some(&import.specifiers)
+                }
+                _ => None,
+            })
+            .flatten();
+
+        let mut namespace = None;
+        for specifier in specifiers {
+            match specifier {
+                ImportSpecifier::Named(named) if !named.is_type_only => {
+                    let imported = match &named.imported {
+                        Some(ModuleExportName::Ident(imported)) => &imported.sym,
+                        Some(ModuleExportName::Str(imported)) => &imported.value,
+                        None => &named.local.sym,
+                    };
+                    if imported == FRAGMENT_EXPORT {
+                        return JSXElementName::Ident(named.local.clone());
+                    }
+                }
+                ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
+                | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
+                    namespace.get_or_insert(local);
+                }
+                _ => {}
+            }
+        }
+
+        match namespace {
+            Some(local) => JSXElementName::JSXMemberExpr(JSXMemberExpr {
+                span: local.span,
+                obj: JSXObject::Ident(local.clone()),
+                prop: IdentName::new(FRAGMENT_EXPORT.into(), local.span),
+            }),
+            None => Self::fragment_element_name(FRAGMENT_SHORTHAND, swc_core::common::DUMMY_SP, self.syntax_context),
+        }
+    }
+
+    /// `"React.Fragment"` → `<React.Fragment>`, `"auto"` → the shorthand marker.
+    fn fragment_element_name(fragment: &str, span: Span, ctxt: SyntaxContext) -> JSXElementName {
+        let name = if fragment == FRAGMENT_SHORTHAND { FRAGMENT_SHORTHAND_PLACEHOLDER } else { fragment };
+        let mut parts = name.split('.');
+        let root = Ident::new(parts.next().unwrap_or_default().into(), span, ctxt);
+        parts.fold(JSXElementName::Ident(root), |name, prop| {
+            let obj = match name {
+                JSXElementName::Ident(ident) => JSXObject::Ident(ident),
+                JSXElementName::JSXMemberExpr(member) => JSXObject::JSXMemberExpr(Box::new(member)),
+                JSXElementName::JSXNamespacedName(_) => unreachable!(),
+            };
+            JSXElementName::JSXMemberExpr(JSXMemberExpr { span, obj, prop: IdentName::new(prop.into(), span) })
+        })
+    }
+
+    /// The tag to dispatch on and the identifier that brings it into scope:
+    /// `<Condition>` yields `Condition` for both, `<Flow.Condition>` yields
+    /// `Condition` bound through `Flow` when `Flow` is a configured namespace.
+    fn control_tag_name<'a>(&self, name: &'a JSXElementName) -> Option<(&'a Atom, &'a Ident)> {
+        match name {
+            JSXElementName::Ident(ident) => Some((&ident.sym, ident)),
+            JSXElementName::JSXMemberExpr(JSXMemberExpr { obj: JSXObject::Ident(namespace), prop, .. })
+                if self.atoms.tag_namespace_atoms.contains(&namespace.sym) =>
+            {
+                Some((&prop.sym, namespace))
+            }
+            _ => None,
+        }
+    }
+
+    /// Member of `<Switch.X>`, or of `<Flow.Switch.X>` under a configured namespace.
+    fn switch_member_prop<'a>(&self, element: &'a JSXElement) -> Option<&'a Atom> {
+        match &element.opening.name {
+            JSXElementName::JSXMemberExpr(member) => match &member.obj {
+                JSXObject::Ident(obj) if obj.sym == self.atoms.switch_atom => Some(&member.prop.sym),
+                JSXObject::JSXMemberExpr(switch) if switch.prop.sym == self.atoms.switch_atom
+                    && matches!(&switch.obj, JSXObject::Ident(namespace) if self.atoms.tag_namespace_atoms.contains(&namespace.sym)) =>
+                {
+                    Some(&member.prop.sym)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn is_compat_tag(&self, element: &JSXElement, tag: impl Fn(&CompatAtoms) -> &Atom) -> bool {
+        match (self.atoms.compat(), &element.opening.name) {
+            (Some(compat), JSXElementName::Ident(ident)) => ident.sym == *tag(compat),
+            _ => false,
+        }
+    }
+
     fn is_switch_case_element(&self, element: &JSXElement) -> bool {
-        matches!(&element.opening.name, 
-            JSXElementName::JSXMemberExpr(member) 
-                if matches!(&member.obj, JSXObject::Ident(obj) 
-                    if obj.sym.as_ref() == "Switch" && member.prop.sym.as_ref() == "Case"))
+        self.switch_member_prop(element).is_some_and(|prop| *prop == self.atoms.case_atom)
+            || self.is_compat_tag(element, |compat| &compat.when_tag)
+    }
+
+    /// `<Switch.Default>` / `<Switch.Else>` (or configured aliases).
+    fn is_switch_else_alias(&self, element: &JSXElement) -> bool {
+        self.switch_member_prop(element).is_some_and(|prop| self.atoms.else_alias_atoms.contains(prop))
+            || self.is_compat_tag(element, |compat| &compat.otherwise_tag)
     }
 
     #[inline]
     fn has_switch_case_children(&self, children: &[JSXElementChild]) -> bool {
-        children.iter().any(|child| matches!(child, JSXElementChild::JSXElement(elem) if self.is_switch_case_element(elem)))
+        children.iter().any(|child| matches!(child, JSXElementChild::JSXElement(elem)
+            if self.is_switch_case_element(elem) || self.is_switch_else_alias(elem)))
     }
 
     fn extract_short_circuit_attr(&self, attrs: &[JSXAttrOrSpread]) -> bool {
+        self.has_flag(attrs, &self.atoms.short_circuit_atom)
+    }
+
+    pub(crate) fn has_flag(&self, attrs: &[JSXAttrOrSpread], atom: &Atom) -> bool {
         attrs.iter().any(|attr| {
             matches!(attr, JSXAttrOrSpread::JSXAttr(jsx_attr) 
                 if matches!(&jsx_attr.name, JSXAttrName::Ident(name) 
-                    if name.sym == self.short_circuit_atom))
+                    if name.sym == *atom))
         })
     }
 
+    /// An expression as JSX child: elements stay elements, anything else
+    /// goes into `{…}`.
+    fn expr_to_child(expr: Expr) -> JSXElementChild {
+        match expr {
+            Expr::JSXElement(element) => JSXElementChild::JSXElement(element),
+            Expr::JSXFragment(fragment) => JSXElementChild::JSXFragment(fragment),
+            expr => JSXElementChild::JSXExprContainer(JSXExprContainer { span: expr.span(), expr: JSXExpr::Expr(Box::new(expr)) }),
+        }
+    }
+
     #[inline]
     fn is_non_whitespace_child(child: &JSXElementChild) -> bool {
         match child {
@@ -308,174 +1818,131 @@ impl TransformVisitor {
         }
     }
 
-    fn create_switch_transformation(&self, children: Vec<JSXElementChild>, short_circuit: bool, span: swc_core::common::Span) -> JSXElement {
+    /// Runs `f` where a lowered tag is an operand of an expression, such as
+    /// `cond && <Condition>` or `a ? <Switch> : null`: JSX children become
+    /// the assignment context, return and assignment stay as they are.
+    #[inline]
+    fn with_expression_context<T, F>(&mut self, f: F) -> T
+    where F: FnOnce(&mut Self) -> T {
+        if self.current_context != WrapperType::Jsx {
+            f(self)
+        } else {
+            let prev_context = std::mem::replace(&mut self.current_context, WrapperType::Assignment);
+            let result = f(self);
+            self.current_context = prev_context;
+            result
+        }
+    }
+
+    /// `lazy` keeps every branch inside its ternary, so markup of cases that
+    /// do not match is never created; it only affects `hoistSharedBranches`,
+    /// the one place branch markup is built ahead of the test.
+    fn create_switch_transformation(&mut self, children: Vec<JSXElementChild>, short_circuit: bool, lazy: bool, value: Option<Box<Expr>>, span: swc_core::common::Span) -> JSXElement {
         let mut switch_cases: Vec<_> = Vec::new();
-        let mut else_case: Option<Vec<JSXElementChild>> = None;
+        let mut else_candidates = Vec::new();
+
+        // 值匹配模式：复杂的 value 表达式提升为临时变量，保证只求值一次
+        let (subject, hoisted_value) = match value {
+            Some(value) if Self::is_simple_subject(&value) => (Some(value), None),
+            Some(value) => {
+                let temp = Ident::new(self.names.named(SWITCH_VALUE_TEMP), span, self.syntax_context);
+                (Some(Box::new(Expr::Ident(temp.clone()))), Some((temp, value)))
+            }
+            None => (None, None),
+        };
 
+        let mut else_shows = Vec::new();
+        let mut case_orders = Vec::new();
+        let mut error_fallback = None;
         for child in children {
             if let JSXElementChild::JSXElement(element) = child {
                 if self.is_switch_case_element(&element) {
-                    if let Some(condition_expr) = self.extract_condition_from_attrs(&element.opening.attrs) {
-                        switch_cases.push((condition_expr, element.children));
+                    if self.is_error_case(&element) {
+                        error_fallback.get_or_insert(element.children);
+                    } else if let Some(condition_expr) = self.extract_case_condition(&element.opening.attrs, subject.as_deref()) {
+                        if self.has_else_attr(&element.opening.attrs) {
+                            self.report_case_with_if_and_else(&element);
+                        }
+                        if let Some(if_condition) = self.extract_condition_from_attrs(&element.opening.attrs) {
+                            self.lint_constant_condition(&if_condition);
+                        }
+                        self.note_nullish_condition(&condition_expr);
+                        else_shows.push(self.extract_attr_value(&element.opening.attrs, &self.atoms.else_show_atom));
+                        case_orders.push(self.case_order(&element));
+                        let case_children = match self.case_key(&element, switch_cases.len(), short_circuit) {
+                            Some(key) => self.keyed_case_children(element.children, key, element.span),
+                            None => element.children,
+                        };
+                        switch_cases.push((condition_expr, case_children));
                     } else if self.has_else_attr(&element.opening.attrs) {
-                        else_case = Some(element.children);
+                        else_candidates.push(*element);
+                    } else {
+                        self.report_invalid_condition(&element, &self.atoms.if_atom.clone());
                     }
+                } else if self.is_switch_else_alias(&element) {
+                    else_candidates.push(*element);
                 }
             }
         }
+        // 只有一个 case 时，else 分支可以直接写在它的 elseShow 上
+        let mut short_circuit = short_circuit;
+        let else_show = match (else_shows.as_mut_slice(), else_candidates.is_empty()) {
+            ([else_show], true) => else_show.take(),
+            _ => None,
+        };
+        let mut else_case = match else_show {
+            Some(else_show) => {
+                short_circuit = true;
+                Some(vec![Self::expr_to_child(*else_show)])
+            }
+            None => self.pick_else_case(else_candidates),
+        };
+        // order 只决定短路求值的先后，并行模式按书写顺序渲染
+        if short_circuit {
+            self.sort_cases_by_order(&mut switch_cases, case_orders);
+        }
+
+        self.fold_env_cases(&mut switch_cases, &mut else_case, short_circuit);
+
+        if self.config.hoist_shared_branches && !lazy {
+            let mut branches: Vec<_> = switch_cases.iter_mut()
+                .map(|(_, children)| children)
+                .chain(else_case.iter_mut())
+                .collect();
+            if branches.len() > 1 {
+                self.hoist_shared_branch_children(&mut branches);
+            }
+        }
 
         if switch_cases.is_empty() && else_case.is_none() {
-            return JSXElement {
-                span,
-                opening: JSXOpeningElement {
-                    span,
-                    name: JSXElementName::Ident(Ident::new(REACT_FRAGMENT.into(), span, self.syntax_context)),
-                    attrs: vec![],
-                    self_closing: false,
-                    type_args: None,
-                },
-                children: vec![],
-                closing: Some(JSXClosingElement {
-                    span,
-                    name: JSXElementName::Ident(Ident::new(REACT_FRAGMENT.into(), span, self.syntax_context)),
-                }),
-            };
+            return self.codegen.make_fragment_children(self.fragment_name_at(span), vec![], span);
         }
 
         // 如果只有 else case，直接返回 else case 的内容
         if switch_cases.is_empty() && else_case.is_some() {
-            let else_children = else_case.unwrap();
-            let current_context = self.get_current_context();
-            
-            let non_whitespace_children = Self::filter_non_whitespace_children(else_children);
-            
-            if non_whitespace_children.len() == 1 {
-                let children = non_whitespace_children;
-                let first_child = children.into_iter().next().unwrap();
-                if let JSXElementChild::JSXElement(element) = first_child {
-                    match current_context {
-                        WrapperType::Return | WrapperType::Assignment => {
-                            return JSXElement {
-                                span,
-                                opening: JSXOpeningElement {
-                                    span,
-                                    name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                                    attrs: vec![],
-                                    self_closing: false,
-                                    type_args: None,
-                                },
-                                children: vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
-                                    span,
-                                    expr: JSXExpr::Expr(Box::new(Expr::JSXElement(element))),
-                                })],
-                                closing: Some(JSXClosingElement {
-                                    span,
-                                    name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                                }),
-                            };
-                        }
-                        WrapperType::Jsx => {
-                            return *element;
-                        }
-                    }
-                } else {
+            let mut children = Self::filter_non_whitespace_children(else_case.unwrap_or_default());
+            let is_jsx = matches!(self.get_current_context(), WrapperType::Jsx);
+            if children.len() == 1 {
+                let expr = match children.pop().expect("checked above") {
+                    JSXElementChild::JSXElement(element) if is_jsx => return *element,
+                    JSXElementChild::JSXElement(element) => Expr::JSXElement(element),
                     // 不是单个JSX元素，使用fragment
-                    let fragment = JSXFragment {
+                    child if is_jsx => return self.codegen.make_fragment_children(self.fragment_name_at(span), vec![child], span),
+                    child => Expr::JSXFragment(JSXFragment {
                         span,
                         opening: JSXOpeningFragment { span },
-                        children: vec![first_child],
+                        children: vec![child],
                         closing: JSXClosingFragment { span },
-                    };
-                    
-                    match current_context {
-                        WrapperType::Return | WrapperType::Assignment => {
-                            return JSXElement {
-                                span,
-                                opening: JSXOpeningElement {
-                                    span,
-                                    name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                                    attrs: vec![],
-                                    self_closing: false,
-                                    type_args: None,
-                                },
-                                children: vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
-                                    span,
-                                    expr: JSXExpr::Expr(Box::new(Expr::JSXFragment(fragment))),
-                                })],
-                                closing: Some(JSXClosingElement {
-                                    span,
-                                    name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                                }),
-                            };
-                        }
-                        WrapperType::Jsx => {
-                            return JSXElement {
-                                span,
-                                opening: JSXOpeningElement {
-                                    span,
-                                    name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                                    attrs: vec![],
-                                    self_closing: false,
-                                    type_args: None,
-                                },
-                                children: fragment.children,
-                                closing: Some(JSXClosingElement {
-                                    span,
-                                    name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                                }),
-                            };
-                        }
-                    }
-                }
-            } else {
-                // 多个子元素，使用fragment
-                let fragment = JSXFragment {
-                    span,
-                    opening: JSXOpeningFragment { span },
-                    children: non_whitespace_children,
-                    closing: JSXClosingFragment { span },
+                    }),
                 };
-                
-                match current_context {
-                    WrapperType::Return | WrapperType::Assignment => {
-                        return JSXElement {
-                            span,
-                            opening: JSXOpeningElement {
-                                span,
-                                name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                                attrs: vec![],
-                                self_closing: false,
-                                type_args: None,
-                            },
-                            children: vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
-                                span,
-                                expr: JSXExpr::Expr(Box::new(Expr::JSXFragment(fragment))),
-                            })],
-                            closing: Some(JSXClosingElement {
-                                span,
-                                name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                            }),
-                        };
-                    }
-                    WrapperType::Jsx => {
-                        return JSXElement {
-                            span,
-                            opening: JSXOpeningElement {
-                                span,
-                                name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                                attrs: vec![],
-                                self_closing: false,
-                                type_args: None,
-                            },
-                            children: fragment.children,
-                            closing: Some(JSXClosingElement {
-                                span,
-                                name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                            }),
-                        };
-                    }
-                }
+                return self.wrap_expr_for_context(expr, span);
             }
+            // 多个子元素，使用fragment（或 branchWrapper）
+            return if is_jsx {
+                self.codegen.make_fragment_children(self.fragment_name_at(span), children, span)
+            } else {
+                self.wrap_expr_for_context(self.branch_expr(children, span), span)
+            };
         }
 
         let current_context = self.get_current_context();
@@ -483,12 +1950,67 @@ impl TransformVisitor {
         // 或者在 return/assignment 上下文中只有一个 case 且没有 else 时
         let effective_short_circuit = short_circuit || 
             (matches!(current_context, WrapperType::Return | WrapperType::Assignment) && switch_cases.len() <= 1 && else_case.is_none());
-        
-        if effective_short_circuit {
+
+        // 融合只在不改变渲染结果时进行：没有 else，且短路模式下只能是最后一个 case
+        if self.config.fuse_case_conditions && else_case.is_none() {
+            let skip = if effective_short_circuit { switch_cases.len().saturating_sub(1) } else { 0 };
+            for case in switch_cases.iter_mut().skip(skip) {
+                self.fuse_case_condition(case);
+            }
+        }
+
+        let mut hoisted_conditions = Vec::new();
+        let result = if effective_short_circuit {
             self.create_short_circuit_switch(switch_cases, else_case, span)
         } else {
+            self.report_loop_captures(&switch_cases);
+            // else 的测试会重复每个 case 条件，有副作用的条件只求值一次
+            if self.config.hoist_case_conditions && else_case.is_some() {
+                for (condition, _) in switch_cases.iter_mut().filter(|(condition, _)| !Self::is_simple_subject(condition)) {
+                    let temp = Ident::new(self.names.numbered(CASE_CONDITION_TEMP), condition.span(), self.syntax_context);
+                    let condition = std::mem::replace(condition, Box::new(Expr::Ident(temp.clone())));
+                    hoisted_conditions.push((temp, condition));
+                }
+            }
             self.create_parallel_switch(switch_cases, else_case, span)
+        };
+
+        let result = self.hoist_bindings(result, hoisted_conditions, span);
+        let result = match hoisted_value {
+            Some(binding) => self.hoist_bindings(result, vec![binding], span),
+            None => result,
+        };
+        match error_fallback {
+            Some(fallback) => self.wrap_error_boundary(result, fallback, span),
+            None => result,
+        }
+    }
+
+    /// Turns `<Switch.Case if={a}><Condition if={b}>…</Condition></Switch.Case>`
+    /// into a case testing `a && b` with the inner Condition's children.
+    fn fuse_case_condition(&self, case: &mut (Box<Expr>, Vec<JSXElementChild>)) {
+        let mut non_whitespace = case.1.iter().filter(|child| Self::is_non_whitespace_child(child));
+        let inner = match (non_whitespace.next(), non_whitespace.next()) {
+            (Some(JSXElementChild::JSXElement(element)), None) => element,
+            _ => return,
+        };
+        if !matches!(self.control_tag_name(&inner.opening.name),
+            Some((tag, binding)) if *tag == self.atoms.condition_atom && self.is_lowerable_tag(binding)) || self.is_raw_element(inner) {
+            return;
         }
+        let Some(inner_condition) = self.extract_condition_from_attrs(&inner.opening.attrs) else {
+            return;
+        };
+        let inner_children = inner.children.clone();
+
+        let case_condition = std::mem::take(&mut case.0);
+        *case.0 = Expr::Bin(BinExpr {
+            span: inner.span,
+            op: BinaryOp::LogicalAnd,
+            left: Self::paren_low_precedence(case_condition),
+            right: Self::paren_low_precedence(inner_condition),
+        });
+        case.1 = inner_children;
     }
 
     fn create_short_circuit_switch(&self, switch_cases: Vec<(Box<Expr>, Vec<JSXElementChild>)>, else_case: Option<Vec<JSXElementChild>>, span: swc_core::common::Span) -> JSXElement {
@@ -500,7 +2022,7 @@ impl TransformVisitor {
                     Box::new(Expr::JSXElement(element))
                 } else {
                     let fragment = JSXFragment {
-                        span,
+                        span: first_child.span(),
                         opening: JSXOpeningFragment { span },
                         children: vec![first_child],
                         closing: JSXClosingFragment { span },
@@ -508,34 +2030,17 @@ impl TransformVisitor {
                     Box::new(Expr::JSXFragment(fragment))
                 }
             } else {
-                let fragment = JSXFragment {
-                    span,
-                    opening: JSXOpeningFragment { span },
-                    children: non_whitespace_children,
-                    closing: JSXClosingFragment { span },
-                };
-                Box::new(Expr::JSXFragment(fragment))
+                Box::new(self.branch_expr(non_whitespace_children, span))
             }
         } else {
-            Box::new(self.null_expr.clone())
+            Box::new(self.fallback_at(span))
         };
         let current_context = self.get_current_context();
         
         for (condition, children) in switch_cases.into_iter().rev() {
             let test_expr = match current_context {
                 WrapperType::Return | WrapperType::Assignment => *condition,
-                WrapperType::Jsx => {
-                    Expr::Call(CallExpr {
-                        span,
-                        callee: Callee::Expr(Box::new(Expr::Ident(self.boolean_ident.clone()))),
-                        args: vec![ExprOrSpread {
-                            spread: None,
-                            expr: condition,
-                        }],
-                        type_args: None,
-                        ctxt: self.syntax_context,
-                    })
-                }
+                WrapperType::Jsx => self.coerce_to_boolean(condition, span),
             };
 
             let non_whitespace_children = Self::filter_non_whitespace_children(children);
@@ -546,7 +2051,7 @@ impl TransformVisitor {
                     Expr::JSXElement(element)
                 } else {
                     let fragment = JSXFragment {
-                        span,
+                        span: first_child.span(),
                         opening: JSXOpeningFragment { span },
                         children: vec![first_child],
                         closing: JSXClosingFragment { span },
@@ -554,65 +2059,18 @@ impl TransformVisitor {
                     Expr::JSXFragment(fragment)
                 }
             } else {
-                let fragment = JSXFragment {
-                    span,
-                    opening: JSXOpeningFragment { span },
-                    children: non_whitespace_children,
-                    closing: JSXClosingFragment { span },
-                };
-                Expr::JSXFragment(fragment)
+                self.branch_expr(non_whitespace_children, span)
             };
 
-            result_expr = Box::new(Expr::Cond(CondExpr {
+            result_expr = Box::new(self.codegen.make_conditional_child(
+                fixer::paren_test(Box::new(test_expr)),
+                Box::new(fragment_expr),
+                result_expr,
                 span,
-                test: Box::new(test_expr),
-                cons: Box::new(fragment_expr),
-                alt: result_expr,
-            }));
-        }
-
-        match current_context {
-            WrapperType::Return | WrapperType::Assignment => {
-                JSXElement {
-                    span,
-                    opening: JSXOpeningElement {
-                        span,
-                        name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                        attrs: vec![],
-                        self_closing: false,
-                        type_args: None,
-                    },
-                    children: vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
-                        span,
-                        expr: JSXExpr::Expr(result_expr),
-                    })],
-                    closing: Some(JSXClosingElement {
-                        span,
-                        name: JSXElementName::Ident(self.condition_placeholder_ident.clone()),
-                    }),
-                }
-            }
-            WrapperType::Jsx => {
-                JSXElement {
-                    span,
-                    opening: JSXOpeningElement {
-                        span,
-                        name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                        attrs: vec![],
-                        self_closing: false,
-                        type_args: None,
-                    },
-                    children: vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
-                        span,
-                        expr: JSXExpr::Expr(result_expr),
-                    })],
-                    closing: Some(JSXClosingElement {
-                        span,
-                        name: JSXElementName::Ident(self.react_fragment_ident.clone()),
-                    }),
-                }
-            }
+            ));
         }
+        let result_expr = self.annotate_conditional(self.layout_conditional(*result_expr));
+        self.wrap_expr_for_context(result_expr, span)
     }
 
     fn create_parallel_switch(&self, switch_cases: Vec<(Box<Expr>, Vec<JSXElementChild>)>, else_case: Option<Vec<JSXElementChild>>, span: swc_core::common::Span) -> JSXElement {
@@ -626,34 +2084,22 @@ impl TransformVisitor {
             // 克隆条件用于后续 else case 的计算
             all_conditions.push(condition.clone());
 
-            let fragment = JSXFragment {
-                span,
-                opening: JSXOpeningFragment { span },
-                children,
-                closing: JSXClosingFragment { span },
-            };
-
-            let conditional_expr = Expr::Cond(CondExpr {
+            let conditional_expr = self.codegen.make_conditional_child(
+                fixer::paren_test(condition),
+                Box::new(self.branch_expr(children, span)),
+                Box::new(self.fallback_at(span)),
                 span,
-                test: condition,
-                cons: Box::new(Expr::JSXFragment(fragment)),
-                alt: Box::new(self.null_expr.clone()),
-            });
+            );
 
             result_children.push(JSXElementChild::JSXExprContainer(JSXExprContainer {
                 span,
-                expr: JSXExpr::Expr(Box::new(conditional_expr)),
+                expr: JSXExpr::Expr(Box::new(self.annotate_conditional(self.layout_conditional(conditional_expr)))),
             }));
         }
 
         // 在非短路模式下，else case 只在所有条件都不满足时显示
         if let Some(else_children) = else_case {
-            let fragment_expr = JSXFragment {
-                span,
-                opening: JSXOpeningFragment { span },
-                children: else_children,
-                closing: JSXClosingFragment { span },
-            };
+            let fragment_expr = self.branch_expr(else_children, span);
 
             // 创建 !condition1 && !condition2 && ... 的表达式
             let else_condition = if all_conditions.is_empty() {
@@ -685,145 +2131,284 @@ impl TransformVisitor {
                 combined_condition
             };
 
-            let else_conditional_expr = Expr::Cond(CondExpr {
+            let else_conditional_expr = self.codegen.make_conditional_child(
+                fixer::paren_test(else_condition),
+                Box::new(fragment_expr),
+                Box::new(self.fallback_at(span)),
                 span,
-                test: else_condition,
-                cons: Box::new(Expr::JSXFragment(fragment_expr)),
-                alt: Box::new(self.null_expr.clone()),
-            });
+            );
 
             result_children.push(JSXElementChild::JSXExprContainer(JSXExprContainer {
                 span,
-                expr: JSXExpr::Expr(Box::new(else_conditional_expr)),
+                expr: JSXExpr::Expr(Box::new(self.annotate_conditional(self.layout_conditional(else_conditional_expr)))),
             }));
         }
 
-        JSXElement {
-            span,
-            opening: JSXOpeningElement {
-                span,
-                name: JSXElementName::Ident(Ident::new(REACT_FRAGMENT.into(), span, self.syntax_context)),
-                attrs: vec![],
-                self_closing: false,
-                type_args: None,
-            },
-            children: result_children,
-            closing: Some(JSXClosingElement {
-                span,
-                name: JSXElementName::Ident(Ident::new(REACT_FRAGMENT.into(), span, self.syntax_context)),
-            }),
-        }
+        self.codegen.make_fragment_children(self.fragment_name_at(span), result_children, span)
     }
 }
 
-pub struct PostTransformVisitor;
+/// Unwraps the placeholders one transform instance leaves behind and turns
+/// shorthand markers into `<>…</>`. The default instance is the one for the
+/// default vocabulary; use [`TransformFactory::post_visitor`] otherwise.
+pub struct PostTransformVisitor {
+    condition_placeholder: Atom,
+}
+
+impl Default for PostTransformVisitor {
+    fn default() -> Self {
+        Self::new(CONDITION_PLACEHOLDER.into())
+    }
+}
 
 impl PostTransformVisitor {
-    fn unwrap_single_element_fragments(&mut self, expr: Expr) -> Expr {
-        match expr {
-            Expr::Cond(mut cond_expr) => {
-                if let Expr::JSXFragment(fragment) = &*cond_expr.cons {
-                    let non_whitespace_count = fragment.children.iter()
-                        .filter(|child| TransformVisitor::is_non_whitespace_child(child))
-                        .count();
-                    
-                    if non_whitespace_count == 1 {
-                        if let Some(child) = fragment.children.iter()
-                            .find(|child| TransformVisitor::is_non_whitespace_child(child)) {
-                            if let JSXElementChild::JSXElement(element) = child {
-                                cond_expr.cons = Box::new(Expr::JSXElement((*element).clone()));
-                            }
-                        }
+    pub(crate) fn new(condition_placeholder: Atom) -> Self {
+        Self { condition_placeholder }
+    }
+
+    fn is_fragment_shorthand(element: &JSXElement) -> bool {
+        matches!(&element.opening.name, JSXElementName::Ident(ident) if ident.sym.as_ref() == FRAGMENT_SHORTHAND_PLACEHOLDER)
+    }
+
+    fn into_fragment(element: JSXElement) -> JSXFragment {
+        JSXFragment {
+            span: element.span,
+            opening: JSXOpeningFragment { span: element.opening.span },
+            children: element.children,
+            closing: JSXClosingFragment { span: element.span },
+        }
+    }
+
+    fn unwrap_single_element_fragments(&mut self, expr: &mut Expr) {
+        if let Expr::Cond(cond_expr) = expr {
+            let single_element = match &mut *cond_expr.cons {
+                Expr::JSXFragment(fragment) => {
+                    let mut non_whitespace = fragment.children.iter_mut()
+                        .filter(|child| TransformVisitor::is_non_whitespace_child(child));
+                    match (non_whitespace.next(), non_whitespace.next()) {
+                        (Some(JSXElementChild::JSXElement(element)), None) => Some(element.take()),
+                        _ => None,
                     }
                 }
-                
-                cond_expr.alt = Box::new(self.unwrap_single_element_fragments(*cond_expr.alt));
-                
-                Expr::Cond(cond_expr)
+                _ => None,
+            };
+            if let Some(element) = single_element {
+                *cond_expr.cons = Expr::JSXElement(element);
             }
-            _ => expr,
+
+            self.unwrap_single_element_fragments(&mut cond_expr.alt);
         }
     }
+
+    /// The expression held by a placeholder element the transform emitted
+    /// for return and assignment contexts.
+    fn take_placeholder_expr(&mut self, element: &mut JSXElement) -> Option<Expr> {
+        let JSXElementName::Ident(ident) = &element.opening.name else {
+            return None;
+        };
+        let is_switch = match ident.sym.as_ref() {
+            _ if ident.sym == self.condition_placeholder => false,
+            sym if sym == SWITCH_PLACEHOLDER => true,
+            _ => return None,
+        };
+        let Some(JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(inner_expr), .. })) =
+            element.children.first_mut()
+        else {
+            return None;
+        };
+
+        let mut inner_expr = inner_expr.take();
+        if is_switch {
+            self.unwrap_single_element_fragments(&mut inner_expr);
+        }
+        // transformNested 的分支里还可能有占位元素
+        inner_expr.visit_mut_with(self);
+        Some(*inner_expr)
+    }
 }
 
-impl Fold for PostTransformVisitor {
-    fn fold_expr(&mut self, expr: Expr) -> Expr {
+impl VisitMut for PostTransformVisitor {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
         match expr {
             Expr::JSXElement(element) => {
-                if let JSXElementName::Ident(ident) = &element.opening.name {
-                    if ident.sym.as_ref() == CONDITION_PLACEHOLDER {
-                        if !element.children.is_empty() {
-                            if let JSXElementChild::JSXExprContainer(container) = &element.children[0] {
-                                if let JSXExpr::Expr(inner_expr) = &container.expr {
-                                    return (**inner_expr).clone();
-                                }
-                            }
-                        }
-                    } else if ident.sym.as_ref() == SWITCH_PLACEHOLDER {
-                        if !element.children.is_empty() {
-                            if let JSXElementChild::JSXExprContainer(container) = &element.children[0] {
-                                if let JSXExpr::Expr(inner_expr) = &container.expr {
-                                    return self.unwrap_single_element_fragments((**inner_expr).clone());
-                                }
-                            }
-                        }
-                    }
+                if let Some(inner_expr) = self.take_placeholder_expr(element) {
+                    *expr = inner_expr;
+                    return;
+                }
+                self.visit_mut_jsx_element(element);
+                if Self::is_fragment_shorthand(element) {
+                    *expr = Expr::JSXFragment(Self::into_fragment(*element.take()));
                 }
-                Expr::JSXElement(Box::new(self.fold_jsx_element(*element)))
             }
             Expr::Paren(paren_expr) => {
-                let inner = self.fold_expr(*paren_expr.expr);
-                match &inner {
-                    Expr::Cond(cond_expr) => {
-                        let needs_inner_parens = match cond_expr.cons.as_ref() {
-                            Expr::JSXElement(elem) => {
-                                elem.children.len() > 1 || 
-                                elem.children.iter().any(|child| match child {
-                                    JSXElementChild::JSXText(text) => text.value.contains('\n'),
-                                    _ => false,
-                                })
-                            },
-                            Expr::JSXFragment(_) => true,
-                            _ => false
-                        };
-                        
-                        if needs_inner_parens {
-                            if let Expr::Cond(mut new_cond) = inner {
-                                let span = new_cond.span;
-                                let cons = std::mem::take(&mut new_cond.cons);
-                                new_cond.cons = Box::new(Expr::Paren(ParenExpr {
-                                    span,
-                                    expr: cons,
-                                }));
-                                Expr::Cond(new_cond)
-                            } else {
-                                inner
-                            }
-                        } else {
-                            inner
-                        }
+                self.visit_mut_expr(&mut paren_expr.expr);
+                if let Expr::Cond(cond_expr) = &mut *paren_expr.expr {
+                    let needs_inner_parens = match cond_expr.cons.as_ref() {
+                        Expr::JSXElement(elem) => {
+                            elem.children.len() > 1 || 
+                            elem.children.iter().any(|child| match child {
+                                JSXElementChild::JSXText(text) => text.value.contains('\n'),
+                                _ => false,
+                            })
+                        },
+                        Expr::JSXFragment(_) => true,
+                        _ => false
+                    };
+
+                    if needs_inner_parens {
+                        let span = cond_expr.span;
+                        let cons = cond_expr.cons.take();
+                        *cond_expr.cons = Expr::Paren(ParenExpr {
+                            span,
+                            expr: cons,
+                        });
                     }
-                    _ => Expr::Paren(ParenExpr {
-                        span: paren_expr.span,
-                        expr: Box::new(inner),
-                    })
+                    *expr = *paren_expr.expr.take();
                 }
             }
-            _ => expr.fold_children_with(self),
+            // `flag && <Condition>` 降级后的三元表达式、嵌进条件链的三元 case 条件，
+            // 以及上面去掉括号后作为成员、调用对象的三元表达式都需要括号
+            Expr::Bin(_) | Expr::Cond(_) | Expr::Unary(_) | Expr::Await(_)
+            | Expr::Member(_) | Expr::Call(_) | Expr::New(_) | Expr::TaggedTpl(_) | Expr::OptChain(_)
+            | Expr::TsAs(_) | Expr::TsSatisfies(_) | Expr::TsNonNull(_) => {
+                expr.visit_mut_children_with(self);
+                fixer::fix_precedence(expr);
+            }
+            _ => expr.visit_mut_children_with(self),
+        }
+    }
+
+    fn visit_mut_jsx_element_child(&mut self, child: &mut JSXElementChild) {
+        match child {
+            JSXElementChild::JSXElement(element) if Self::is_fragment_shorthand(element) => {
+                self.visit_mut_jsx_element(element);
+                *child = JSXElementChild::JSXFragment(Self::into_fragment(*element.take()));
+            }
+            child => child.visit_mut_children_with(self),
         }
     }
 
+    fn visit_mut_jsx_element(&mut self, element: &mut JSXElement) {
+        element.opening.attrs.visit_mut_with(self);
+        element.children.visit_mut_with(self);
+    }
+}
+
+/// Builds the transform and post-transform passes with deterministic default
+/// settings, in the order they must run.
+pub fn test_pass() -> (impl VisitMut, impl VisitMut) {
+    test_pass_with_config(Config::default())
+}
 
+/// Same as [`test_pass`], with an explicit plugin config.
+pub fn test_pass_with_config(config: Config) -> (impl VisitMut, impl VisitMut) {
+    let factory = TransformFactory::new(config);
+    (factory.visitor(), factory.post_visitor())
+}
 
-    fn fold_jsx_element(&mut self, mut element: JSXElement) -> JSXElement {
-        element.children = element.children.fold_with(self);
-        element
+/// Runs every enabled stage over `program`, in pipeline order.
+pub fn transform_program(program: Program, config: Config) -> Program {
+    transform_program_with_host(program, config, TransformHost::default())
+}
+
+/// Facilities of the embedding host that some options depend on.
+#[derive(Default)]
+pub struct TransformHost {
+    /// Comment store of the program: read for JSX pragmas, written to by
+    /// `annotateBranches`.
+    pub comments: Option<Box<dyn Comments>>,
+    /// Resolves spans to files and lines.
+    pub source_map: Option<Lrc<SourceMapperDyn>>,
+    /// Environment variables visible to `process.env` folding.
+    pub env: HashMap<String, String>,
+    /// Mark the resolver gave unresolved (global) references; the injected
+    /// `Boolean` carries it so it never binds to a local `Boolean`. Unused
+    /// with `conditionHelper`, whose import provides the binding.
+    pub unresolved_mark: Option<Mark>,
+    /// Path of the file being transformed, for `include` and `exclude`.
+    pub filename: Option<String>,
+    /// Project directory that `include` and `exclude` are relative to.
+    pub cwd: Option<String>,
+}
+
+/// Same as [`transform_program`], with access to the host's comments and source map.
+pub fn transform_program_with_host(program: Program, config: Config, host: TransformHost) -> Program {
+    run_stages(program, &TransformFactory::new(config), host)
+}
+
+/// Same as [`transform_program`], also returning a [`SpanMapping`] for every
+/// lowered control tag.
+#[cfg(feature = "cli")]
+pub fn transform_program_with_span_mappings(program: Program, config: Config) -> (Program, Vec<SpanMapping>) {
+    run_stages_mapped(program, &TransformFactory::new(config), TransformHost::default(), true)
+}
+
+fn run_stages(program: Program, factory: &TransformFactory, host: TransformHost) -> Program {
+    run_stages_mapped(program, factory, host, false).0
+}
+
+fn run_stages_mapped(mut program: Program, factory: &TransformFactory, host: TransformHost, record_spans: bool) -> (Program, Vec<SpanMapping>) {
+    let config = factory.config();
+    let mut span_mappings = Vec::new();
+    if !config.applies_to(host.filename.as_deref(), host.cwd.as_deref()) {
+        return (program, span_mappings);
+    }
+    let source_map = host.source_map.clone();
+    let mut comments = None;
+    // Transform 阶段的预扫描没有发现控制标签时，后处理也没有可做的
+    let mut found_control_tags = true;
+    let mut host = Some(host);
+    for stage in Stage::ORDER {
+        if !stage.is_enabled(config) {
+            continue;
+        }
+        match stage {
+            Stage::Transform => {
+                let mut visitor = factory.visitor().with_host(host.take().unwrap_or_default());
+                if record_spans {
+                    visitor = visitor.with_span_mappings();
+                }
+                program.visit_mut_with(&mut visitor);
+                visitor.flush_json_diagnostics();
+                span_mappings = visitor.take_span_mappings();
+                comments = visitor.comments.clone();
+                found_control_tags = visitor.found_control_tags;
+            }
+            Stage::PostTransform if found_control_tags => program.visit_mut_with(&mut factory.post_visitor()),
+            Stage::PostTransform => {}
+            Stage::Verify => {
+                let mut visitor = factory.visitor().with_host(TransformHost { source_map: source_map.clone(), ..Default::default() });
+                visitor.comments = comments.clone();
+                visitor.verify_output(&program);
+                visitor.flush_json_diagnostics();
+            }
+            Stage::StripImports => program.visit_mut_with(&mut StripImportsVisitor::new(config)),
+        }
     }
+    (program, span_mappings)
 }
 
 #[plugin_transform]
-pub fn process_transform(program: Program, _metadata: TransformPluginProgramMetadata) -> Program {
-    let transformed = program.fold_with(&mut TransformVisitor::default());
-    transformed.fold_with(&mut PostTransformVisitor)
+pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    let config = metadata
+        .get_transform_plugin_config()
+        .map(|json| Config::from_json(&json).expect("invalid swc-condition-plugin config"))
+        .unwrap_or_default();
+    // SWC 只暴露 envName，按惯例对应 NODE_ENV
+    let env = metadata
+        .get_context(&TransformPluginMetadataContextKind::Env)
+        .map(|env_name| HashMap::from([(NODE_ENV.to_string(), env_name)]))
+        .unwrap_or_default();
+    let filename = metadata.get_context(&TransformPluginMetadataContextKind::Filename);
+    let cwd = metadata.get_context(&TransformPluginMetadataContextKind::Cwd);
+    let host = TransformHost {
+        comments: metadata.comments.map(|comments| Box::new(comments) as Box<dyn Comments>),
+        source_map: Some(Lrc::new(metadata.source_map)),
+        env,
+        unresolved_mark: Some(metadata.unresolved_mark),
+        filename,
+        cwd,
+    };
+    transform_program_with_host(program, config, host)
 }
 
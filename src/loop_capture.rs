@@ -0,0 +1,60 @@
+use swc_core::atoms::Atom;
+use swc_core::common::Spanned;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Runs `f` with the names of an enclosing `for (var …)` head in scope.
+    pub(crate) fn with_loop_vars<F: FnOnce(&mut Self)>(&mut self, declared: Vec<Atom>, f: F) {
+        let depth = self.loop_vars.len();
+        self.loop_vars.extend(declared);
+        f(self);
+        self.loop_vars.truncate(depth);
+    }
+
+    /// Warns about parallel Switch cases whose condition reads a `var` loop
+    /// variable: every case holds a reference to the one shared binding.
+    pub(crate) fn report_loop_captures(&mut self, cases: &[(Box<Expr>, Vec<JSXElementChild>)]) {
+        if self.loop_vars.is_empty() {
+            return;
+        }
+        for (condition, _) in cases {
+            let mut finder = IdentFinder { names: &self.loop_vars, found: None };
+            condition.visit_with(&mut finder);
+            let Some(name) = finder.found else {
+                continue;
+            };
+            let message = format!(
+                "`{name}` is declared with `var` by an enclosing loop and shared by every iteration; \
+                 declare it with `let` or give this Switch `shortCircuit`"
+            );
+            self.report_warning(condition.span(), "loop-var-capture", message);
+        }
+    }
+}
+
+/// Names a loop head declares with `var`; `let` and `const` get a fresh
+/// binding per iteration.
+pub(crate) fn loop_var_names(decl: &VarDecl) -> Vec<Atom> {
+    if decl.kind != VarDeclKind::Var {
+        return Vec::new();
+    }
+    decl.decls.iter()
+        .filter_map(|declarator| declarator.name.as_ident().map(|ident| ident.sym.clone()))
+        .collect()
+}
+
+struct IdentFinder<'a> {
+    names: &'a [Atom],
+    found: Option<Atom>,
+}
+
+impl Visit for IdentFinder<'_> {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if self.found.is_none() && self.names.contains(&ident.sym) {
+            self.found = Some(ident.sym.clone());
+        }
+    }
+}
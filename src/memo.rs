@@ -0,0 +1,78 @@
+use swc_core::atoms::Atom;
+use swc_core::common::{EqIgnoreSpan, Span};
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Runs `f` over a function body. The body may call hooks when the
+    /// function is declared under a component (`App`) or hook (`useX`) name.
+    pub(crate) fn with_fn_scope<F: FnOnce(&mut Self)>(&mut self, f: F) {
+        let is_hook_scope = self.pending_fn_name.take().is_some_and(|name| is_component_or_hook(&name));
+        let prev = std::mem::replace(&mut self.hook_scope, is_hook_scope);
+        f(self);
+        self.hook_scope = prev;
+    }
+
+    /// `<Switch memo deps={[a, b]}>` → `useMemo(() => …, [a, b])` around the
+    /// lowered Switch. Left unmemoized, with a warning, when `deps` is
+    /// missing or the Switch is not directly inside a component or hook.
+    pub(crate) fn memoize_switch(&mut self, attrs: &[JSXAttrOrSpread], lowered: JSXElement, span: Span) -> JSXElement {
+        let has_memo = attrs.iter().any(|attr| {
+            matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), .. }) if name.sym == self.atoms.memo_atom)
+        });
+        let Some(hook) = self.memo_hook.clone().filter(|_| has_memo) else {
+            return lowered;
+        };
+        let Some(deps) = self.extract_attr_value(attrs, &self.atoms.deps_atom) else {
+            self.report_warning(span, "memo-without-deps", "`memo` on a Switch needs `deps={[…]}`; the Switch is left unmemoized".into());
+            return lowered;
+        };
+        if !self.hook_scope {
+            let message = "`memo` on a Switch outside a component or hook: hooks may only be called at the top level of a \
+                           function named like `App` or `useThing`; the Switch is left unmemoized".to_string();
+            self.report_warning(span, "memo-outside-component", message);
+            return lowered;
+        }
+
+        let body = self.lowered_expr(lowered);
+        let factory = Expr::Arrow(ArrowExpr {
+            span,
+            ctxt: self.syntax_context,
+            params: vec![],
+            body: Box::new(BlockStmtOrExpr::Expr(Box::new(body))),
+            is_async: false,
+            is_generator: false,
+            type_params: None,
+            return_type: None,
+        });
+        let memoized = Expr::Call(CallExpr {
+            span,
+            ctxt: self.syntax_context,
+            callee: Callee::Expr(Box::new(hook)),
+            args: vec![factory, *deps].into_iter().map(|expr| ExprOrSpread { spread: None, expr: Box::new(expr) }).collect(),
+            type_args: None,
+        });
+        self.wrap_expr_for_context(memoized, span)
+    }
+
+    /// The expression inside a placeholder or fragment holder, or the
+    /// element itself.
+    pub(crate) fn lowered_expr(&self, lowered: JSXElement) -> Expr {
+        let is_holder = self.is_placeholder(&lowered)
+            || (lowered.opening.attrs.is_empty() && lowered.opening.name.eq_ignore_span(&self.fragment_name));
+        match (is_holder, lowered.children.as_slice()) {
+            (true, [JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })]) => (**expr).clone(),
+            _ => Expr::JSXElement(Box::new(lowered)),
+        }
+    }
+}
+
+/// Name of a function that may call hooks: `App`, `useThing` or `use`.
+pub(crate) fn is_component_or_hook(name: &Atom) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_uppercase() => true,
+        _ => name.strip_prefix("use").is_some_and(|rest| rest.chars().next().is_none_or(|c| c.is_ascii_uppercase())),
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::{HashMap, HashSet};
+
+use swc_core::atoms::Atom;
+
+/// Names for the temporaries and hoisted constants the transform declares.
+/// Every name used anywhere in the file is taken, so a generated one never
+/// shadows or captures a binding of the source; numbering runs per prefix
+/// and per file, so the same input always gets the same names.
+#[derive(Default)]
+pub(crate) struct NameAllocator {
+    taken: HashSet<Atom>,
+    counters: HashMap<&'static str, usize>,
+}
+
+impl NameAllocator {
+    /// An allocator that avoids `taken`, every identifier of the file.
+    pub(crate) fn new(taken: HashSet<Atom>) -> Self {
+        Self { taken, counters: HashMap::new() }
+    }
+
+    /// The next free `<prefix><n>`, counting from 1: `__case1`, `__case2`, …
+    pub(crate) fn numbered(&mut self, prefix: &'static str) -> Atom {
+        let counter = self.counters.entry(prefix).or_default();
+        loop {
+            *counter += 1;
+            let name = Atom::from(format!("{prefix}{counter}"));
+            if self.taken.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+
+    /// `name` itself when the file does not use it, else the first unused
+    /// numbered variant (`_`, `_2`, …), without reserving it. For parameters,
+    /// which are scoped to their function and may repeat between functions.
+    pub(crate) fn unused(&self, name: &str) -> Atom {
+        let atom = Atom::from(name);
+        if !self.taken.contains(&atom) {
+            return atom;
+        }
+        (2..)
+            .map(|counter| Atom::from(format!("{name}{counter}")))
+            .find(|numbered| !self.taken.contains(numbered))
+            .expect("the numbered names are endless")
+    }
+
+    /// `name` itself while it is free, else the next free numbered variant
+    /// (`__switchValue`, `__switchValue2`, …).
+    pub(crate) fn named(&mut self, name: &'static str) -> Atom {
+        let atom = Atom::from(name);
+        if self.taken.insert(atom.clone()) {
+            return atom;
+        }
+        let counter = self.counters.entry(name).or_insert(1);
+        loop {
+            *counter += 1;
+            let numbered = Atom::from(format!("{name}{counter}"));
+            if self.taken.insert(numbered.clone()) {
+                return numbered;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocator(taken: &[&str]) -> NameAllocator {
+        NameAllocator::new(taken.iter().map(|name| Atom::from(*name)).collect())
+    }
+
+    #[test]
+    fn numbered_skips_names_of_the_file() {
+        let mut names = allocator(&["__case1", "__case3"]);
+        assert_eq!(names.numbered("__case"), "__case2");
+        assert_eq!(names.numbered("__case"), "__case4");
+    }
+
+    #[test]
+    fn named_keeps_the_name_until_it_is_taken() {
+        let mut names = allocator(&["__switchValue2"]);
+        assert_eq!(names.named("__switchValue"), "__switchValue");
+        assert_eq!(names.named("__switchValue"), "__switchValue3");
+        assert_eq!(names.named("__switchValue"), "__switchValue4");
+    }
+
+    #[test]
+    fn prefixes_are_numbered_separately() {
+        let mut names = allocator(&[]);
+        assert_eq!(names.numbered("__case"), "__case1");
+        assert_eq!(names.numbered("__branchShared"), "__branchShared1");
+        assert_eq!(names.numbered("__case"), "__case2");
+    }
+
+    #[test]
+    fn unused_does_not_reserve() {
+        let mut names = allocator(&["_", "_2"]);
+        assert_eq!(names.unused("_"), "_3");
+        assert_eq!(names.unused("_"), "_3");
+        assert_eq!(names.unused("__index"), "__index");
+        assert_eq!(names.named("__index"), "__index");
+        assert_eq!(names.unused("__index"), "__index2");
+    }
+}
@@ -0,0 +1,52 @@
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// `transformNested`: lowers the control tags inside `element`'s branches
+    /// before `element` itself. A branch whose only child is a control tag
+    /// inherits the current context, so `return <Condition><Switch>…` nests
+    /// bare ternaries; other branch content is lowered as JSX children.
+    pub(crate) fn lower_nested_branches(&mut self, element: &mut JSXElement) {
+        let Some((tag, _)) = self.control_tag_name(&element.opening.name) else {
+            return;
+        };
+        let is_switch = *tag == self.atoms.switch_atom
+            || self.atoms.compat().is_some_and(|compat| *tag == compat.choose_tag);
+        if !is_switch {
+            return self.lower_branch(&mut element.children);
+        }
+        for child in &mut element.children {
+            if let JSXElementChild::JSXElement(case) = child {
+                if self.is_switch_case_element(case) || self.is_switch_else_alias(case) {
+                    self.lower_branch(&mut case.children);
+                }
+            }
+        }
+    }
+
+    /// Whether `element` is a control tag this visitor lowers.
+    pub(crate) fn is_lowerable_root(&self, element: &JSXElement) -> bool {
+        self.control_tag_name(&element.opening.name)
+            .is_some_and(|(tag, binding)| self.is_root_control_tag(tag) && self.is_lowerable_tag(binding))
+            && !self.is_raw_element(element)
+    }
+
+    fn lower_branch(&mut self, children: &mut Vec<JSXElementChild>) {
+        let mut non_whitespace = children.iter_mut().filter(|child| Self::is_non_whitespace_child(child));
+        match (non_whitespace.next(), non_whitespace.next()) {
+            (Some(JSXElementChild::JSXElement(only)), None) if self.is_lowerable_root(only) => self.visit_mut_jsx_element(only),
+            _ => self.with_jsx_context(|visitor| children.visit_mut_with(visitor)),
+        }
+    }
+}
+
+impl TransformVisitor {
+    /// A lowered return/assignment expression standing in for a whole branch.
+    /// Only this instance's placeholders count; another plugin instance in the
+    /// same pipeline uses its own name.
+    pub(crate) fn is_placeholder(&self, element: &JSXElement) -> bool {
+        matches!(&element.opening.name, JSXElementName::Ident(ident) if ident.sym == self.condition_placeholder_ident.sym)
+    }
+}
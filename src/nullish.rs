@@ -0,0 +1,35 @@
+use swc_core::common::Spanned;
+use swc_core::ecma::ast::*;
+
+use crate::{BooleanStrategy, TransformVisitor};
+
+static FIND_METHODS: [&str; 2] = ["find", "findLast"];
+
+impl TransformVisitor {
+    /// Notes a test that reads like "is there a value" (`a?.b`, `x ?? y`,
+    /// `list.find(…)`) but is coerced by truthiness, so `0` and `""` hide the
+    /// branch. Code migrated from runtime components often expects otherwise.
+    pub(crate) fn note_nullish_condition(&mut self, condition: &Expr) {
+        if self.config.boolean_strategy == BooleanStrategy::None || !is_nullish_returning(condition) {
+            return;
+        }
+        self.report_note(
+            condition.span(),
+            "falsy-nullish-condition",
+            "this condition may be `0` or `\"\"`, which are falsy and do not render the branch; compare with `!= null` to render them".into(),
+        );
+    }
+}
+
+fn is_nullish_returning(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_nullish_returning(&paren.expr),
+        Expr::OptChain(_) => true,
+        Expr::Bin(BinExpr { op: BinaryOp::NullishCoalescing, .. }) => true,
+        Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) => matches!(
+            &**callee,
+            Expr::Member(MemberExpr { prop: MemberProp::Ident(prop), .. }) if FIND_METHODS.contains(&&*prop.sym)
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,46 @@
+use swc_core::common::comments::Comments;
+use swc_core::common::util::take::Take;
+use swc_core::common::Mark;
+use swc_core::ecma::ast::{Pass, Program};
+use swc_core::ecma::transforms::base::{fixer::fixer, hygiene::hygiene, resolver};
+
+use crate::{run_stages, Config, TransformFactory, TransformHost};
+
+/// The enabled stages as one swc [`Pass`], for tuples of passes next to
+/// swc's own transforms. The host (comments, marks, env) is handed to the
+/// first program processed; later programs get a default host.
+pub struct ConditionPass {
+    factory: TransformFactory,
+    host: Option<TransformHost>,
+}
+
+impl Pass for ConditionPass {
+    fn process(&mut self, program: &mut Program) {
+        let host = self.host.take().unwrap_or_default();
+        *program = run_stages(program.take(), &self.factory, host);
+    }
+}
+
+/// Wraps the transform as a [`Pass`].
+pub fn condition_pass(config: Config, host: TransformHost) -> ConditionPass {
+    ConditionPass { factory: TransformFactory::new(config), host: Some(host) }
+}
+
+/// `resolver`, the transform, `hygiene` and `fixer`, in the order they must
+/// run when embedding the transform outside of an swc plugin. Must be
+/// called inside `GLOBALS.set`; the resolver's unresolved mark is the
+/// host's, or a fresh one.
+pub fn recommended_chain<'a>(
+    config: Config,
+    mut host: TransformHost,
+    top_level_mark: Mark,
+    comments: Option<&'a dyn Comments>,
+) -> impl Pass + 'a {
+    let unresolved_mark = *host.unresolved_mark.get_or_insert_with(Mark::new);
+    (
+        resolver(unresolved_mark, top_level_mark, true),
+        condition_pass(config, host),
+        hygiene(),
+        fixer(comments),
+    )
+}
@@ -0,0 +1,16 @@
+use swc_core::common::Span;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Test of `<Platform web native>`: whether the configured `platform` is
+    /// among the tag's attributes. `None` without a `platform`.
+    pub(crate) fn platform_condition(&self, attrs: &[JSXAttrOrSpread], span: Span) -> Option<Box<Expr>> {
+        let platform = self.config.platform.as_deref()?;
+        let value = attrs.iter().any(|attr| {
+            matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), .. }) if &*name.sym == platform)
+        });
+        Some(Box::new(Expr::Lit(Lit::Bool(Bool { span, value }))))
+    }
+}
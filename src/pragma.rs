@@ -0,0 +1,64 @@
+use swc_core::common::comments::{Comment, Comments};
+use swc_core::common::{Span, DUMMY_SP};
+use swc_core::ecma::ast::JSXElementName;
+
+use crate::{TransformVisitor, FRAGMENT_EXPORT};
+
+static JSX_FRAG_PRAGMA: &str = "@jsxFrag";
+static JSX_PRAGMA: &str = "@jsx";
+static DISABLE_DIRECTIVE: &str = "@swc-condition-disable";
+
+impl TransformVisitor {
+    /// Fragment named by the file's leading `@jsxFrag` pragma, or derived
+    /// from `@jsx` (`@jsx h` → `Fragment`, `@jsx Preact.h` → `Preact.Fragment`).
+    /// Pragmas override the `fragment` option for that file.
+    pub(crate) fn pragma_fragment_name(&self, file: Span, first_item: Option<Span>) -> Option<JSXElementName> {
+        let (jsx_frag, jsx) = self.find_file_pragma(file, first_item, |comments| {
+            let find = |pragma| comments.iter().find_map(|comment| pragma_value(&comment.text, pragma));
+            Some((find(JSX_FRAG_PRAGMA), find(JSX_PRAGMA))).filter(|(jsx_frag, jsx)| jsx_frag.is_some() || jsx.is_some())
+        })?;
+
+        let fragment = match (jsx_frag, jsx) {
+            (Some(fragment), _) => fragment,
+            (None, Some(factory)) => match factory.rsplit_once('.') {
+                Some((namespace, _)) => format!("{namespace}.{FRAGMENT_EXPORT}"),
+                None => FRAGMENT_EXPORT.to_string(),
+            },
+            (None, None) => return None,
+        };
+        Some(Self::fragment_element_name(&fragment, DUMMY_SP, self.syntax_context))
+    }
+
+    /// Whether the file opts out of the transform with a leading
+    /// `// @swc-condition-disable`; its control tags are then left as written.
+    pub(crate) fn is_disabled_file(&self, file: Span, first_item: Option<Span>) -> bool {
+        self.find_file_pragma(file, first_item, |comments| {
+            comments.iter().any(|comment| comment.text.split_whitespace().any(|token| token == DISABLE_DIRECTIVE)).then_some(())
+        })
+        .is_some()
+    }
+
+    /// The first match of `find` among the comments leading the file, where
+    /// pragmas go. They are looked up before the file and, for generated files
+    /// whose own span is a dummy or not where the parser left the comments,
+    /// before its first item.
+    fn find_file_pragma<R>(&self, file: Span, first_item: Option<Span>, find: impl Fn(&[Comment]) -> Option<R>) -> Option<R> {
+        let comments = self.comments.as_ref()?;
+        [Some(file), first_item]
+            .into_iter()
+            .flatten()
+            .filter(|span| !span.is_dummy())
+            .find_map(|span| comments.with_leading(span.lo, |comments| find(comments)))
+    }
+}
+
+/// `* @jsxFrag h.Fragment` → `h.Fragment`; the name must be an exact token,
+/// so `@jsx` does not match `@jsxFrag` or `@jsxRuntime`.
+fn pragma_value(text: &str, pragma: &str) -> Option<String> {
+    let mut tokens = text.split_whitespace();
+    tokens.find(|token| *token == pragma)?;
+    tokens
+        .next()
+        .filter(|value| value.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$')))
+        .map(str::to_string)
+}
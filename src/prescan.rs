@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use swc_core::atoms::Atom;
+use swc_core::common::comments::Comments;
+use swc_core::common::{Span, SyntaxContext};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::ignore::{has_leading_directive, is_directive_child};
+use crate::TransformVisitor;
+
+/// What one walk over a file finds before the transform: whether it has
+/// any control tag markup, its `importSources` imports, the local bindings
+/// that shadow a tag name, every identifier (for generated names), and the
+/// control tags a `@condition-ignore` comment applies to.
+#[derive(Default)]
+pub(crate) struct FileScan {
+    pub(crate) has_control_tags: bool,
+    pub(crate) imported_tags: Vec<(Atom, SyntaxContext)>,
+    pub(crate) shadowed_tags: Vec<(Atom, SyntaxContext)>,
+    pub(crate) idents: HashSet<Atom>,
+    pub(crate) ignored_elements: Vec<Span>,
+}
+
+impl TransformVisitor {
+    /// Gathers the [`FileScan`] in a single traversal, so files without
+    /// control tags cost one read-only walk and files with them no more.
+    pub(crate) fn scan_file<N: for<'a> VisitWith<FileScanner<'a>>>(&self, node: &N) -> FileScan {
+        let mut scanner = FileScanner { visitor: self, comments: self.comments.as_deref(), scan: FileScan::default() };
+        node.visit_with(&mut scanner);
+        let mut scan = scanner.scan;
+        scan.shadowed_tags.retain(|(sym, _)| self.is_control_tag_name(sym));
+        scan
+    }
+
+    /// Tags that start a lowering: the root of a Condition, Switch, For, …
+    pub(crate) fn is_root_control_tag(&self, sym: &Atom) -> bool {
+        let atoms = &self.atoms;
+        [&atoms.condition_atom, &atoms.unless_atom, &atoms.switch_atom, &atoms.for_atom, &atoms.with_atom, &atoms.feature_atom, &atoms.platform_atom].contains(&sym)
+            || (self.config.solid_show && *sym == atoms.show_atom)
+            || atoms.compat().is_some_and(|compat| *sym == compat.if_tag || *sym == compat.choose_tag)
+    }
+}
+
+pub(crate) struct FileScanner<'a> {
+    visitor: &'a TransformVisitor,
+    comments: Option<&'a dyn Comments>,
+    scan: FileScan,
+}
+
+impl FileScanner<'_> {
+    fn add_binding(&mut self, ident: &Ident) {
+        self.scan.shadowed_tags.push((ident.sym.clone(), ident.ctxt));
+    }
+
+    /// Only control tags are ignored, and only by a span of their own.
+    fn add_ignored(&mut self, element: &JSXElement) {
+        let visitor = self.visitor;
+        let is_control = visitor.control_tag_name(&element.opening.name).is_some_and(|(tag, _)| visitor.is_root_control_tag(tag));
+        if is_control && !element.span.is_dummy() {
+            self.scan.ignored_elements.push(element.span);
+        }
+    }
+}
+
+impl Visit for FileScanner<'_> {
+    fn visit_jsx_element(&mut self, element: &JSXElement) {
+        if !self.scan.has_control_tags {
+            let visitor = self.visitor;
+            self.scan.has_control_tags = match visitor.control_tag_name(&element.opening.name) {
+                Some((tag, _)) => visitor.is_root_control_tag(tag),
+                None => false,
+            } || visitor.inline_if_attr(&element.opening.attrs).is_some();
+        }
+        if self.comments.is_some_and(|comments| has_leading_directive(comments, element)) {
+            self.add_ignored(element);
+        }
+        element.visit_children_with(self);
+    }
+
+    fn visit_jsx_element_childs(&mut self, children: &[JSXElementChild]) {
+        if let Some(comments) = self.comments {
+            let mut pending = false;
+            for child in children {
+                match child {
+                    JSXElementChild::JSXElement(element) if pending => {
+                        self.add_ignored(element);
+                        pending = false;
+                    }
+                    JSXElementChild::JSXText(text) if text.value.trim().is_empty() => continue,
+                    _ => pending = is_directive_child(comments, child),
+                }
+            }
+        }
+        children.visit_children_with(self);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.scan.idents.insert(ident.sym.clone());
+    }
+
+    // import 不算遮蔽，它正是引入控制标签的方式
+    fn visit_import_decl(&mut self, import: &ImportDecl) {
+        self.scan.imported_tags.extend(self.visitor.imported_tags_of(import));
+        for specifier in &import.specifiers {
+            self.visit_ident(specifier.local());
+        }
+    }
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.add_binding(&binding.id);
+        binding.visit_children_with(self);
+    }
+
+    fn visit_fn_decl(&mut self, decl: &FnDecl) {
+        self.add_binding(&decl.ident);
+        decl.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, decl: &ClassDecl) {
+        self.add_binding(&decl.ident);
+        decl.visit_children_with(self);
+    }
+
+    fn visit_fn_expr(&mut self, expr: &FnExpr) {
+        if let Some(ident) = &expr.ident {
+            self.add_binding(ident);
+        }
+        expr.visit_children_with(self);
+    }
+
+    fn visit_class_expr(&mut self, expr: &ClassExpr) {
+        if let Some(ident) = &expr.ident {
+            self.add_binding(ident);
+        }
+        expr.visit_children_with(self);
+    }
+}
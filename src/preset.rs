@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use swc_core::common::Spanned;
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+/// Bundle of settings for a build flavor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// Unit-test builds: control tags stay runtime components (rendered by
+    /// the runtime shim), while `process.env` conditions are still folded.
+    Jest,
+}
+
+impl TransformVisitor {
+    pub(crate) fn keeps_runtime_tags(&self) -> bool {
+        self.config.preset == Some(Preset::Jest)
+    }
+
+    /// Replaces control-tag conditions that `env` decides with `true` or
+    /// `false`, leaving the tag itself in place.
+    pub(crate) fn fold_env_attrs(&self, element: &mut JSXElement) {
+        let is_control = self.control_tag_name(&element.opening.name).is_some_and(|(tag, _)| self.is_root_control_tag(tag))
+            || self.is_switch_case_element(element);
+        if !is_control {
+            return;
+        }
+        let atoms = &self.atoms;
+        let condition_attrs = [Some(&atoms.if_atom), Some(&atoms.when_atom), atoms.compat().map(|compat| &compat.condition_attr)];
+
+        for attr in &mut element.opening.attrs {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(name),
+                value: Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })),
+                ..
+            }) = attr
+            else {
+                continue;
+            };
+            if !condition_attrs.contains(&Some(&name.sym)) {
+                continue;
+            }
+            if let Some(value) = self.evaluate_static_condition(expr) {
+                **expr = Expr::Lit(Lit::Bool(Bool { span: expr.span(), value }));
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// `<Condition if={user}>{() => <Name value={user.name} />}</Condition>`:
+    /// a branch given as a render prop is only called when the test passes,
+    /// so its body is inlined as the branch. Block bodies stay a call,
+    /// `(() => { … })()`.
+    pub(crate) fn inline_render_prop(&self, children: &mut Vec<JSXElementChild>) {
+        let mut non_whitespace = children.iter().filter(|child| Self::is_non_whitespace_child(child));
+        let (Some(JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })), None) =
+            (non_whitespace.next(), non_whitespace.next())
+        else {
+            return;
+        };
+        let Expr::Arrow(arrow) = &**expr else {
+            return;
+        };
+        if !arrow.params.is_empty() || arrow.is_async || arrow.is_generator {
+            return;
+        }
+
+        let child = match &*arrow.body {
+            BlockStmtOrExpr::Expr(body) => Self::expr_to_child(unparen((**body).clone())),
+            BlockStmtOrExpr::BlockStmt(_) => {
+                let span = arrow.span;
+                Self::expr_to_child(Expr::Call(CallExpr {
+                    span,
+                    ctxt: self.syntax_context,
+                    callee: Callee::Expr(Box::new(Expr::Paren(ParenExpr { span, expr: expr.clone() }))),
+                    args: vec![],
+                    type_args: None,
+                }))
+            }
+        };
+        *children = vec![child];
+    }
+}
+
+/// `() => (<Foo />)` 的括号只是排版，JSX 本身可以直接作为子节点
+fn unparen(expr: Expr) -> Expr {
+    match expr {
+        Expr::Paren(paren) => unparen(*paren.expr),
+        expr => expr,
+    }
+}
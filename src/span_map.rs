@@ -0,0 +1,43 @@
+use swc_core::common::{EqIgnoreSpan, Span, Spanned};
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+/// Where a lowered control tag went: the span of the original element and
+/// of the expression that replaced it. Codemods can map both through the
+/// source map to update tests or snapshots that pointed at the markup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanMapping {
+    pub original: Span,
+    pub generated: Span,
+}
+
+impl TransformVisitor {
+    /// Records a [`SpanMapping`] for every control tag this visitor lowers.
+    pub fn with_span_mappings(mut self) -> Self {
+        self.span_mappings = Some(Vec::new());
+        self
+    }
+
+    /// The mappings recorded so far, in the order the tags were lowered.
+    pub fn take_span_mappings(&mut self) -> Vec<SpanMapping> {
+        self.span_mappings.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    pub(crate) fn record_span_mapping(&mut self, original: Span, lowered: &JSXElement) {
+        // 没有真实位置的元素无从映射
+        if self.span_mappings.is_none() || original.is_dummy() || self.config.synthetic_spans {
+            return;
+        }
+        // 占位元素和 Fragment 容器在后处理或输出时只剩里面的表达式
+        let is_holder = self.is_placeholder(lowered)
+            || (lowered.opening.attrs.is_empty() && lowered.opening.name.eq_ignore_span(&self.fragment_name));
+        let generated = match (is_holder, lowered.children.as_slice()) {
+            (true, [JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })]) => expr.span(),
+            _ => lowered.span,
+        };
+        if let Some(mappings) = &mut self.span_mappings {
+            mappings.push(SpanMapping { original, generated });
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use swc_core::common::{util::take::Take, Spanned};
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// What a `<Condition>`-like child whose test is known at build time
+    /// leaves in its parent: its children, its fallback, or nothing, so
+    /// debug-only markup is gone before dead-code elimination even runs.
+    /// `None` when `element` is not such a child.
+    pub(crate) fn fold_static_child(&mut self, element: &mut JSXElement) -> Option<Vec<JSXElementChild>> {
+        let (tag, binding) = self.control_tag_name(&element.opening.name)?;
+        // keepMounted 的子树必须保持挂载，交给常规降级输出隐藏的包装元素
+        if self.keeps_runtime_tags() || !self.is_lowerable_tag(binding) || self.is_raw_element(element) || self.is_keep_mounted(&element.opening.attrs) {
+            return None;
+        }
+        let attrs = &element.opening.attrs;
+        let atoms = &self.atoms;
+        let (condition, negate, fallback) = if *tag == atoms.condition_atom {
+            (self.extract_condition_from_attrs(attrs)?, false, None)
+        } else if *tag == atoms.unless_atom {
+            (self.extract_condition_from_attrs(attrs)?, true, None)
+        } else if self.config.solid_show && *tag == atoms.show_atom {
+            (self.extract_condition_attr(attrs, &atoms.when_atom)?, false, self.extract_attr_value(attrs, &atoms.fallback_atom))
+        } else if *tag == atoms.feature_atom {
+            (self.feature_condition(attrs, element.span)?, false, None)
+        } else if *tag == atoms.platform_atom {
+            (self.platform_condition(attrs, element.span)?, false, None)
+        } else if let Some(compat) = atoms.compat().filter(|compat| *tag == compat.if_tag) {
+            (self.extract_condition_attr(attrs, &compat.condition_attr)?, false, None)
+        } else {
+            return None;
+        };
+
+        let value = self.evaluate_static_condition(&condition)?;
+        // 折叠掉的标签不会再走 lower_control_element，在这里检查属性和条件
+        if self.config.strict_attributes {
+            self.report_unknown_attributes(element, &tag.clone());
+        }
+        if *tag != self.atoms.feature_atom && *tag != self.atoms.platform_atom {
+            self.lint_constant_condition(&condition);
+        }
+        if value != negate {
+            self.inline_render_prop(&mut element.children);
+            return Some(match self.take_as_element(element) {
+                Some(wrapped) => vec![JSXElementChild::JSXElement(Box::new(wrapped))],
+                None => element.children.take(),
+            });
+        }
+        let fallback = fallback.unwrap_or_else(|| Box::new(self.fallback_at(element.span)));
+        if renders_nothing(&fallback) {
+            return Some(Vec::new());
+        }
+        Some(vec![JSXElementChild::JSXExprContainer(JSXExprContainer {
+            span: fallback.span(),
+            expr: JSXExpr::Expr(fallback),
+        })])
+    }
+}
+
+fn renders_nothing(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Null(_) | Lit::Bool(_)) => true,
+        Expr::Ident(ident) => &*ident.sym == "undefined",
+        _ => false,
+    }
+}
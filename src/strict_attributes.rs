@@ -0,0 +1,100 @@
+use swc_core::atoms::Atom;
+use swc_core::common::Spanned;
+use swc_core::ecma::ast::*;
+
+use crate::diagnostics::jsx_name;
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Under `strictAttributes`, reports attributes that a control tag (and,
+    /// for a Switch, its cases) does not read, so a typo such as `iff={x}`
+    /// is not silently dropped with the tag.
+    pub(crate) fn report_unknown_attributes(&mut self, element: &JSXElement, tag: &Atom) {
+        let atoms = &self.atoms;
+        let known = if *tag == atoms.condition_atom || *tag == atoms.unless_atom {
+            vec![&atoms.if_atom, &atoms.key_atom, &atoms.as_atom, &atoms.keep_mounted_atom]
+        } else if self.config.solid_show && *tag == atoms.show_atom {
+            vec![&atoms.when_atom, &atoms.fallback_atom, &atoms.key_atom, &atoms.as_atom, &atoms.keep_mounted_atom]
+        } else if *tag == atoms.feature_atom {
+            vec![&atoms.name_atom, &atoms.not_atom, &atoms.key_atom, &atoms.as_atom, &atoms.keep_mounted_atom]
+        } else if *tag == atoms.switch_atom {
+            vec![&atoms.short_circuit_atom, &atoms.value_atom, &atoms.values_atom, &atoms.key_atom, &atoms.memo_atom, &atoms.deps_atom, &atoms.lazy_atom]
+        } else {
+            return;
+        };
+        let known: Vec<Atom> = known.into_iter().cloned().collect();
+        self.report_attributes_outside(element, &known);
+
+        if *tag != self.atoms.switch_atom {
+            return;
+        }
+        let atoms = &self.atoms;
+        let case_known: Vec<Atom> = [
+            &atoms.if_atom, &atoms.else_atom, &atoms.is_atom, &atoms.in_atom, &atoms.when_atom,
+            &atoms.key_atom, &atoms.priority_atom, &atoms.else_show_atom, &atoms.on_error_atom, &atoms.order_atom,
+        ]
+        .into_iter()
+        .cloned()
+        .collect();
+        let else_known = vec![atoms.key_atom.clone(), atoms.priority_atom.clone()];
+        for child in &element.children {
+            let JSXElementChild::JSXElement(case) = child else {
+                continue;
+            };
+            if self.is_switch_case_element(case) {
+                self.report_attributes_outside(case, &case_known);
+            } else if self.is_switch_else_alias(case) {
+                self.report_attributes_outside(case, &else_known);
+            }
+        }
+    }
+
+    fn report_attributes_outside(&mut self, element: &JSXElement, known: &[Atom]) {
+        let tag = jsx_name(&element.opening.name);
+        for attr in &element.opening.attrs {
+            let (span, message) = match attr {
+                JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), span, .. }) => {
+                    if known.contains(&name.sym) {
+                        continue;
+                    }
+                    let message = match closest(&name.sym, known) {
+                        Some(suggestion) => format!("`<{tag}>` has no `{}` attribute (did you mean `{suggestion}`?); it is ignored", name.sym),
+                        None => format!("`<{tag}>` has no `{}` attribute; it is ignored", name.sym),
+                    };
+                    (*span, message)
+                }
+                JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::JSXNamespacedName(name), span, .. }) => {
+                    (*span, format!("`<{tag}>` has no `{}:{}` attribute; it is ignored", name.ns.sym, name.name.sym))
+                }
+                JSXAttrOrSpread::SpreadElement(spread) => {
+                    (spread.expr.span(), format!("spread attributes on `<{tag}>` are ignored"))
+                }
+            };
+            self.report_error(span, "unknown-attribute", message);
+        }
+    }
+}
+
+/// A known attribute within two edits of `name`, for a "did you mean" hint.
+fn closest<'a>(name: &str, known: &'a [Atom]) -> Option<&'a Atom> {
+    known
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
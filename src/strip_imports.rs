@@ -0,0 +1,82 @@
+use swc_core::atoms::Atom;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitMut, VisitWith};
+
+use crate::factory::TagAtoms;
+use crate::Config;
+
+/// Removes imports of control components from the configured modules once
+/// lowering has left them unreferenced.
+pub struct StripImportsVisitor {
+    sources: Vec<Atom>,
+    tag_names: Vec<Atom>,
+}
+
+impl StripImportsVisitor {
+    pub fn new(config: &Config) -> Self {
+        let atoms = TagAtoms::new(config);
+        let mut tag_names: Vec<Atom> = atoms.control_tags().into_iter().cloned().collect();
+        if config.solid_show {
+            tag_names.push(atoms.show_atom.clone());
+        }
+        if let Some(compat) = atoms.compat() {
+            tag_names.extend([&compat.if_tag, &compat.choose_tag, &compat.when_tag, &compat.otherwise_tag].into_iter().cloned());
+        }
+        tag_names.extend(config.tag_namespaces.iter().map(|namespace| namespace.as_str().into()));
+        Self {
+            sources: config.strip_imports.iter().map(|source| source.as_str().into()).collect(),
+            tag_names,
+        }
+    }
+
+    /// Whether `specifier` binds a control tag that nothing references anymore.
+    fn is_dead(&self, specifier: &ImportSpecifier, referenced: &[Atom]) -> bool {
+        let local = match specifier {
+            ImportSpecifier::Named(named) => &named.local.sym,
+            ImportSpecifier::Default(default) => &default.local.sym,
+            ImportSpecifier::Namespace(namespace) => &namespace.local.sym,
+        };
+        self.tag_names.contains(local) && !referenced.contains(local)
+    }
+}
+
+impl VisitMut for StripImportsVisitor {
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        if self.sources.is_empty() {
+            return;
+        }
+
+        let mut collector = ReferenceCollector::default();
+        for item in &module.body {
+            if !matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))) {
+                item.visit_with(&mut collector);
+            }
+        }
+
+        module.body.retain_mut(|item| {
+            let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+                return true;
+            };
+            if !self.sources.contains(&import.src.value) || import.specifiers.is_empty() {
+                return true;
+            }
+            import.specifiers.retain(|specifier| !self.is_dead(specifier, &collector.referenced));
+            // 只剩副作用导入时整条删除
+            !import.specifiers.is_empty()
+        });
+    }
+}
+
+/// Every identifier and JSX tag name still used outside imports.
+#[derive(Default)]
+struct ReferenceCollector {
+    referenced: Vec<Atom>,
+}
+
+impl Visit for ReferenceCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if !self.referenced.contains(&ident.sym) {
+            self.referenced.push(ident.sym.clone());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+/// Whether `expr` awaits or yields in the function it is written in. Such
+/// an expression cannot be moved into a generated arrow, which would be
+/// neither async nor a generator.
+pub(crate) fn suspends(expr: &Expr) -> bool {
+    let mut finder = SuspendFinder::default();
+    expr.visit_with(&mut finder);
+    finder.found
+}
+
+#[derive(Default)]
+struct SuspendFinder {
+    found: bool,
+}
+
+impl Visit for SuspendFinder {
+    fn visit_await_expr(&mut self, _: &AwaitExpr) {
+        self.found = true;
+    }
+
+    fn visit_yield_expr(&mut self, _: &YieldExpr) {
+        self.found = true;
+    }
+
+    // 嵌套函数里的 await / yield 属于它自己
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, _: &Class) {}
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use swc_core::atoms::Atom;
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::codegen::{Codegen, Preact, ReactAutomatic, ReactClassic};
+use crate::{TransformVisitor, FRAGMENT_EXPORT};
+
+/// JSX runtime the output is written for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    /// Classic runtime, wraps in `React.Fragment`.
+    #[default]
+    React,
+    /// Automatic runtime, wraps in `<>…</>` unless `fragment` is set explicitly.
+    #[serde(rename = "react-automatic")]
+    ReactAutomatic,
+    /// Wraps in `Fragment` from `preact` unless `fragment` is set explicitly.
+    Preact,
+}
+
+impl Target {
+    /// The built-in [`Codegen`] for this runtime.
+    pub(crate) fn codegen(self) -> Arc<dyn Codegen> {
+        match self {
+            Target::React => Arc::new(ReactClassic),
+            Target::ReactAutomatic => Arc::new(ReactAutomatic),
+            Target::Preact => Arc::new(Preact),
+        }
+    }
+}
+
+impl TransformVisitor {
+    /// With `importFragment`, adds `import { Fragment } from "<target>"` when
+    /// the output uses a bare fragment name that no import provides.
+    pub(crate) fn insert_fragment_import(&self, module: &mut Module) {
+        if !self.config.import_fragment {
+            return;
+        }
+        let JSXElementName::Ident(fragment) = &self.fragment_name else {
+            return;
+        };
+
+        if uses_element(module, &fragment.sym) {
+            insert_named_import(module, fragment, FRAGMENT_EXPORT, self.codegen.fragment_module());
+        }
+    }
+}
+
+/// Adds `import { imported as local } from "src"` after the file's
+/// directives, unless some import already binds `local`.
+pub(crate) fn insert_named_import(module: &mut Module, local: &Ident, imported: &str, src: &str) {
+    let already_imported = module.body.iter().any(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+            import.specifiers.iter().any(|specifier| specifier.local().sym == local.sym)
+        }
+        _ => false,
+    });
+    if already_imported {
+        return;
+    }
+
+    let import = ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        span: DUMMY_SP,
+        specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: local.clone(),
+            imported: (local.sym != imported)
+                .then(|| ModuleExportName::Ident(Ident::new_no_ctxt(imported.into(), DUMMY_SP))),
+            is_type_only: false,
+        })],
+        src: Box::new(src.into()),
+        type_only: false,
+        with: None,
+        phase: Default::default(),
+    }));
+    // 放在 "use client" 等指令之后
+    let position = module.body.iter()
+        .take_while(|item| matches!(
+            item,
+            ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) if matches!(&**expr, Expr::Lit(Lit::Str(_)))
+        ))
+        .count();
+    module.body.insert(position, import);
+}
+
+fn uses_element(module: &Module, sym: &Atom) -> bool {
+    let mut finder = ElementFinder { sym, found: false };
+    module.visit_with(&mut finder);
+    finder.found
+}
+
+struct ElementFinder<'a> {
+    sym: &'a Atom,
+    found: bool,
+}
+
+impl Visit for ElementFinder<'_> {
+    fn visit_jsx_opening_element(&mut self, opening: &JSXOpeningElement) {
+        if matches!(&opening.name, JSXElementName::Ident(ident) if ident.sym == *self.sym) {
+            self.found = true;
+        }
+        opening.visit_children_with(self);
+    }
+}
@@ -0,0 +1,82 @@
+use swc_core::common::{Span, Spanned};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::diagnostics::jsx_name;
+use crate::{TransformVisitor, FRAGMENT_SHORTHAND_PLACEHOLDER, SWITCH_PLACEHOLDER};
+
+impl TransformVisitor {
+    /// Under `verifyOutput`, fails the build on control tags and placeholders
+    /// left in the final output, which would otherwise only show up as
+    /// unknown components at runtime. Tags deliberately kept, such as those
+    /// shadowed by a local binding, are not reported.
+    pub(crate) fn verify_output(&mut self, program: &Program) {
+        if self.keeps_runtime_tags() {
+            return;
+        }
+        let first_item = match program {
+            Program::Module(module) => module.body.first().map(|item| item.span()),
+            Program::Script(script) => script.body.first().map(|stmt| stmt.span()),
+        };
+        if self.is_disabled_file(program.span(), first_item) {
+            return;
+        }
+        match program {
+            Program::Module(module) => self.scan_module(module, self.scan_file(module)),
+            Program::Script(script) => self.scan_script(script, self.scan_file(script)),
+        }
+
+        let mut finder = SurvivorFinder { visitor: self, survivors: Vec::new() };
+        program.visit_with(&mut finder);
+        for (span, message) in finder.survivors {
+            self.report_error(span, "untransformed-output", message);
+        }
+    }
+}
+
+struct SurvivorFinder<'a> {
+    visitor: &'a TransformVisitor,
+    survivors: Vec<(Span, String)>,
+}
+
+impl Visit for SurvivorFinder<'_> {
+    fn visit_jsx_element(&mut self, element: &JSXElement) {
+        let visitor = self.visitor;
+        let name = jsx_name(&element.opening.name);
+        let is_placeholder = matches!(&element.opening.name, JSXElementName::Ident(ident)
+            if ident.sym == visitor.condition_placeholder_ident.sym || ident.sym == SWITCH_PLACEHOLDER || ident.sym == FRAGMENT_SHORTHAND_PLACEHOLDER);
+        if is_placeholder {
+            self.survivors.push((element.span, format!("placeholder `<{name}>` is still in the output; the postTransform stage did not unwrap it")));
+        } else if visitor.is_raw_element(element) {
+            // 有意保留的运行时组件，它的 case 也随之保留
+            for child in &element.children {
+                match child {
+                    JSXElementChild::JSXElement(case) if visitor.is_switch_case_element(case) => case.visit_children_with(self),
+                    child => child.visit_with(self),
+                }
+            }
+            return;
+        } else if visitor
+            .control_tag_name(&element.opening.name)
+            .is_some_and(|(tag, binding)| visitor.is_root_control_tag(tag) && visitor.is_lowerable_tag(binding))
+            || (visitor.is_switch_case_element(element) && switch_is_lowerable(visitor, &element.opening.name))
+        {
+            self.survivors.push((element.span, format!("`<{name}>` is still in the output; the transform did not lower it")));
+        }
+        element.visit_children_with(self);
+    }
+}
+
+/// Whether the `Switch` of a `<Switch.Case>` is one the transform lowers; the
+/// cases of a locally defined Switch stay with it.
+fn switch_is_lowerable(visitor: &TransformVisitor, name: &JSXElementName) -> bool {
+    let JSXElementName::JSXMemberExpr(member) = name else {
+        // compat 的 `<When>`
+        return true;
+    };
+    let mut object = &member.obj;
+    while let JSXObject::JSXMemberExpr(inner) = object {
+        object = &inner.obj;
+    }
+    matches!(object, JSXObject::Ident(ident) if visitor.is_lowerable_tag(ident))
+}
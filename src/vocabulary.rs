@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::fallback::{parse_config_expr, parse_fallback};
+use crate::{
+    Config, AS_ATTR, CASE_MEMBER, CONDITION_TAG, EACH_ATTR, ELSE_ATTR, DEPS_ATTR, ELSE_SHOW_ATTR, FALLBACK_ATTR, FOR_TAG, IF_ATTR, IN_ATTR, KEEP_MOUNTED_ATTR, LAZY_ATTR, MEMO_ATTR, ON_ERROR_ATTR, ORDER_ATTR, RAW_ATTR, VALUES_ATTR,
+    FEATURE_TAG, IS_ATTR, NAME_ATTR, NOT_ATTR, PLATFORM_TAG, PRIORITY_ATTR, SHORT_CIRCUIT_ATTR, SHOW_TAG, SWITCH_TAG, VALUE_ATTR, WHEN_ATTR, WITH_TAG,
+};
+
+/// Tag names of the control components, e.g. `tags: { condition: "When" }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TagNames {
+    pub condition: String,
+    pub switch: String,
+    /// Member of `switch` holding one case, as in `<Switch.Case>`.
+    pub case: String,
+    pub r#for: String,
+    pub show: String,
+    pub with: String,
+    pub feature: String,
+    pub platform: String,
+}
+
+impl Default for TagNames {
+    fn default() -> Self {
+        Self {
+            condition: CONDITION_TAG.into(),
+            switch: SWITCH_TAG.into(),
+            case: CASE_MEMBER.into(),
+            r#for: FOR_TAG.into(),
+            show: SHOW_TAG.into(),
+            with: WITH_TAG.into(),
+            feature: FEATURE_TAG.into(),
+            platform: PLATFORM_TAG.into(),
+        }
+    }
+}
+
+/// Attribute names read from the control components, e.g. `attributes: { if: "test" }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AttributeNames {
+    pub r#if: String,
+    pub r#else: String,
+    pub short_circuit: String,
+    pub value: String,
+    pub is: String,
+    pub r#in: String,
+    pub when: String,
+    pub each: String,
+    pub fallback: String,
+    /// Rank of an else case when a Switch has several, lowest wins.
+    pub priority: String,
+    /// Else branch written on the only case of a Switch.
+    pub else_show: String,
+    /// Flag read by `<Feature>`.
+    pub name: String,
+    /// Inverts a `<Feature>`.
+    pub not: String,
+    /// Element rendered around the children of a condition-like tag.
+    pub r#as: String,
+    /// Hides the children of a condition-like tag instead of unmounting them.
+    pub keep_mounted: String,
+    /// Memoizes a lowered `<Switch>`.
+    pub memo: String,
+    /// Dependencies of a memoized `<Switch>`.
+    pub deps: String,
+    /// Keeps the branches of a `<Switch>` from being built ahead of their test.
+    pub lazy: String,
+    /// Marks the Switch case rendered when the Switch throws.
+    pub on_error: String,
+    /// Evaluation order of a case in a short-circuit `<Switch>`.
+    pub order: String,
+    /// Values a `<Switch value>` must have a case for.
+    pub values: String,
+    /// Leaves a control tag as written, as a runtime component.
+    pub raw: String,
+}
+
+impl Default for AttributeNames {
+    fn default() -> Self {
+        Self {
+            r#if: IF_ATTR.into(),
+            r#else: ELSE_ATTR.into(),
+            short_circuit: SHORT_CIRCUIT_ATTR.into(),
+            value: VALUE_ATTR.into(),
+            is: IS_ATTR.into(),
+            r#in: IN_ATTR.into(),
+            when: WHEN_ATTR.into(),
+            each: EACH_ATTR.into(),
+            fallback: FALLBACK_ATTR.into(),
+            priority: PRIORITY_ATTR.into(),
+            else_show: ELSE_SHOW_ATTR.into(),
+            name: NAME_ATTR.into(),
+            not: NOT_ATTR.into(),
+            r#as: AS_ATTR.into(),
+            keep_mounted: KEEP_MOUNTED_ATTR.into(),
+            memo: MEMO_ATTR.into(),
+            deps: DEPS_ATTR.into(),
+            lazy: LAZY_ATTR.into(),
+            on_error: ON_ERROR_ATTR.into(),
+            order: ORDER_ATTR.into(),
+            values: VALUES_ATTR.into(),
+            raw: RAW_ATTR.into(),
+        }
+    }
+}
+
+/// Keys a `vocabularyFile` may set: the subset of the config that describes
+/// which markup the plugin recognizes. The file is merged into the config as
+/// written, so `elseAliases`, `unlessTag` and the `compat` preset sit at the
+/// top level as they do in `.swcrc` rather than under `tags` / `attributes`.
+static VOCABULARY_KEYS: [&str; 5] = ["tags", "attributes", "elseAliases", "unlessTag", "compat"];
+
+impl Config {
+    /// Parses the plugin's JSON config. A `vocabularyFile` is loaded first and
+    /// the inline options are layered on top, so a shared vocabulary can still
+    /// be overridden per project.
+    pub fn from_json(json: &str) -> Result<Config, String> {
+        let mut config: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+
+        if let Some(file) = config.get("vocabularyFile").and_then(Value::as_str) {
+            let path = resolve_vocabulary_path(file);
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|err| format!("cannot read vocabularyFile {}: {}", path.display(), err))?;
+            let vocabulary: Value = serde_json::from_str(&contents)
+                .map_err(|err| format!("invalid vocabularyFile {}: {}", path.display(), err))?;
+            check_vocabulary(&vocabulary).map_err(|err| format!("invalid vocabularyFile {}: {}", path.display(), err))?;
+            config = merge(vocabulary, config);
+        }
+
+        let config: Config = serde_json::from_value(config).map_err(|err| err.to_string())?;
+        parse_fallback(&config.fallback)?;
+        if let Some(lookup) = &config.feature_lookup {
+            parse_config_expr("featureLookup", lookup)?;
+        }
+        parse_config_expr("memoHook", &config.memo_hook)?;
+        check_features(&config)?;
+        Ok(config)
+    }
+}
+
+fn check_vocabulary(vocabulary: &Value) -> Result<(), String> {
+    let Value::Object(keys) = vocabulary else {
+        return Err("expected an object".into());
+    };
+    match keys.keys().find(|key| !VOCABULARY_KEYS.contains(&key.as_str())) {
+        Some(key) => Err(format!("unknown field `{key}`, expected one of {}", VOCABULARY_KEYS.map(|key| format!("`{key}`")).join(", "))),
+        None => Ok(()),
+    }
+}
+
+/// Rejects options whose subsystem was left out of this build, rather than
+/// silently ignoring them.
+fn check_features(config: &Config) -> Result<(), String> {
+    let gated = [
+        ("compat", "compat", config.compat.is_some(), cfg!(feature = "compat")),
+        ("define", "defines", !config.define.is_empty(), cfg!(feature = "defines")),
+        ("env", "defines", !config.env.is_empty(), cfg!(feature = "defines")),
+        ("annotateBranches", "instrumentation", config.annotate_branches, cfg!(feature = "instrumentation")),
+    ];
+    match gated.into_iter().find(|(_, _, used, enabled)| *used && !enabled) {
+        Some((option, feature, ..)) => Err(format!("{option} needs the plugin built with the `{feature}` feature")),
+        None => Ok(()),
+    }
+}
+
+/// Relative paths are resolved against the project root. Inside the wasm
+/// sandbox the host maps it to `/cwd`.
+fn resolve_vocabulary_path(file: &str) -> PathBuf {
+    let path = Path::new(file);
+    if cfg!(target_arch = "wasm32") && path.is_relative() {
+        Path::new("/cwd").join(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Deep-merges JSON objects, with `overrides` winning on conflicts.
+fn merge(base: Value, overrides: Value) -> Value {
+    match (base, overrides) {
+        (Value::Object(mut base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                let merged = match base.remove(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+            Value::Object(base)
+        }
+        (_, overrides) => overrides,
+    }
+}
@@ -0,0 +1,72 @@
+use swc_core::ecma::ast::*;
+
+use crate::TransformVisitor;
+
+impl TransformVisitor {
+    /// Lowers `<With user={getUser()} theme={t}>…</With>` into
+    /// `((user, theme) => <>…</>)(getUser(), t)`, so each attribute is
+    /// evaluated once and bound to its name inside the children.
+    ///
+    /// Returns `None` (leaving the element untouched) when an attribute can't
+    /// become a parameter: spreads, namespaced or non-identifier names, and
+    /// valueless attributes.
+    pub(crate) fn create_with_expr(&self, element: &JSXElement) -> Option<Expr> {
+        let mut params = Vec::with_capacity(element.opening.attrs.len());
+        let mut args = Vec::with_capacity(element.opening.attrs.len());
+
+        for attr in &element.opening.attrs {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(name), value: Some(value), .. }) = attr else {
+                return None;
+            };
+            // data-foo 之类的属性名不是合法的参数名
+            if name.sym.contains('-') {
+                return None;
+            }
+            let value = match value {
+                JSXAttrValue::Lit(lit) => Expr::Lit(lit.clone()),
+                JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. }) => (**expr).clone(),
+                JSXAttrValue::JSXElement(element) => Expr::JSXElement(element.clone()),
+                JSXAttrValue::JSXFragment(fragment) => Expr::JSXFragment(fragment.clone()),
+                _ => return None,
+            };
+
+            params.push(Pat::Ident(BindingIdent {
+                id: Ident::new(name.sym.clone(), name.span, self.syntax_context),
+                type_ann: None,
+            }));
+            args.push(ExprOrSpread { spread: None, expr: Box::new(value) });
+        }
+
+        if params.is_empty() {
+            return None;
+        }
+
+        let span = element.span;
+        let body = JSXFragment {
+            span,
+            opening: JSXOpeningFragment { span },
+            children: element.children.clone(),
+            closing: JSXClosingFragment { span },
+        };
+
+        Some(Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(Box::new(Expr::Paren(ParenExpr {
+                span,
+                expr: Box::new(Expr::Arrow(ArrowExpr {
+                    span,
+                    ctxt: self.syntax_context,
+                    params,
+                    body: Box::new(BlockStmtOrExpr::Expr(Box::new(Expr::JSXFragment(body)))),
+                    is_async: false,
+                    is_generator: false,
+                    type_params: None,
+                    return_type: None,
+                })),
+            }))),
+            args,
+            type_args: None,
+            ctxt: self.syntax_context,
+        }))
+    }
+}
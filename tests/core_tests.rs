@@ -0,0 +1,78 @@
+//! Checks that only matter for builds without the optional subsystems:
+//!
+//! ```bash
+//! cargo test --no-default-features --test core_tests
+//! ```
+#![cfg(not(feature = "full"))]
+
+use swc_condition_plugin::{transform_program, Config};
+use swc_core::common::{sync::Lrc, FileName, SourceMap};
+use swc_core::ecma::ast::Program;
+use swc_core::ecma::codegen::{text_writer::JsWriter, Emitter};
+use swc_core::ecma::parser::{lexer::Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+#[test]
+fn test_core_config_still_parses() {
+    let config = Config::from_json(r#"{ "mergeGuards": true, "fragment": "Fragment" }"#);
+    assert!(config.is_ok(), "{:?}", config.err());
+}
+
+#[test]
+fn test_options_of_disabled_features_are_rejected() {
+    let cases = [
+        (cfg!(feature = "compat"), r#"{ "compat": "jsx-control-statements" }"#, "compat needs the plugin built with the `compat` feature"),
+        (cfg!(feature = "defines"), r#"{ "define": { "DEBUG": false } }"#, "define needs the plugin built with the `defines` feature"),
+        (cfg!(feature = "defines"), r#"{ "env": { "NODE_ENV": "production" } }"#, "env needs the plugin built with the `defines` feature"),
+        (cfg!(feature = "instrumentation"), r#"{ "annotateBranches": true }"#, "annotateBranches needs the plugin built with the `instrumentation` feature"),
+    ];
+    for (enabled, json, message) in cases {
+        match Config::from_json(json) {
+            Ok(_) => assert!(enabled, "{json} should be rejected"),
+            Err(err) => assert_eq!(err, message),
+        }
+    }
+}
+
+#[test]
+fn test_literal_feature_and_platform_conditions_fold_without_defines() {
+    let input = r#"
+    const App = () => <main>
+      <Condition if={false}><Debug/></Condition>
+      <Feature name="checkout-v2"><NewCheckout/></Feature>
+      <Platform native><TextInput/></Platform>
+      <Platform web><input/></Platform>
+    </main>;
+    "#;
+    let config = Config::from_json(r#"{ "features": { "checkout-v2": true }, "platform": "web" }"#).unwrap();
+    let output = transform(input, config);
+    assert!(output.contains("<main>\n      \n      <NewCheckout/>\n      \n      <input/>\n    </main>"), "{output}");
+
+    // 环境变量和 define 仍然要 `defines` feature
+    let output = transform("\nconst App = () => <Condition if={process.env.DEBUG}><Debug/></Condition>;", Config::default());
+    assert!(output.contains("process.env.DEBUG"), "{output}");
+}
+
+fn transform(input: &str, config: Config) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Anon.into(), input.to_string());
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsSyntax { tsx: true, ..Default::default() }),
+        Default::default(),
+        StringInput::from(&*fm),
+        None,
+    );
+    let module = Parser::new_from(lexer).parse_module().expect("Failed to parse input");
+    let program = transform_program(Program::Module(module), config);
+
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter.emit_program(&program).expect("Failed to emit");
+    }
+    String::from_utf8(buf).expect("Invalid UTF-8")
+}
@@ -0,0 +1,333 @@
+//! Golden semantic tests: each input is rendered with mock runtime control
+//! components, its transformed output without them, and the render trees
+//! must match for every set of props. Needs `node` on the PATH:
+//!
+//! ```bash
+//! cargo test --features semantic-tests --test semantic_tests
+//! ```
+#![cfg(feature = "semantic-tests")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use swc_condition_plugin::{transform_program, Config};
+use swc_core::common::{SourceMap, DUMMY_SP};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::{
+    codegen::{text_writer::JsWriter, Emitter},
+    parser::{lexer::Lexer, Parser, StringInput, Syntax, TsSyntax},
+    visit::{VisitMut, VisitMutWith},
+};
+
+/// `h`, `render` and the runtime semantics of the control components.
+const PRELUDE: &str = r#"
+const Fragment = Symbol("Fragment");
+const React = { Fragment };
+function h(type, props, ...children) {
+  return { type, props: { ...props, children } };
+}
+function render(node) {
+  if (node == null || typeof node === "boolean") return [];
+  if (Array.isArray(node)) return merge(node.flatMap(render));
+  if (typeof node !== "object") return [String(node)];
+  const { type, props } = node;
+  if (type === Fragment) return render(props.children);
+  if (typeof type === "function") return render(type(props));
+  const attrs = Object.fromEntries(Object.entries(props).filter(([name]) => name !== "children" && name !== "key"));
+  return [{ type, attrs, children: render(props.children) }];
+}
+function merge(nodes) {
+  return nodes.reduce((merged, node) => {
+    if (typeof node === "string" && typeof merged[merged.length - 1] === "string") merged[merged.length - 1] += node;
+    else merged.push(node);
+    return merged;
+  }, []);
+}
+function Condition(props) {
+  return props.if ? props.children : null;
+}
+function Unless(props) {
+  return props.if ? null : props.children;
+}
+function Switch({ value, shortCircuit, children }) {
+  const cases = [children].flat(Infinity).filter((child) => child && typeof child === "object");
+  const isElse = (child) => child.type === Switch.Default || child.type === Switch.Else || child.props.else;
+  const matches = ({ props }) =>
+    "if" in props ? Boolean(props.if)
+      : "is" in props ? props.is === value
+      : "in" in props ? props.in.includes(value)
+      : "when" in props ? Boolean(props.when(value))
+      : false;
+  const matched = cases.filter((child) => !isElse(child) && matches(child));
+  const rendered = shortCircuit ? matched.slice(0, 1) : matched;
+  if (rendered.length > 0) return rendered.map((child) => child.props.children);
+  const fallback = cases.filter(isElse).pop();
+  if (fallback) return fallback.props.children;
+  const only = cases.length === 1 ? cases[0].props.elseShow : undefined;
+  return only ?? null;
+}
+Switch.Case = () => null;
+Switch.Default = () => null;
+Switch.Else = () => null;
+"#;
+
+/// Asserts that `source` (defining `App`) renders the same before and after
+/// the transform for each props object literal in `props`.
+fn assert_same_render(source: &str, props: &[&str], config: Config) {
+    let transformed = transform_program(Program::Module(parse_module(source)), config).expect_module();
+    let output = emit_js(transformed.clone());
+    let before = render_all(parse_module(source), props);
+    let after = render_all(transformed, props);
+    assert_eq!(after, before, "Transformed output renders differently.\nOutput: {output}");
+}
+
+fn render_all(mut module: Module, props: &[&str]) -> Vec<String> {
+    module.visit_mut_with(&mut JsxToCalls);
+    let program = emit_js(module);
+    let renders: String = props.iter()
+        .map(|props| format!("console.log(JSON.stringify(render(h(App, {props}))));\n"))
+        .collect();
+    run_node(&format!("{PRELUDE}\n{program}\n{renders}")).lines().map(String::from).collect()
+}
+
+fn run_node(script: &str) -> String {
+    let mut child = Command::new("node")
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("semantic tests need `node` on the PATH");
+    child.stdin.take().unwrap().write_all(script.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "node failed: {}\n{script}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn parse_module(input: &str) -> Module {
+    let syntax = Syntax::Typescript(TsSyntax {
+        tsx: true,
+        ..Default::default()
+    });
+    let lexer = Lexer::new(syntax, Default::default(), StringInput::new(input, Default::default(), Default::default()), None);
+    Parser::new_from(lexer).parse_module().expect("Failed to parse input")
+}
+
+fn emit_js(module: Module) -> String {
+    let cm = Arc::new(SourceMap::default());
+    let mut buf = vec![];
+    {
+        let writer = JsWriter::new(cm.clone(), "\n", &mut buf, None);
+        let mut emitter = Emitter { cfg: Default::default(), cm, comments: None, wr: writer };
+        emitter.emit_module(&module).expect("Failed to emit");
+    }
+    String::from_utf8(buf).expect("Invalid UTF-8")
+}
+
+/// Lowers JSX to `h(type, props, ...children)` calls, like the classic
+/// runtime, so node can run the programs.
+struct JsxToCalls;
+
+impl VisitMut for JsxToCalls {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+        match expr {
+            Expr::JSXElement(element) => *expr = element_call(element),
+            Expr::JSXFragment(fragment) => *expr = h_call(member("React", "Fragment"), None, &fragment.children),
+            _ => {}
+        }
+    }
+}
+
+fn element_call(element: &JSXElement) -> Expr {
+    let props = element.opening.attrs.iter().map(|attr| match attr {
+        JSXAttrOrSpread::SpreadElement(spread) => PropOrSpread::Spread(SpreadElement { dot3_token: DUMMY_SP, expr: spread.expr.clone() }),
+        JSXAttrOrSpread::JSXAttr(attr) => {
+            let name = match &attr.name {
+                JSXAttrName::Ident(name) => name.sym.to_string(),
+                JSXAttrName::JSXNamespacedName(name) => format!("{}:{}", name.ns.sym, name.name.sym),
+            };
+            let value = match &attr.value {
+                None => Expr::Lit(Lit::Bool(Bool { span: DUMMY_SP, value: true })),
+                Some(JSXAttrValue::Lit(lit)) => Expr::Lit(lit.clone()),
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. })) => (**expr).clone(),
+                Some(JSXAttrValue::JSXElement(element)) => element_call(element),
+                Some(JSXAttrValue::JSXFragment(fragment)) => h_call(member("React", "Fragment"), None, &fragment.children),
+                Some(_) => Expr::Lit(Lit::Bool(Bool { span: DUMMY_SP, value: true })),
+            };
+            PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Str(name.as_str().into()),
+                value: Box::new(value),
+            })))
+        }
+    }).collect();
+    let props = Expr::Object(ObjectLit { span: DUMMY_SP, props });
+    h_call(element_type(&element.opening.name), Some(props), &element.children)
+}
+
+fn element_type(name: &JSXElementName) -> Expr {
+    match name {
+        JSXElementName::Ident(ident) if ident.sym.starts_with(|c: char| c.is_ascii_lowercase()) => Expr::Lit(Lit::Str(ident.sym.as_str().into())),
+        JSXElementName::Ident(ident) => Expr::Ident(ident.clone()),
+        JSXElementName::JSXMemberExpr(member_expr) => {
+            let obj = match &member_expr.obj {
+                JSXObject::Ident(ident) => Expr::Ident(ident.clone()),
+                JSXObject::JSXMemberExpr(inner) => element_type(&JSXElementName::JSXMemberExpr((**inner).clone())),
+            };
+            Expr::Member(MemberExpr { span: DUMMY_SP, obj: Box::new(obj), prop: MemberProp::Ident(member_expr.prop.clone()) })
+        }
+        JSXElementName::JSXNamespacedName(name) => Expr::Lit(Lit::Str(format!("{}:{}", name.ns.sym, name.name.sym).into())),
+    }
+}
+
+fn h_call(element_type: Expr, props: Option<Expr>, children: &[JSXElementChild]) -> Expr {
+    let props = props.unwrap_or(Expr::Lit(Lit::Null(Null { span: DUMMY_SP })));
+    let args = [element_type, props].into_iter()
+        .map(|expr| ExprOrSpread { spread: None, expr: Box::new(expr) })
+        .chain(children.iter().filter_map(child_arg))
+        .collect();
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new_no_ctxt("h".into(), DUMMY_SP)))),
+        args,
+        ..Default::default()
+    })
+}
+
+fn child_arg(child: &JSXElementChild) -> Option<ExprOrSpread> {
+    let (spread, expr) = match child {
+        JSXElementChild::JSXText(text) => (None, Expr::Lit(Lit::Str(jsx_text(&text.value)?.into()))),
+        JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(expr), .. }) => (None, (**expr).clone()),
+        JSXElementChild::JSXExprContainer(_) => return None,
+        JSXElementChild::JSXSpreadChild(spread) => (Some(DUMMY_SP), (*spread.expr).clone()),
+        JSXElementChild::JSXElement(element) => (None, element_call(element)),
+        JSXElementChild::JSXFragment(fragment) => (None, h_call(member("React", "Fragment"), None, &fragment.children)),
+    };
+    Some(ExprOrSpread { spread, expr: Box::new(expr) })
+}
+
+/// JSX text as React reads it: lines are trimmed and blank lines dropped.
+fn jsx_text(value: &str) -> Option<String> {
+    let lines: Vec<&str> = value.split('\n').collect();
+    let last = lines.len() - 1;
+    let text = lines.iter().enumerate()
+        .map(|(index, line)| {
+            let line = if index > 0 { line.trim_start() } else { line };
+            if index < last { line.trim_end() } else { line }
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+fn member(obj: &str, prop: &str) -> Expr {
+    Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(Expr::Ident(Ident::new_no_ctxt(obj.into(), DUMMY_SP))),
+        prop: MemberProp::Ident(IdentName::new(prop.into(), DUMMY_SP)),
+    })
+}
+
+#[test]
+fn test_condition_and_unless_render_the_same() {
+    let input = r#"
+    function App({ show, count, user }) {
+      const badge = <Condition if={count}><span>{count}</span></Condition>;
+      return (
+        <div>
+          <Condition if={show}><p>Shown</p> text</Condition>
+          <Unless if={user}><button>Log in</button></Unless>
+          <Condition if={user && user.admin}><b>admin</b><Unless if={show}><i>hidden</i></Unless></Condition>
+          {badge}
+        </div>
+      );
+    }
+    "#;
+
+    let props = [
+        "{ show: true, count: 0, user: null }",
+        "{ show: false, count: 3, user: { admin: true } }",
+        "{ show: 1, count: '', user: { admin: false } }",
+    ];
+    assert_same_render(input, &props, Config::default());
+}
+
+#[test]
+fn test_switch_modes_render_the_same() {
+    let input = r#"
+    function App({ status, priority, a, b }) {
+      const parallel = (
+        <Switch>
+          <Switch.Case if={a}><p>a</p></Switch.Case>
+          <Switch.Case if={b}><p>b</p><p>b2</p></Switch.Case>
+          <Switch.Case else>neither</Switch.Case>
+        </Switch>
+      );
+      return (
+        <section>
+          {parallel}
+          <Switch shortCircuit>
+            <Switch.Case if={status === 'urgent' ? priority > 5 : priority > 8}><Alert /></Switch.Case>
+            <Switch.Case if={a || b}>some</Switch.Case>
+            <Switch.Default>none</Switch.Default>
+          </Switch>
+          <Switch value={status} shortCircuit>
+            <Switch.Case is="loading">loading</Switch.Case>
+            <Switch.Case in={["error", "timeout"]}>failed</Switch.Case>
+            <Switch.Case when={(s) => s.length > 6}>long</Switch.Case>
+          </Switch>
+        </section>
+      );
+    }
+    function Alert() {
+      return <strong>alert</strong>;
+    }
+    "#;
+
+    let mut props = Vec::new();
+    for status in ["'urgent'", "'loading'", "'timeout'", "'finished'"] {
+        for priority in [3, 7, 9] {
+            for (a, b) in [(true, false), (false, true), (true, true), (false, false)] {
+                props.push(format!("{{ status: {status}, priority: {priority}, a: {a}, b: {b} }}"));
+            }
+        }
+    }
+    let props: Vec<&str> = props.iter().map(String::as_str).collect();
+    assert_same_render(input, &props, Config::default());
+}
+
+#[test]
+fn test_switch_options_render_the_same() {
+    let input = r#"
+    function App({ user, mode }) {
+      return (
+        <div>
+          <Switch>
+            <Switch.Case if={user.admin}><Switch shortCircuit><Switch.Case if={mode}>edit</Switch.Case></Switch></Switch.Case>
+            <Switch.Case if={user.premium} key="premium">premium</Switch.Case>
+            <Switch.Else>basic</Switch.Else>
+          </Switch>
+          <Switch>
+            <Switch.Case if={mode === 'dark'} elseShow={<span>light</span>}>dark</Switch.Case>
+          </Switch>
+        </div>
+      );
+    }
+    "#;
+
+    let props = [
+        "{ user: { admin: true }, mode: 'dark' }",
+        "{ user: { admin: true, premium: true }, mode: null }",
+        "{ user: { premium: true }, mode: 'light' }",
+        "{ user: {}, mode: undefined }",
+    ];
+    let config = Config {
+        hoist_case_conditions: true,
+        transform_nested: true,
+        case_keys: true,
+        ..Config::default()
+    };
+    assert_same_render(input, &props, config);
+}
@@ -1,11 +1,28 @@
-use swc_condition_plugin::{TransformVisitor, PostTransformVisitor};
+use swc_condition_plugin::{
+    test_pass_with_config, BooleanStrategy, BranchWrapper, Codegen, DiagnosticLevel, OutputMode, transform_program, transform_program_with_host, ConditionHelper, Config, TagNames, Target, TransformFactory, TransformHost,
+    StagesConfig,
+};
+#[cfg(feature = "cli")]
+use swc_condition_plugin::{analyze, audit, condition_pass, recommended_chain, transform_batch, transform_program_with_span_mappings, ControlSiteKind, SpanMapping, SwitchMode, TransformVisitor};
+#[cfg(feature = "compat")]
+use swc_condition_plugin::Compat;
+#[cfg(feature = "defines")]
+use swc_condition_plugin::Preset;
+use swc_core::ecma::ast::{BinExpr, BinaryOp, CondExpr, Expr, Ident, JSXMemberExpr, Lit, Module, Null, Program, UnaryExpr, UnaryOp};
 use swc_core::ecma::{
     parser::{lexer::Lexer, Parser, StringInput, Syntax, TsSyntax},
     codegen::{text_writer::JsWriter, Emitter},
-    visit::FoldWith,
+    visit::{Visit, VisitMutWith, VisitWith},
 };
-use swc_core::common::SourceMap;
-use std::sync::Arc;
+use swc_core::common::errors::{DiagnosticBuilder, Emitter as DiagnosticEmitter, Handler, Level, HANDLER};
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::{FileName, SourceMap, Span, SyntaxContext};
+#[cfg(feature = "instrumentation")]
+use swc_core::common::{BytePos, DUMMY_SP, GLOBALS};
+#[cfg(feature = "instrumentation")]
+use swc_core::ecma::visit::VisitMut;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 #[test]
 fn test_basic_jsx_condition() {
@@ -121,7 +138,7 @@ fn test_function_call_condition() {
     function App({ items }) {
       return (
         <div>
-          <React.Fragment>{Boolean(items.length > 0) ? <><ul>
+          <React.Fragment>{items.length > 0 ? <><ul>
               {items.map((item)=><li key={item.id}>{item.name}</li>)}
             </ul></> : null}</React.Fragment>
         </div>
@@ -169,6 +186,58 @@ fn test_nested_conditions() {
     test_transform(input, expected);
 }
 
+#[test]
+fn test_transform_nested() {
+    let input = r#"
+    function App({ showOuter, showInner, status }) {
+      const panel = <Condition if={showOuter}>
+        <Switch shortCircuit>
+          <Switch.Case if={status === "ok"}><Ok/></Switch.Case>
+          <Switch.Case else><Fail/></Switch.Case>
+        </Switch>
+      </Condition>;
+      if (status) {
+        return <Condition if={showOuter}>
+          <Condition if={showInner}><Inner/></Condition>
+        </Condition>;
+      }
+      return (
+        <div>
+          <Condition if={showOuter}>
+            <div>
+              <p>Outer content</p>
+              <Condition if={showInner}>
+                <p>Inner content</p>
+              </Condition>
+            </div>
+          </Condition>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ showOuter, showInner, status }) {
+      const panel = Boolean(showOuter) ? status === "ok" ? <Ok/> : <Fail/> : null;
+      if (status) {
+        return showOuter ? showInner ? <><Inner/></> : null : null;
+      }
+      return (
+        <div>
+          <React.Fragment>{Boolean(showOuter) ? <><div>
+              <p>Outer content</p>
+              <React.Fragment>{Boolean(showInner) ? <>
+                <p>Inner content</p>
+              </> : null}</React.Fragment>
+            </div></> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { transform_nested: true, ..Default::default() });
+}
+
 #[test]
 fn test_multiple_parallel_conditions() {
     let input = r#"
@@ -500,10 +569,8 @@ fn test_switch_in_array_map() {
       return (
         <div>
           {users.map((user)=>(
-            <React.Fragment>
-              {user.isAdmin ? <><AdminBadge user={user}/></> : null}
-              {user.isPremium ? <><PremiumBadge user={user}/></> : null}
-            </React.Fragment>
+            <React.Fragment key={user.id}>{user.isAdmin ? <><AdminBadge user={user}/></> : null}
+              {user.isPremium ? <><PremiumBadge user={user}/></> : null}</React.Fragment>
           ))}
         </div>
       )
@@ -534,7 +601,7 @@ fn test_switch_case_with_complex_ternary_conditions() {
     function App({ status, priority }) {
       return (
         <React.Fragment>
-          {status === 'urgent' ? priority > 5 : priority > 8 ? <><HighPriorityAlert/></> : null}
+          {(status === 'urgent' ? priority > 5 : priority > 8) ? <><HighPriorityAlert/></> : null}
           {status === 'normal' ? <><NormalAlert/></> : null}
         </React.Fragment>
       )
@@ -542,6 +609,26 @@ fn test_switch_case_with_complex_ternary_conditions() {
     "#;
 
     test_transform(input, expected);
+
+    // 短路链里同样需要括号，否则三元条件会和外层链重新分组
+    let input = r#"
+    function App({ status, priority, fallback }) {
+      const alert = <Switch shortCircuit>
+        <Switch.Case if={status === 'urgent' ? priority > 5 : priority > 8}><HighPriorityAlert/></Switch.Case>
+        <Switch.Case if={fallback ?? status === 'normal'}><NormalAlert/></Switch.Case>
+      </Switch>;
+      return alert;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ status, priority, fallback }) {
+      const alert = (status === 'urgent' ? priority > 5 : priority > 8) ? <HighPriorityAlert/> : fallback ?? status === 'normal' ? <NormalAlert/> : null;
+      return alert;
+    }
+    "#;
+
+    test_transform(input, expected);
 }
 
 #[test]
@@ -824,12 +911,30 @@ fn test_switch_single_if_with_else_non_short_circuit() {
 }
 
 fn test_transform(input: &str, expected: &str) {
+    test_transform_with_config(input, expected, Config::default());
+}
+
+fn test_transform_with_config(input: &str, expected: &str, config: Config) {
+    test_transform_with(input, expected, |mut module| {
+        let (mut transform, mut post_transform) = test_pass_with_config(config);
+        module.visit_mut_with(&mut transform);
+        module.visit_mut_with(&mut post_transform);
+        module
+    });
+}
+
+fn test_transform_program(input: &str, expected: &str, config: Config) {
+    test_transform_with(input, expected, |module| {
+        transform_program(Program::Module(module), config).expect_module()
+    });
+}
+
+fn parse_module(input: &str) -> Module {
     let syntax = Syntax::Typescript(TsSyntax {
         tsx: true,
         ..Default::default()
     });
 
-    let cm = Arc::new(SourceMap::default());
     let lexer = Lexer::new(
         syntax,
         Default::default(),
@@ -837,11 +942,41 @@ fn test_transform(input: &str, expected: &str) {
         None,
     );
     let mut parser = Parser::new_from(lexer);
-    let module = parser.parse_module().expect("Failed to parse input");
+    parser.parse_module().expect("Failed to parse input")
+}
+
+struct CollectingEmitter(Arc<Mutex<Vec<(Level, String)>>>);
+
+impl DiagnosticEmitter for CollectingEmitter {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        self.0.lock().unwrap().push((db.level, db.message()));
+    }
+}
+
+/// Runs the whole pipeline with a handler installed and returns every
+/// diagnostic it reported.
+fn collect_diagnostics(input: &str, config: Config) -> Vec<(Level, String)> {
+    let diagnostics = Arc::new(Mutex::new(Vec::new()));
+    let handler = Handler::with_emitter(true, false, Box::new(CollectingEmitter(diagnostics.clone())));
+    let module = parse_module(input);
+    HANDLER.set(&handler, || {
+        transform_program(Program::Module(module), config);
+    });
+    let collected = diagnostics.lock().unwrap().clone();
+    collected
+}
 
-    let transformed = module.fold_with(&mut TransformVisitor::default());
-    let final_result = transformed.fold_with(&mut PostTransformVisitor);
+/// Transforms `input` and returns the emitted code with placeholders removed.
+fn transform_output(input: &str, config: Config) -> String {
+    let (mut transform, mut post_transform) = test_pass_with_config(config);
+    let mut module = parse_module(input);
+    module.visit_mut_with(&mut transform);
+    module.visit_mut_with(&mut post_transform);
+    emit_module(&module)
+}
 
+fn emit_module(module: &Module) -> String {
+    let cm = Arc::new(SourceMap::default());
     let mut buf = vec![];
     {
         let writer = JsWriter::new(cm.clone(), "\n", &mut buf, None);
@@ -851,20 +986,23 @@ fn test_transform(input: &str, expected: &str) {
             comments: None,
             wr: writer,
         };
-        emitter.emit_module(&final_result).expect("Failed to emit");
+        emitter.emit_module(module).expect("Failed to emit");
     }
 
     let output = String::from_utf8(buf).expect("Invalid UTF-8");
-    
-    let cleaned_output = output
+
+    output
         .replace("<__CONDITION_PLACEHOLDER__>", "")
         .replace("</__CONDITION_PLACEHOLDER__>", "")
         .replace("<__DIRECT_EXPR__>", "")
-        .replace("</__DIRECT_EXPR__>", "");
+        .replace("</__DIRECT_EXPR__>", "")
+}
+
+fn test_transform_with(input: &str, expected: &str, run: impl FnOnce(Module) -> Module) {
+    let cleaned_output = emit_module(&run(parse_module(input)));
 
     let normalize = |s: &str| {
-        s.trim()
-            .split_whitespace()
+        s.split_whitespace()
             .collect::<Vec<_>>()
             .join(" ")
             .replace("< >", "<>")
@@ -878,7 +1016,6 @@ fn test_transform(input: &str, expected: &str) {
             .replace(" ;", "")
             .replace(";", "")
             .replace("(item)=>", "item =>")
-            .replace("item => ", "item => ")
             .replace("=> <", "=>  <")
             .replace("alt=\"Avatar\"/>", "alt=\"Avatar\" />")
             .replace("<Switch/>", "<Switch />")
@@ -1128,3 +1265,3928 @@ fn test_switch_assignment_context_with_map() {
 
     test_transform(input, expected);
 }
+
+#[test]
+fn test_merge_guards_jsx_context() {
+    let input = r#"
+    function App({ ready, user }) {
+      return (
+        <div>
+          {ready && <Condition if={user}>
+            <p>Hello</p>
+          </Condition>}
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ ready, user }) {
+      return (
+        <div>
+          {Boolean(ready && user) ? <><p>Hello</p></> : null}
+        </div>
+      )
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { merge_guards: true, ..Default::default() });
+}
+
+#[test]
+fn test_merge_guards_return_context() {
+    let input = r#"
+    function App({ ready, a, b }) {
+      return ready && <Condition if={a || b}>
+        <p>Hello</p>
+      </Condition>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ ready, a, b }) {
+      return ready && (a || b) ? <><p>Hello</p></> : null
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { merge_guards: true, ..Default::default() });
+}
+
+#[test]
+fn test_merge_guards_disabled_by_default() {
+    let input = r#"
+    function App({ ready, user }) {
+      return (
+        <div>
+          {ready && <Condition if={user}>
+            <p>Hello</p>
+          </Condition>}
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ ready, user }) {
+      return (
+        <div>
+          {ready && (Boolean(user) ? <><p>Hello</p></> : null)}
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_value_short_circuit() {
+    let input = r#"
+    function App({ status }) {
+      return (
+        <div>
+          <Switch value={status} shortCircuit>
+            <Switch.Case is="loading">
+              <Spinner />
+            </Switch.Case>
+            <Switch.Case is={ERROR}>
+              <ErrorView />
+            </Switch.Case>
+            <Switch.Case else>
+              <Done />
+            </Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ status }) {
+      return (
+        <div>
+          <React.Fragment>{status === "loading" ? <Spinner/> : status === ERROR ? <ErrorView/> : <Done/>}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_value_hoisted_return_context() {
+    let input = r#"
+    function App() {
+      return <Switch value={getStatus()}>
+        <Switch.Case is="ready">
+          <Ready />
+        </Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function App() {
+      return ((__switchValue)=>__switchValue === "ready" ? <Ready/> : null)(getStatus())
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_value_hoisted_with_await_and_yield() {
+    // 箭头函数里不能 await / yield，临时变量改为就地赋值
+    let input = r#"
+    async function App() {
+      return <Switch value={getStatus()}>
+        <Switch.Case is={await loadExpected()}>
+          <Ready />
+        </Switch.Case>
+      </Switch>
+    }
+
+    function* Steps() {
+      yield <Switch value={getStep()}>
+        <Switch.Case is="a">
+          <A value={yield "a"} />
+        </Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    async function App() {
+      let __switchValue;
+      return (__switchValue = getStatus(), __switchValue === await loadExpected() ? <Ready/> : null)
+    }
+
+    function* Steps() {
+      let __switchValue2;
+      yield <React.Fragment>{(__switchValue2 = getStep(), __switchValue2 === "a" ? <><A value={yield "a"}/></> : null)}</React.Fragment>
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_value_hoisted_parallel() {
+    let input = r#"
+    function App({ store }) {
+      return (
+        <div>
+          <Switch value={store.getStatus()}>
+            <Switch.Case is="a">
+              <A />
+            </Switch.Case>
+            <Switch.Case is="b">
+              <B />
+            </Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ store }) {
+      return (
+        <div>
+          <React.Fragment>{((__switchValue)=><>{__switchValue === "a" ? <><A/></> : null}{__switchValue === "b" ? <><B/></> : null}</>)(store.getStatus())}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_value_in_membership() {
+    let input = r#"
+    function App({ status }) {
+      return (
+        <div>
+          <Switch value={status} shortCircuit>
+            <Switch.Case in={["idle", "loading"]}>
+              <Spinner />
+            </Switch.Case>
+            <Switch.Case in={FAILED_STATES}>
+              <ErrorView />
+            </Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ status }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean([ "idle", "loading" ].includes(status)) ? <Spinner/> : Boolean(FAILED_STATES.includes(status)) ? <ErrorView/> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_value_in_membership_hoisted() {
+    let input = r#"
+    function App() {
+      return <Switch value={getStatus()}>
+        <Switch.Case in={["a", "b"]}>
+          <Ready />
+        </Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function App() {
+      return ((__switchValue)=>[ "a", "b" ].includes(__switchValue) ? <Ready/> : null)(getStatus())
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_value_when_predicate() {
+    let input = r#"
+    function App({ name }) {
+      return (
+        <div>
+          <Switch value={name} shortCircuit>
+            <Switch.Case when={v => v.length > 3}>
+              <Long />
+            </Switch.Case>
+            <Switch.Case when={isEmpty}>
+              <Empty />
+            </Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ name }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(((v)=>v.length > 3)(name)) ? <Long/> : Boolean(isEmpty(name)) ? <Empty/> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_fuse_case_conditions_return_context() {
+    let input = r#"
+    function App({ isAdmin, canEdit }) {
+      return <Switch>
+        <Switch.Case if={isAdmin}>
+          <Condition if={canEdit || force}>
+            <Editor />
+          </Condition>
+        </Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ isAdmin, canEdit }) {
+      return isAdmin && (canEdit || force) ? <Editor/> : null
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { fuse_case_conditions: true, ..Default::default() });
+}
+
+#[test]
+fn test_fuse_case_conditions_skipped_with_else() {
+    let input = r#"
+    function App({ a, b }) {
+      return (
+        <div>
+          <Switch shortCircuit>
+            <Switch.Case if={a}>
+              <Condition if={b}>
+                <A />
+              </Condition>
+            </Switch.Case>
+            <Switch.Case else>
+              <Fallback />
+            </Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a, b }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(a) ? <Condition if={b}>
+                <A/>
+              </Condition> : <Fallback/>}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { fuse_case_conditions: true, ..Default::default() });
+}
+
+#[test]
+fn test_pipeline_runs_all_stages_by_default() {
+    let input = r#"
+    function App({ condition }) {
+      return <Condition if={condition}>
+        <div>Return context</div>
+      </Condition>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ condition }) {
+      return condition ? <><div>Return context</div></> : null
+    }
+    "#;
+
+    test_transform_program(input, expected, Config::default());
+}
+
+#[test]
+fn test_pipeline_transform_stage_disabled() {
+    let input = r#"
+    function App({ condition }) {
+      return <Condition if={condition}>
+        <div>Untouched</div>
+      </Condition>
+    }
+    "#;
+
+    let config = Config {
+        stages: StagesConfig { transform: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    test_transform_program(input, input, config);
+}
+
+#[test]
+fn test_switch_default_alias() {
+    let input = r#"
+    function App({ a }) {
+      return (
+        <div>
+          <Switch shortCircuit>
+            <Switch.Case if={a}>
+              <A />
+            </Switch.Case>
+            <Switch.Default>
+              <Fallback />
+            </Switch.Default>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(a) ? <A/> : <Fallback/>}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_custom_else_alias() {
+    let input = r#"
+    function App({ a }) {
+      return (
+        <div>
+          <Switch shortCircuit>
+            <Switch.Case if={a}>
+              <A />
+            </Switch.Case>
+            <Switch.Otherwise>
+              <Fallback />
+            </Switch.Otherwise>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(a) ? <A/> : <Fallback/>}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { else_aliases: vec!["Otherwise".into()], ..Default::default() });
+}
+
+#[test]
+fn test_runtime_condition_component_is_reported() {
+    let input = r#"
+    function Condition({ if: show, children }) {
+      return show ? children : null
+    }
+
+    function App({ ready }) {
+      return <div>
+        <Condition if={ready}>
+          <p>Ready</p>
+        </Condition>
+      </div>
+    }
+    "#;
+
+    let diagnostics = collect_diagnostics(input, Config::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].0, Level::Error);
+    assert!(diagnostics[0].1.contains("`<Condition>` is also defined in this file"));
+
+    // The markup is left for the local component to render.
+    test_transform(input, input);
+
+    // 可以降级为警告
+    let config = Config { diagnostics: HashMap::from([("runtime-component".to_string(), DiagnosticLevel::Warn)]), ..Config::default() };
+    let levels: Vec<_> = collect_diagnostics(input, config).into_iter().map(|(level, _)| level).collect();
+    assert_eq!(levels, [Level::Warning]);
+}
+
+#[test]
+fn test_runtime_switch_component_does_not_affect_condition() {
+    let input = r#"
+    export const Switch = (props) => props.children
+
+    function App({ ready }) {
+      return <Condition if={ready}>
+        <p>Ready</p>
+      </Condition>
+    }
+    "#;
+
+    let expected = r#"
+    export const Switch = (props)=>props.children
+
+    function App({ ready }) {
+      return ready ? <><p>Ready</p></> : null
+    }
+    "#;
+
+    assert!(collect_diagnostics(input, Config::default()).is_empty());
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_warns_on_var_loop_variables_in_parallel_cases() {
+    let input = r#"
+    function List({ items }) {
+      const rows = [];
+      for (var i = 0; i < items.length; i++) {
+        rows.push(() => <Switch>
+          <Switch.Case if={items[i].done}><Done/></Switch.Case>
+          <Switch.Case if={ready}><Ready/></Switch.Case>
+        </Switch>);
+      }
+      for (let j = 0; j < items.length; j++) {
+        rows.push(<Switch>
+          <Switch.Case if={items[j].done}><Done/></Switch.Case>
+        </Switch>);
+      }
+      for (var key in items) {
+        rows.push(<Switch shortCircuit>
+          <Switch.Case if={key === "a"}><A/></Switch.Case>
+        </Switch>);
+      }
+      return rows;
+    }
+    "#;
+
+    let diagnostics = collect_diagnostics(input, Config::default());
+    assert_eq!(diagnostics.len(), 1);
+    let (level, message) = &diagnostics[0];
+    assert_eq!(*level, Level::Warning);
+    assert!(message.starts_with("`i` is declared with `var` by an enclosing loop"));
+}
+
+#[test]
+fn test_hoist_case_conditions() {
+    let input = r#"
+    function App({ user }) {
+      return <div>
+        <Switch>
+          <Switch.Case if={check(user)}><Checked/></Switch.Case>
+          <Switch.Case if={user.admin}><Admin/></Switch.Case>
+          <Switch.Case if={load() > 1}><Loaded/></Switch.Case>
+          <Switch.Case else><Guest/></Switch.Case>
+        </Switch>
+        <Switch value={getRole()}>
+          <Switch.Case is={"a"}><A/></Switch.Case>
+          <Switch.Case else><B/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return <div>
+        <React.Fragment>{((__case1, __case2)=><>{__case1 ? <><Checked/></> : null}{user.admin ? <><Admin/></> : null}{__case2 ? <><Loaded/></> : null}{!__case1 && !user.admin && !__case2 ? <><Guest/></> : null}</>)(check(user), load() > 1)}</React.Fragment>
+        <React.Fragment>{((__switchValue)=>((__case3)=><>{__case3 ? <><A/></> : null}{!__case3 ? <><B/></> : null}</>)(__switchValue === "a"))(getRole())}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { hoist_case_conditions: true, ..Default::default() });
+}
+
+#[test]
+fn test_hoist_case_conditions_with_await() {
+    // 条件作为实参在外层求值，await 留在原函数中；分支里有 await 时改为就地赋值
+    let input = r#"
+    async function App({ user }) {
+      return <div>
+        <Switch>
+          <Switch.Case if={await check(user)}><Checked/></Switch.Case>
+          <Switch.Case else><Guest/></Switch.Case>
+        </Switch>
+        <Switch>
+          <Switch.Case if={load() > 1}><Loaded data={await fetchData()}/></Switch.Case>
+          <Switch.Case else><Guest/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    async function App({ user }) {
+      let __case2;
+      return <div>
+        <React.Fragment>{((__case1)=><>{__case1 ? <><Checked/></> : null}{!__case1 ? <><Guest/></> : null}</>)(await check(user))}</React.Fragment>
+        <React.Fragment>{(__case2 = load() > 1, <>{__case2 ? <><Loaded data={await fetchData()}/></> : null}{!__case2 ? <><Guest/></> : null}</>)}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { hoist_case_conditions: true, ..Default::default() });
+}
+
+#[test]
+fn test_case_else_show() {
+    let input = r#"
+    function App({ user, items }) {
+      const list = <Switch>
+        <Switch.Case if={items.length} elseShow={<Empty/>}><List items={items}/></Switch.Case>
+      </Switch>;
+      return <div>
+        <Switch>
+          <Switch.Case if={user} elseShow={"Signed out"}><Profile/></Switch.Case>
+        </Switch>
+        <Switch>
+          <Switch.Case if={user} elseShow={<Ignored/>}><Profile/></Switch.Case>
+          <Switch.Case else><Login/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user, items }) {
+      const list = items.length ? <List items={items}/> : <Empty/>;
+      return <div>
+        <React.Fragment>{Boolean(user) ? <Profile/> : <>{"Signed out"}</>}</React.Fragment>
+        <React.Fragment>{user ? <><Profile/></> : null}{!user ? <><Login/></> : null}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_else_case_priority() {
+    let input = r#"
+    function App({ status }) {
+      return <Switch shortCircuit>
+        <Switch.Case if={status === "ok"}><Ok/></Switch.Case>
+        <Switch.Case else priority={2}><Generic/></Switch.Case>
+        <Switch.Default><Plain/></Switch.Default>
+        <Switch.Case else priority="1"><Specific/></Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ status }) {
+      return status === "ok" ? <Ok/> : <Specific/>
+    }
+    "#;
+
+    test_transform(input, expected);
+
+    let diagnostics = collect_diagnostics(input, Config::default());
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics.iter().all(|(level, message)| *level == Level::Warning && message.starts_with("else case ignored")));
+}
+
+#[cfg(feature = "compat")]
+#[test]
+fn test_compat_jsx_control_statements_if() {
+    let input = r#"
+    function App({ ready }) {
+      return (
+        <div>
+          <If condition={ready}>
+            <p>Ready</p>
+          </If>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ ready }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(ready) ? <><p>Ready</p></> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { compat: Some(Compat::JsxControlStatements), ..Default::default() });
+}
+
+#[cfg(feature = "compat")]
+#[test]
+fn test_compat_jsx_control_statements_choose() {
+    let input = r#"
+    function App({ a, b }) {
+      return <Choose>
+        <When condition={a}>
+          <A />
+        </When>
+        <When condition={b}>
+          <B />
+        </When>
+        <Otherwise>
+          <Fallback />
+        </Otherwise>
+      </Choose>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a, b }) {
+      return a ? <A/> : b ? <B/> : <Fallback/>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { compat: Some(Compat::JsxControlStatements), ..Default::default() });
+}
+
+#[test]
+fn test_compat_tags_ignored_by_default() {
+    let input = r#"
+    function App({ ready }) {
+      return <If condition={ready}>
+        <p>Ready</p>
+      </If>
+    }
+    "#;
+
+    test_transform(input, input);
+}
+
+#[test]
+fn test_hoist_shared_branches() {
+    let input = r#"
+    function App({ a }) {
+      return <Switch shortCircuit>
+        <Switch.Case if={a}>
+          <A />
+          <footer className="links"><p>Shared</p><p>Footer</p></footer>
+        </Switch.Case>
+        <Switch.Case else>
+          <B />
+          <footer className="links"><p>Shared</p><p>Footer</p></footer>
+        </Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a }) {
+      const __branchShared1 = <footer className="links"><p>Shared</p><p>Footer</p></footer>;
+      return a ? <><A/>{__branchShared1}</> : <><B/>{__branchShared1}</>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { hoist_shared_branches: true, ..Default::default() });
+
+    // lazy 的 Switch 不提前构造分支
+    let lazy_input = input.replace("<Switch shortCircuit>", "<Switch shortCircuit lazy>");
+    let expected = r#"
+    function App({ a }) {
+      return a ? <><A/><footer className="links"><p>Shared</p><p>Footer</p></footer></> : <><B/><footer className="links"><p>Shared</p><p>Footer</p></footer></>
+    }
+    "#;
+
+    test_transform_with_config(&lazy_input, expected, Config { hoist_shared_branches: true, ..Default::default() });
+    test_transform_with_config(input, expected, Config { hoist_shared_branches: true, lazy_branches: true, ..Default::default() });
+}
+
+#[test]
+fn test_hoist_shared_branches_in_concise_arrow() {
+    let input = r#"
+    const render = (a) => <div>
+      <Switch shortCircuit>
+        <Switch.Case if={a}>
+          <A />
+          <footer><p>Shared</p><p>Footer</p></footer>
+        </Switch.Case>
+        <Switch.Case else>
+          <footer><p>Shared</p><p>Footer</p></footer>
+        </Switch.Case>
+      </Switch>
+    </div>
+    "#;
+
+    let expected = r#"
+    const render = (a)=>{
+      const __branchShared1 = <footer><p>Shared</p><p>Footer</p></footer>;
+      return <div>
+      <React.Fragment>{Boolean(a) ? <><A/>{__branchShared1}</> : <>{__branchShared1}</>}</React.Fragment>
+    </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { hoist_shared_branches: true, ..Default::default() });
+}
+
+#[test]
+fn test_hoist_shared_branches_skips_dynamic_subtrees() {
+    let input = r#"
+    function App({ a, user }) {
+      return <Switch shortCircuit>
+        <Switch.Case if={a}>
+          <footer><p>{user.name}</p><p>Footer</p></footer>
+        </Switch.Case>
+        <Switch.Case else>
+          <footer><p>{user.name}</p><p>Footer</p></footer>
+        </Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a, user }) {
+      return a ? <footer><p>{user.name}</p><p>Footer</p></footer> : <footer><p>{user.name}</p><p>Footer</p></footer>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { hoist_shared_branches: true, ..Default::default() });
+}
+
+#[test]
+fn test_solid_show_with_fallback() {
+    let input = r#"
+    function App({ user }) {
+      return (
+        <div>
+          <Show when={user} fallback={<Login />}>
+            <Profile />
+          </Show>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(user) ? <><Profile/></> : <Login/>}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { solid_show: true, ..Default::default() });
+}
+
+#[test]
+fn test_solid_show_without_fallback_return_context() {
+    let input = r#"
+    function App({ user }) {
+      return <Show when={user}>
+        <Profile />
+      </Show>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return user ? <><Profile/></> : null
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { solid_show: true, ..Default::default() });
+}
+
+#[test]
+fn test_solid_show_disabled_by_default() {
+    let input = r#"
+    function App({ user }) {
+      return <Show when={user} fallback={<Login/>}>
+        <Profile/>
+      </Show>
+    }
+    "#;
+
+    test_transform(input, input);
+}
+
+#[test]
+fn test_for_injects_index_key() {
+    let input = r#"
+    function List({ items }) {
+      return (
+        <ul>
+          <For each={items}>
+            {item => <Row item={item} />}
+          </For>
+        </ul>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function List({ items }) {
+      return (
+        <ul>
+          <React.Fragment>{items.map((item, __index)=><Row item={item} key={__index}/>)}</React.Fragment>
+        </ul>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_for_keeps_explicit_key_and_index() {
+    let input = r#"
+    function List({ items }) {
+      return <For each={items.filter(Boolean)}>
+        {(item, i) => <Row key={item.id} index={i} />}
+      </For>
+    }
+    "#;
+
+    let expected = r#"
+    function List({ items }) {
+      return items.filter(Boolean).map((item, i)=><Row key={item.id} index={i}/>)
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_for_uses_existing_index_param_for_key() {
+    let input = r#"
+    const list = <For each={rows}>
+      {(row, i) => <Row row={row} />}
+    </For>
+    "#;
+
+    let expected = r#"
+    const list = rows.map((row, i)=><Row row={row} key={i}/>)
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_for_without_callback_child_is_reported() {
+    let input = r#"
+    function List({ items }) {
+      return <For each={items}>
+        <Row/>
+      </For>
+    }
+    "#;
+
+    let diagnostics = collect_diagnostics(input, Config::default());
+    assert_eq!(diagnostics, [(Level::Warning, "`<For>` expects `{(item, index) => …}` as its only child; the tag is left as a runtime component".to_string())]);
+    test_transform(input, input);
+}
+
+#[test]
+fn test_use_hook_condition_jsx_context() {
+    let input = r#"
+    function App({ flagPromise }) {
+      return (
+        <div>
+          <Condition if={use(flagPromise)}>
+            <NewUI />
+          </Condition>
+          <Condition if={React.use(ctx).enabled}>
+            <Banner />
+          </Condition>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ flagPromise }) {
+      return (
+        <div>
+          <React.Fragment>{use(flagPromise) ? <><NewUI/></> : null}</React.Fragment>
+          <React.Fragment>{Boolean(React.use(ctx).enabled) ? <><Banner/></> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_use_hook_condition_return_context() {
+    let input = r#"
+    function App({ flagPromise }) {
+      return <Condition if={use(flagPromise)}>
+        <NewUI />
+      </Condition>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ flagPromise }) {
+      return use(flagPromise) ? <><NewUI/></> : null
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_use_hook_switch_case_short_circuit() {
+    let input = r#"
+    function App({ a, flagPromise }) {
+      return (
+        <div>
+          <Switch shortCircuit>
+            <Switch.Case if={a}>
+              <A />
+            </Switch.Case>
+            <Switch.Case if={React.use(flagPromise)}>
+              <B />
+            </Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a, flagPromise }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(a) ? <A/> : React.use(flagPromise) ? <B/> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_line_width_breaks_long_branches() {
+    let input = r#"
+    function App({ isLoading, hasError }) {
+      return <Switch shortCircuit>
+        <Switch.Case if={isLoading}>
+          <Spinner size="large" label="Loading your dashboard" />
+          <Hint />
+        </Switch.Case>
+        <Switch.Case else>
+          <Dashboard />
+        </Switch.Case>
+      </Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ isLoading, hasError }) {
+      return isLoading ? (<>
+        <Spinner size="large" label="Loading your dashboard"/>
+        <Hint/>
+      </>) : <Dashboard/>
+    }
+    "#;
+
+    let config = || Config { line_width: Some(40), ..Default::default() };
+    test_transform_with_config(input, expected, config());
+
+    let output = transform_output(input, config());
+    assert!(output.contains("(<>\n"), "branch should start on its own line:\n{output}");
+    assert!(output.contains("\n    <Hint/>"), "children should be indented:\n{output}");
+}
+
+#[test]
+fn test_line_width_keeps_short_ternaries_inline() {
+    let input = r#"
+    function App({ condition }) {
+      return <Condition if={condition}>
+        <div>Short</div>
+      </Condition>
+    }
+    "#;
+
+    let output = transform_output(input, Config { line_width: Some(80), ..Default::default() });
+    assert!(!output.contains("(<>"), "short ternary should stay inline:\n{output}");
+}
+
+#[test]
+fn test_unless_negates_condition() {
+    let input = r#"
+    function App({ user, isAdmin }) {
+      return (
+        <div>
+          <Unless if={user}>
+            <Login />
+          </Unless>
+          <Unless if={user && isAdmin}>
+            <Upgrade />
+          </Unless>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user, isAdmin }) {
+      return (
+        <div>
+          <React.Fragment>{!user ? <><Login/></> : null}</React.Fragment>
+          <React.Fragment>{!(user && isAdmin) ? <><Upgrade/></> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_unless_custom_tag_return_context() {
+    let input = r#"
+    function App({ ready }) {
+      return <IfNot if={ready}>
+        <Spinner />
+      </IfNot>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ ready }) {
+      return !ready ? <><Spinner/></> : null
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { unless_tag: "IfNot".into(), ..Default::default() });
+}
+
+#[test]
+fn test_with_binds_attributes_once() {
+    let input = r#"
+    function App({ t }) {
+      return (
+        <div>
+          <With user={getUser()} theme={t}>
+            <Profile user={user} theme={theme} />
+          </With>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ t }) {
+      return (
+        <div>
+          <React.Fragment>{((user, theme)=><>
+            <Profile user={user} theme={theme}/>
+          </>)(getUser(), t)}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_with_return_context_and_invalid_binding() {
+    let input = r#"
+    function App() {
+      const badge = <With data-id="x"><Badge /></With>
+      return <With label="Hi"><Badge text={label} /></With>
+    }
+    "#;
+
+    let expected = r#"
+    function App() {
+      const badge = <With data-id="x"><Badge/></With>
+      return ((label)=><><Badge text={label}/></>)("Hi")
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+/// Runs the pipeline with `annotateBranches` and emits the output with comments.
+fn annotated_output(input: &str, config: Config) -> String {
+    annotated_output_with(input, config, |_| {})
+}
+
+/// [`annotated_output`] with `edit` applied to the parsed module, e.g. to
+/// give it the spans of generated code.
+fn annotated_output_with(input: &str, config: Config, edit: impl FnOnce(&mut Module)) -> String {
+    let cm = Arc::new(SourceMap::default());
+    let fm = cm.new_source_file(FileName::Real("App.tsx".into()).into(), input.to_string());
+    let comments = SingleThreadedComments::default();
+
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsSyntax { tsx: true, ..Default::default() }),
+        Default::default(),
+        StringInput::from(&*fm),
+        Some(&comments),
+    );
+    let mut module = Parser::new_from(lexer).parse_module().expect("Failed to parse input");
+    edit(&mut module);
+
+    let host = TransformHost {
+        comments: Some(Box::new(comments.clone())),
+        source_map: Some(cm.clone()),
+        ..Default::default()
+    };
+    let program = transform_program_with_host(Program::Module(module), config, host);
+
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter.emit_program(&program).expect("Failed to emit");
+    }
+    String::from_utf8(buf).expect("Invalid UTF-8")
+}
+
+#[cfg(feature = "instrumentation")]
+#[test]
+fn test_annotate_branches_short_circuit_switch() {
+    let input = r#"function App({ status }) {
+  return <Switch shortCircuit>
+    <Switch.Case if={status === "loading"}>
+      <Spinner />
+    </Switch.Case>
+    <Switch.Case else>
+      <Content />
+      <Footer />
+    </Switch.Case>
+  </Switch>
+}"#;
+
+    let output = annotated_output(input, Config { annotate_branches: true, ..Default::default() });
+    assert!(
+        output.contains(r#"status === "loading" ? /* condition-switch:branch(App.tsx:4) */ (<Spinner/>)"#),
+        "case branch should be annotated:\n{output}"
+    );
+    assert!(
+        output.contains(": /* condition-switch:branch(App.tsx:7) */ (<>"),
+        "else branch should be annotated:\n{output}"
+    );
+}
+
+#[cfg(feature = "instrumentation")]
+#[test]
+fn test_annotate_branches_condition_and_off_by_default() {
+    let input = r#"function App({ show }) {
+  return <div>
+    <Condition if={show}>
+      <p>Hi</p>
+    </Condition>
+  </div>
+}"#;
+
+    let output = annotated_output(input, Config { annotate_branches: true, ..Default::default() });
+    assert!(
+        output.contains("Boolean(show) ? /* condition-switch:branch(App.tsx:3) */ (<>"),
+        "condition branch should be annotated:\n{output}"
+    );
+    assert!(output.contains(": null"), "missing else branch stays bare:\n{output}");
+
+    let output = annotated_output(input, Config::default());
+    assert!(!output.contains("condition-switch:branch"), "annotations are opt-in:\n{output}");
+}
+
+#[test]
+fn test_strip_imports_removes_lowered_components() {
+    let input = r#"
+    import { Condition, Switch } from "@/components/control";
+    import { Card } from "@/components/card";
+    function App({ show }) {
+      return <Condition if={show}>
+        <Card />
+      </Condition>
+    }
+    "#;
+
+    let expected = r#"
+    import { Card } from "@/components/card";
+    function App({ show }) {
+      return show ? <><Card/></> : null
+    }
+    "#;
+
+    test_transform_program(input, expected, Config {
+        strip_imports: vec!["@/components/control".into()],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_strip_imports_keeps_referenced_and_unlisted_imports() {
+    let input = r#"
+    import { Condition, Switch, helper } from "@/components/control";
+    import { For } from "other-lib";
+    export { Switch };
+    function App({ show }) {
+      return <Condition if={show}>
+        <Card />
+      </Condition>
+    }
+    "#;
+
+    let expected = r#"
+    import { Switch, helper } from "@/components/control";
+    import { For } from "other-lib";
+    export { Switch };
+    function App({ show }) {
+      return show ? <><Card/></> : null
+    }
+    "#;
+
+    test_transform_program(input, expected, Config {
+        strip_imports: vec!["@/components/control".into()],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_import_sources_only_lowers_imported_tags() {
+    let input = r#"
+    import { Condition } from "@ourco/flow";
+    function App({ show }) {
+      return <div>
+        <Condition if={show}><p>Flow</p></Condition>
+        <Switch><Switch.Case if={show}><p>Local</p></Switch.Case></Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    import { Condition } from "@ourco/flow";
+    function App({ show }) {
+      return <div>
+        <React.Fragment>{Boolean(show) ? <><p>Flow</p></> : null}</React.Fragment>
+        <Switch><Switch.Case if={show}><p>Local</p></Switch.Case></Switch>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config {
+        import_sources: vec!["@ourco/flow".into()],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_import_sources_ignores_other_packages_and_aliases() {
+    let input = r#"
+    import { Condition } from "other-lib";
+    import { Switch as Choice } from "@ourco/flow";
+    function App({ show }) {
+      return <Condition if={show}><p>Other</p></Condition>
+    }
+    "#;
+
+    let expected = r#"
+    import { Condition } from "other-lib";
+    import { Switch as Choice } from "@ourco/flow";
+    function App({ show }) {
+      return <Condition if={show}><p>Other</p></Condition>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config {
+        import_sources: vec!["@ourco/flow".into()],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_vocabulary_file_renames_tags_and_attributes() {
+    let dir = std::env::temp_dir().join(format!("swc-condition-vocabulary-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("jsx-control.json");
+    std::fs::write(&file, r#"{
+      "tags": { "condition": "Guard", "switch": "Match" },
+      "attributes": { "if": "test", "shortCircuit": "first" },
+      "elseAliases": ["Fallback"]
+    }"#).unwrap();
+
+    let json = format!(r#"{{ "vocabularyFile": {:?}, "attributes": {{ "if": "when" }} }}"#, file.to_str().unwrap());
+    let config = Config::from_json(&json).expect("config should load");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let input = r#"
+    function App({ ready, user }) {
+      return <div>
+        <Guard when={ready}><p>Ready</p></Guard>
+        <Match first>
+          <Match.Case when={user}><Profile/></Match.Case>
+          <Match.Fallback><Login/></Match.Fallback>
+        </Match>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ ready, user }) {
+      return <div>
+        <React.Fragment>{Boolean(ready) ? <><p>Ready</p></> : null}</React.Fragment>
+        <React.Fragment>{Boolean(user) ? <Profile/> : <Login/>}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, config);
+}
+
+#[test]
+fn test_vocabulary_file_errors() {
+    let missing = Config::from_json(r#"{ "vocabularyFile": "/nonexistent/jsx-control.json" }"#);
+    assert!(missing.unwrap_err().contains("cannot read vocabularyFile"));
+
+    let dir = std::env::temp_dir().join(format!("swc-condition-vocabulary-bad-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("jsx-control.json");
+    std::fs::write(&file, r#"{ "mergeGuards": true }"#).unwrap();
+    let json = format!(r#"{{ "vocabularyFile": {:?} }}"#, file.to_str().unwrap());
+    let unknown_key = Config::from_json(&json);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let err = unknown_key.unwrap_err();
+    assert!(err.contains("invalid vocabularyFile") && err.contains("unknown field `mergeGuards`"), "{err}");
+}
+
+#[test]
+fn test_local_binding_shadows_condition() {
+    let input = r#"
+    function App({ show }) {
+      const Condition = MyComponent;
+      return <Condition if={show}><p>Mine</p></Condition>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show }) {
+      const Condition = MyComponent;
+      return <Condition if={show}><p>Mine</p></Condition>
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_shadowing_is_scoped_after_resolver() {
+    use swc_core::common::{Globals, Mark, GLOBALS};
+    use swc_core::ecma::transforms::base::resolver;
+
+    let input = r#"
+    function Local({ show, Switch }) {
+      return <Switch><Switch.Case if={show}><p>Param</p></Switch.Case></Switch>
+    }
+    function App({ show }) {
+      return <Switch><Switch.Case if={show}><p>Global</p></Switch.Case></Switch>
+    }
+    "#;
+
+    let expected = r#"
+    function Local({ show, Switch }) {
+      return <Switch><Switch.Case if={show}><p>Param</p></Switch.Case></Switch>
+    }
+    function App({ show }) {
+      return show ? <p>Global</p> : null
+    }
+    "#;
+
+    GLOBALS.set(&Globals::new(), || {
+        test_transform_with(input, expected, |module| {
+            let program = Program::Module(module).apply(resolver(Mark::new(), Mark::new(), true));
+            transform_program(program, Config::default()).expect_module()
+        });
+    });
+}
+
+#[test]
+fn test_injected_boolean_is_unresolved_global() {
+    use swc_core::common::{Globals, Mark, GLOBALS};
+    use swc_core::ecma::transforms::base::{hygiene::hygiene, resolver};
+
+    let input = r#"
+    function App({ show }) {
+      const Boolean = (value) => value === "yes";
+      const badge = <Condition if={Boolean(show)}><Badge/></Condition>;
+      return badge;
+    }
+    "#;
+
+    // 注入的 `Boolean` 指向全局，hygiene 会改名遮蔽它的局部变量
+    let expected = r#"
+    function App({ show }) {
+      const Boolean1 = (value)=>value === "yes";
+      const badge = Boolean(Boolean1(show)) ? <><Badge/></> : null;
+      return badge;
+    }
+    "#;
+
+    GLOBALS.set(&Globals::new(), || {
+        test_transform_with(input, expected, |module| {
+            let unresolved_mark = Mark::new();
+            let program = Program::Module(module).apply(resolver(unresolved_mark, Mark::new(), true));
+            let host = TransformHost { unresolved_mark: Some(unresolved_mark), ..Default::default() };
+            transform_program_with_host(program, Config::default(), host).apply(hygiene()).expect_module()
+        });
+    });
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_recommended_chain_runs_as_one_pass() {
+    use swc_core::common::{Globals, Mark, GLOBALS};
+    use swc_core::ecma::transforms::base::hygiene::hygiene;
+
+    let input = r#"
+    function App({ show }) {
+      const Boolean = (value) => value === "yes";
+      const badge = <Condition if={Boolean(show)}><Badge/></Condition>;
+      return badge;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show }) {
+      const Boolean1 = (value)=>value === "yes";
+      const badge = Boolean(Boolean1(show)) ? <><Badge/></> : null;
+      return badge;
+    }
+    "#;
+
+    GLOBALS.set(&Globals::new(), || {
+        test_transform_with(input, expected, |module| {
+            let chain = recommended_chain(Config::default(), TransformHost::default(), Mark::new(), None);
+            Program::Module(module).apply(chain).expect_module()
+        });
+        // 单独的 pass 可以和其它 pass 组成元组
+        let untouched = input.replace("(value) => ", "(value)=>");
+        test_transform_with(input, &untouched, |module| {
+            let config = Config { stages: StagesConfig { transform: false, ..Default::default() }, ..Default::default() };
+            Program::Module(module).apply((condition_pass(config, TransformHost::default()), hygiene())).expect_module()
+        });
+    });
+}
+
+#[test]
+fn test_tag_namespaces_member_expression_tags() {
+    let input = r#"
+    function App({ show, status }) {
+      return <div>
+        <Flow.Condition if={show}><p>Shown</p></Flow.Condition>
+        <UI.Switch shortCircuit>
+          <UI.Switch.Case if={status === "ok"}><Ok/></UI.Switch.Case>
+          <Switch.Case else><Fail/></Switch.Case>
+        </UI.Switch>
+        <Other.Condition if={show}><p>Kept</p></Other.Condition>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show, status }) {
+      return <div>
+        <React.Fragment>{Boolean(show) ? <><p>Shown</p></> : null}</React.Fragment>
+        <React.Fragment>{status === "ok" ? <Ok/> : <Fail/>}</React.Fragment>
+        <Other.Condition if={show}><p>Kept</p></Other.Condition>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config {
+        tag_namespaces: vec!["Flow".into(), "UI".into()],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_tag_namespaces_with_import_sources() {
+    let input = r#"
+    import * as Flow from "@ourco/flow";
+    function App({ show }) {
+      return <Flow.Condition if={show}><p>Shown</p></Flow.Condition>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show }) {
+      return show ? <><p>Shown</p></> : null
+    }
+    "#;
+
+    test_transform_program(input, expected, Config {
+        tag_namespaces: vec!["Flow".into()],
+        import_sources: vec!["@ourco/flow".into()],
+        strip_imports: vec!["@ourco/flow".into()],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_class_static_property_assignment_context() {
+    let input = r#"
+    class Page extends React.Component {
+      static placeholder = <Condition if={FLAG}>
+        <Skeleton />
+      </Condition>;
+      #fallback = <Switch shortCircuit>
+        <Switch.Case if={isMobile}><Compact/></Switch.Case>
+        <Switch.Case else><Full/></Switch.Case>
+      </Switch>;
+    }
+    "#;
+
+    let expected = r#"
+    class Page extends React.Component {
+      static placeholder = Boolean(FLAG) ? <><Skeleton/></> : null;
+      #fallback = isMobile ? <Compact/> : <Full/>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_hook_callback_conditional_context() {
+    let input = r#"
+    function App({ flag, isMobile, ready }) {
+      const content = useMemo(() => flag ? <Switch shortCircuit>
+        <Switch.Case if={isMobile}><Compact/></Switch.Case>
+        <Switch.Case else><Full/></Switch.Case>
+      </Switch> : null, [flag, isMobile]);
+      const renderBadge = useCallback(() => ready && <Condition if={flag}><Badge/></Condition>, [ ready, flag ]);
+      return <div>{useMemo(() => (flag ? <Condition if={ready}><Spinner/></Condition> : null), [flag, ready])}</div>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ flag, isMobile, ready }) {
+      const content = useMemo(()=>flag ? isMobile ? <Compact/> : <Full/> : null, [ flag, isMobile ]);
+      const renderBadge = useCallback(()=>ready && (flag ? <><Badge/></> : null), [ ready, flag ]);
+      return <div>{useMemo(()=>flag ? ready ? <><Spinner/></> : null : null, [ flag, ready ])}</div>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_portal_and_clone_element_arguments() {
+    let input = r#"
+    function Overlay({ open, node, child, compact }) {
+      const cloned = cloneElement(child, {}, <Switch shortCircuit>
+        <Switch.Case if={compact}><Small/></Switch.Case>
+        <Switch.Case else><Large/></Switch.Case>
+      </Switch>);
+      return <div>
+        {cloned}
+        {createPortal(<Condition if={open}><Modal/></Condition>, node)}
+      </div>;
+    }
+    ReactDOM.createPortal(<Condition if={open}><Toast/></Condition>, document.body);
+    "#;
+
+    let expected = r#"
+    function Overlay({ open, node, child, compact }) {
+      const cloned = cloneElement(child, {}, compact ? <Small/> : <Large/>);
+      return <div>
+        {cloned}
+        {createPortal(Boolean(open) ? <><Modal/></> : null, node)}
+      </div>;
+    }
+    ReactDOM.createPortal(Boolean(open) ? <><Toast/></> : null, document.body);
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_fragment_config_named_wrapper() {
+    let input = r#"
+    function App({ show, status }) {
+      return <div>
+        <Condition if={show}><p>Shown</p></Condition>
+        <Switch>
+          <Switch.Case if={status}><Ok/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show, status }) {
+      return <div>
+        <Fragment>{Boolean(show) ? <><p>Shown</p></> : null}</Fragment>
+        <Fragment>{status ? <><Ok/></> : null}</Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { fragment: "Fragment".into(), ..Default::default() });
+
+    let expected = expected.replace("Fragment>", "Preact.Fragment>");
+    test_transform_with_config(input, &expected, Config { fragment: "Preact.Fragment".into(), ..Default::default() });
+}
+
+#[test]
+fn test_fragment_config_auto_shorthand() {
+    let input = r#"
+    function App({ show }) {
+      return <div>
+        <Condition if={show}><p>Shown</p></Condition>
+        {ready && <Condition if={show}><p>Nested</p></Condition>}
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show }) {
+      return <div>
+        <>{Boolean(show) ? <><p>Shown</p></> : null}</>
+        {ready && (Boolean(show) ? <><p>Nested</p></> : null)}
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { fragment: "auto".into(), ..Default::default() });
+}
+
+#[test]
+fn test_fragment_auto_detects_react_imports() {
+    let cases = [
+        (r#"import { Fragment as F, useState } from "react";"#, "F"),
+        (r#"import React from "react";"#, "React.Fragment"),
+        (r#"import * as R from "react";"#, "R.Fragment"),
+        (r#"import { useState } from "react";"#, ""),
+    ];
+
+    for (import, wrapper) in cases {
+        let input = format!(r#"
+        {import}
+        function App({{ show }}) {{
+          return <div><Condition if={{show}}><p>Shown</p></Condition></div>
+        }}
+        "#);
+
+        let expected = format!(r#"
+        {import}
+        function App({{ show }}) {{
+          return <div><{wrapper}>{{Boolean(show) ? <><p>Shown</p></> : null}}</{wrapper}></div>
+        }}
+        "#);
+
+        test_transform_with_config(&input, &expected, Config { fragment: "auto".into(), ..Default::default() });
+    }
+}
+
+#[test]
+fn test_preact_target_imports_fragment() {
+    let input = r#"
+    "use client";
+    import { h } from "preact";
+    function App({ show }) {
+      return <div><Condition if={show}><p>Shown</p></Condition></div>
+    }
+    "#;
+
+    let expected = r#"
+    "use client";
+    import { Fragment } from "preact";
+    import { h } from "preact";
+    function App({ show }) {
+      return <div><Fragment>{Boolean(show) ? <><p>Shown</p></> : null}</Fragment></div>
+    }
+    "#;
+
+    let config = Config { target: Target::Preact, import_fragment: true, ..Default::default() };
+    test_transform_with_config(input, expected, config.clone());
+
+    // Without `importFragment` the wrapper is emitted as-is.
+    test_transform_with_config(input, &expected.replace("import { Fragment } from \"preact\";", ""), Config {
+        import_fragment: false,
+        ..config.clone()
+    });
+
+    // Files without lowered JSX keep their imports untouched.
+    let untouched = r#"
+    import { h } from "preact";
+    const App = ()=><div/>
+    "#;
+    test_transform_with_config(untouched, untouched, config);
+}
+
+#[test]
+fn test_preact_target_auto_fragment_reads_preact_imports() {
+    let input = r#"
+    import { Fragment as F } from "preact";
+    import { Fragment } from "react";
+    function App({ show }) {
+      return <div><Condition if={show}><p>Shown</p></Condition></div>
+    }
+    "#;
+
+    let expected = r#"
+    import { Fragment as F } from "preact";
+    import { Fragment } from "react";
+    function App({ show }) {
+      return <div><F>{Boolean(show) ? <><p>Shown</p></> : null}</F></div>
+    }
+    "#;
+
+    let config = Config { target: Target::Preact, fragment: "auto".into(), import_fragment: true, ..Default::default() };
+    test_transform_with_config(input, expected, config);
+}
+
+#[test]
+fn test_react_automatic_target_uses_shorthand_fragment() {
+    let input = r#"
+    function App({ show }) {
+      return <div><Condition if={show}><p>Shown</p></Condition></div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show }) {
+      return <div><>{Boolean(show) ? <><p>Shown</p></> : null}</></div>
+    }
+    "#;
+
+    test_transform_program(input, expected, Config { target: Target::ReactAutomatic, ..Default::default() });
+}
+
+#[test]
+fn test_transform_factory_with_custom_codegen() {
+    struct Inferno;
+
+    impl Codegen for Inferno {
+        fn fragment_module(&self) -> &str {
+            "inferno"
+        }
+
+        fn default_fragment(&self) -> &str {
+            "Inferno.Fragment"
+        }
+
+        fn make_coercion_call(&self, _callee: Ident, condition: Box<Expr>, span: Span, _ctxt: SyntaxContext) -> Expr {
+            let not = |arg| Box::new(Expr::Unary(UnaryExpr { span, op: UnaryOp::Bang, arg }));
+            *not(not(condition))
+        }
+
+        // 没有 fallback 的分支写成 `&&`
+        fn make_conditional_child(&self, test: Box<Expr>, rendered: Box<Expr>, fallback: Box<Expr>, span: Span) -> Expr {
+            match *fallback {
+                Expr::Lit(Lit::Null(_)) => Expr::Bin(BinExpr { span, op: BinaryOp::LogicalAnd, left: test, right: rendered }),
+                fallback => Expr::Cond(CondExpr { span, test, cons: rendered, alt: Box::new(fallback) }),
+            }
+        }
+    }
+
+    let factory = TransformFactory::new(Config::default()).with_codegen(Arc::new(Inferno));
+    let mut module = parse_module("\nconst el = <div><Condition if={flag}>x</Condition></div>");
+    module.visit_mut_with(&mut factory.visitor());
+    let output = emit_module(&module);
+    assert!(output.contains("<Inferno.Fragment>{!!flag && <>x</>}</Inferno.Fragment>"), "{output}");
+
+    // Switch 的各种形态也经过 Codegen
+    let mut module = parse_module(r#"
+    const el = <div>
+      <Switch>
+        <Switch.Case if={a}><A/></Switch.Case>
+        <Switch.Case if={b}><B/></Switch.Case>
+      </Switch>
+      <Switch shortCircuit>
+        <Switch.Case if={a}><A/></Switch.Case>
+        <Switch.Default><B/></Switch.Default>
+      </Switch>
+    </div>
+    "#);
+    module.visit_mut_with(&mut factory.visitor());
+    let output = emit_module(&module);
+    assert!(output.contains("<Inferno.Fragment>{a && <><A/></>}{b && <><B/></>}</Inferno.Fragment>"), "{output}");
+    assert!(output.contains("<Inferno.Fragment>{!!a ? <A/> : <B/>}</Inferno.Fragment>"), "{output}");
+}
+
+#[test]
+fn test_expression_containers_drop_fragment_holder() {
+    let input = r#"
+    function App({ show, status, items }) {
+      return <div>
+        <Condition if={show}><p>Shown</p></Condition>
+        <Switch shortCircuit>
+          <Switch.Case if={status === "ok"}><Ok/></Switch.Case>
+          <Switch.Default><Pending/></Switch.Default>
+        </Switch>
+        <For each={items}>{item => <Row item={item}/>}</For>
+        <React.Fragment>{items.length}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ show, status, items }) {
+      return <div>
+        {Boolean(show) ? <><p>Shown</p></> : null}
+        {status === "ok" ? <Ok/> : <Pending/>}
+        {items.map((item, __index)=><Row item={item} key={__index}/>)}
+        <React.Fragment>{items.length}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { expression_containers: true, ..Default::default() });
+}
+
+#[test]
+fn test_expression_containers_keep_multi_child_holder() {
+    // A parallel Switch yields one container per case, which still need a holder.
+    let input = r#"
+    function App({ a, b }) {
+      return <div>
+        <Switch>
+          <Switch.Case if={a}><A/></Switch.Case>
+          <Switch.Case if={b}><B/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a, b }) {
+      return <div>
+        <React.Fragment>{a ? <><A/></> : null}{b ? <><B/></> : null}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { expression_containers: true, ..Default::default() });
+}
+
+#[test]
+fn test_jsx_pragmas_pick_fragment_per_file() {
+    let body = r#"
+function App({ show }) {
+  return <div><Condition if={show}><p>Shown</p></Condition></div>
+}"#;
+
+    // `fragment: "auto"` would pick the shorthand; a pragma overrides it.
+    let cases = [
+        ("/** @jsx h */\n/** @jsxFrag Preact.Fragment */", "<Preact.Fragment>"),
+        ("/** @jsx h */", "<Fragment>"),
+        ("/* @jsx Preact.h */", "<Preact.Fragment>"),
+        ("/* @jsxRuntime classic */", "<>"),
+    ];
+    for (pragma, opening) in cases {
+        let output = annotated_output(&format!("{pragma}{body}"), Config { fragment: "auto".into(), ..Default::default() });
+        assert!(output.contains(&format!("{opening}{{Boolean(show)")), "{pragma}: {output}");
+    }
+}
+
+#[test]
+fn test_condition_helper_replaces_boolean() {
+    let config = Config {
+        condition_helper: Some(ConditionHelper { name: "isTruthy".into(), from: "@acme/utils".into() }),
+        ..Default::default()
+    };
+
+    let input = r#"
+    "use client";
+    function App({ items, status }) {
+      return <div>
+        <Condition if={items}><List/></Condition>
+        <Switch shortCircuit><Switch.Case if={status}><Status/></Switch.Case></Switch>
+      </div>;
+    }
+    "#;
+    let expected = r#"
+    "use client";
+    import { isTruthy } from "@acme/utils";
+    function App({ items, status }) {
+      return <div>
+        <React.Fragment>{isTruthy(items) ? <><List/></> : null}</React.Fragment>
+        <React.Fragment>{isTruthy(status) ? <Status/> : null}</React.Fragment>
+      </div>;
+    }
+    "#;
+    test_transform_with_config(input, expected, config.clone());
+
+    // Return context tests the condition directly, so nothing is imported.
+    let input = r#"
+    const App = ({ items }) => <Condition if={items}><List/></Condition>;
+    "#;
+    let expected = r#"
+    const App = ({ items })=>items ? <><List/></> : null;
+    "#;
+    test_transform_with_config(input, expected, config);
+}
+
+#[test]
+fn test_boolean_conditions_skip_coercion() {
+    let input = r#"
+    function App({ value, user, items }) {
+      return <div>
+        <Condition if={typeof value === "string"}><Text/></Condition>
+        <Condition if={user instanceof Admin || (items.length > 0 && !user.banned)}><Panel/></Condition>
+        <Condition if={true}><Always/></Condition>
+        <Condition if={user && user.admin}><Admin/></Condition>
+      </div>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ value, user, items }) {
+      return <div>
+        <React.Fragment>{typeof value === "string" ? <><Text/></> : null}</React.Fragment>
+        <React.Fragment>{user instanceof Admin || (items.length > 0 && !user.banned) ? <><Panel/></> : null}</React.Fragment>
+        <Always/>
+        <React.Fragment>{Boolean(user && user.admin) ? <><Admin/></> : null}</React.Fragment>
+      </div>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_boolean_strategy() {
+    let input = r#"
+    function App({ items, user, status }) {
+      return <div>
+        <Condition if={items.length}><List/></Condition>
+        <Condition if={user && user.admin}><Admin/></Condition>
+        <Switch shortCircuit><Switch.Case if={status}><Status/></Switch.Case></Switch>
+      </div>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ items, user, status }) {
+      return <div>
+        <React.Fragment>{!!items.length ? <><List/></> : null}</React.Fragment>
+        <React.Fragment>{!!(user && user.admin) ? <><Admin/></> : null}</React.Fragment>
+        <React.Fragment>{!!status ? <Status/> : null}</React.Fragment>
+      </div>;
+    }
+    "#;
+    let config = Config { boolean_strategy: BooleanStrategy::BangBang, ..Default::default() };
+    test_transform_with_config(input, expected, config);
+
+    let expected = r#"
+    function App({ items, user, status }) {
+      return <div>
+        <React.Fragment>{items.length ? <><List/></> : null}</React.Fragment>
+        <React.Fragment>{user && user.admin ? <><Admin/></> : null}</React.Fragment>
+        <React.Fragment>{status ? <Status/> : null}</React.Fragment>
+      </div>;
+    }
+    "#;
+    let config = Config { boolean_strategy: BooleanStrategy::None, ..Default::default() };
+    test_transform_with_config(input, expected, config);
+}
+
+#[test]
+fn test_logical_and_output() {
+    let config = Config { output: OutputMode::LogicalAnd, solid_show: true, ..Default::default() };
+
+    let input = r#"
+    function App({ count, user, ready }) {
+      return <div>
+        <Condition if={count}><Badge/></Condition>
+        <Unless if={user}><Login/></Unless>
+        <Show when={ready} fallback={<Spinner/>}><Content/></Show>
+      </div>;
+    }
+    const Hint = ({ a, b }) => <Condition if={a || b}><p>hint</p></Condition>;
+    "#;
+
+    let expected = r#"
+    function App({ count, user, ready }) {
+      return <div>
+        <React.Fragment>{Boolean(count) && <><Badge/></>}</React.Fragment>
+        <React.Fragment>{!user && <><Login/></>}</React.Fragment>
+        <React.Fragment>{Boolean(ready) ? <><Content/></> : <Spinner/>}</React.Fragment>
+      </div>;
+    }
+    const Hint = ({ a, b })=>Boolean(a || b) && <><p>hint</p></>;
+    "#;
+
+    test_transform_with_config(input, expected, config);
+}
+
+#[test]
+fn test_fallback_config() {
+    let input = r#"
+    function App({ items, status }) {
+      const list = <Condition if={items.length > 0}><List/></Condition>;
+      return <div>
+        {list}
+        <Switch><Switch.Case if={status}><Status/></Switch.Case></Switch>
+      </div>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ items, status }) {
+      const list = items.length > 0 ? <><List/></> : undefined;
+      return <div>
+        {list}
+        <React.Fragment>{status ? <><Status/></> : undefined}</React.Fragment>
+      </div>;
+    }
+    "#;
+    let config = Config::from_json(r#"{ "fallback": "undefined" }"#).unwrap();
+    test_transform_with_config(input, expected, config);
+
+    let expected = r#"
+    function App({ items, status }) {
+      const list = items.length > 0 ? <><List/></> : <EmptyState size="sm"/>;
+      return <div>
+        {list}
+        <React.Fragment>{status ? <><Status/></> : <EmptyState size="sm"/>}</React.Fragment>
+      </div>;
+    }
+    "#;
+    let config = Config::from_json(r#"{ "fallback": "<EmptyState size=\"sm\"/>" }"#).unwrap();
+    test_transform_with_config(input, expected, config);
+
+    for invalid in [r#"{ "fallback": "<EmptyState" }"#, r#"{ "fallback": "null extra" }"#] {
+        assert!(Config::from_json(invalid).unwrap_err().contains("invalid fallback"));
+    }
+}
+
+#[test]
+fn test_literal_conditions_fold_at_build_time() {
+    let input = r#"
+    function App({ user }) {
+      const debug = <Condition if={false}><DebugPanel/></Condition>;
+      return <div>
+        <Condition if={true}><Header/><Nav/></Condition>
+        <Condition if={0}><Never/></Condition>
+        <Unless if={null}><Always/></Unless>
+        <Show when={false} fallback={<Empty/>}><Content/></Show>
+        <Switch shortCircuit>
+          <Switch.Case if={false}><Old/></Switch.Case>
+          <Switch.Case if={user}><Home/></Switch.Case>
+          <Switch.Case else><Login/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      const debug = null;
+      return <div>
+        <Header/><Nav/>
+        <Always/>
+        {<Empty/>}
+        <React.Fragment>{Boolean(user) ? <Home/> : <Login/>}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { solid_show: true, ..Default::default() });
+}
+
+#[test]
+fn test_bare_if_attribute_is_true() {
+    let input = r#"
+    function App({ user }) {
+      const header = <Condition if><Header/></Condition>;
+      return <div>
+        <Condition if><Nav/></Condition>
+        <Unless if><Never/></Unless>
+        <Switch shortCircuit>
+          <Switch.Case if={user}><Home/></Switch.Case>
+          <Switch.Case if><Login/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      const header = <><Header/></>;
+      return <div>
+        <Nav/>
+        <React.Fragment>{Boolean(user) ? <Home/> : <Login/>}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_inline_if_attribute() {
+    let input = r#"
+    function App({ user, items }) {
+      const badge = <span render-if={user.admin} className="badge">Admin</span>;
+      return <ul className="list">
+        <li render-if={items.length > 0} key="first">{items[0]}</li>
+        <Banner render-if={true}/>
+        <div render-if={user}>
+          <Condition if={user.name}><b>{user.name}</b></Condition>
+        </div>
+      </ul>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user, items }) {
+      const badge = Boolean(user.admin) ? <span className="badge">Admin</span> : null;
+      return <ul className="list">
+        <React.Fragment>{items.length > 0 ? <li key="first">{items[0]}</li> : null}</React.Fragment>
+        <React.Fragment>{<Banner/>}</React.Fragment>
+        <React.Fragment>{Boolean(user) ? <div>
+          <React.Fragment>{Boolean(user.name) ? <><b>{user.name}</b></> : null}</React.Fragment>
+        </div> : null}</React.Fragment>
+      </ul>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { inline_if: Some("render-if".into()), ..Default::default() });
+
+    // 未配置时属性原样保留
+    let plain = r#"
+    const node = <div render-if={ready}/>;
+    "#;
+    test_transform(plain, plain);
+}
+
+#[test]
+fn test_inline_else_pairs_with_previous_sibling() {
+    let input = r#"
+    function App({ user }) {
+      return <main>
+        <Profile render-if={user} user={user}/>
+        <Login render-else/>
+        <p render-if={user.admin}>Admin</p>
+        <p>Footer</p>
+        <p render-else>Orphan</p>
+      </main>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return <main>
+        <React.Fragment>{Boolean(user) ? <Profile user={user}/> : <Login/>}</React.Fragment>
+        <React.Fragment>{Boolean(user.admin) ? <p>Admin</p> : null}</React.Fragment>
+        <p>Footer</p>
+        <p render-else>Orphan</p>
+      </main>
+    }
+    "#;
+
+    let config = Config {
+        inline_if: Some("render-if".into()),
+        inline_else: Some("render-else".into()),
+        ..Default::default()
+    };
+    test_transform_with_config(input, expected, config);
+}
+
+#[test]
+fn test_branch_wrapper_replaces_multi_child_fragments() {
+    let input = r#"
+    function App({ user, tab }) {
+      const header = <Condition if={user}><Avatar/><Name/></Condition>;
+      return <div>
+        <Condition if={user}><Profile/></Condition>
+        <Switch shortCircuit>
+          <Switch.Case if={tab === "a"}><A/><B/></Switch.Case>
+          <Switch.Case else>Nothing</Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user, tab }) {
+      const header = Boolean(user) ? <div className="branch" style={{ display: "contents" }}><Avatar/><Name/></div> : null;
+      return <div>
+        <React.Fragment>{Boolean(user) ? <><Profile/></> : null}</React.Fragment>
+        <React.Fragment>{tab === "a" ? <div className="branch" style={{ display: "contents" }}><A/><B/></div> : <>Nothing</>}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    let branch_wrapper: BranchWrapper = serde_json::from_value(serde_json::json!({
+        "element": "div",
+        "props": { "className": "branch", "style": { "display": "contents" } },
+    })).unwrap();
+    test_transform_with_config(input, expected, Config { branch_wrapper: Some(branch_wrapper), ..Default::default() });
+}
+
+#[test]
+fn test_feature_flags() {
+    let input = r#"
+    function Checkout() {
+      const banner = <Feature name="promo"><Promo/></Feature>;
+      return <div>
+        <Feature name="checkout-v2"><NewCheckout/></Feature>
+        <Feature not name="checkout-v2"><OldCheckout/></Feature>
+        <Feature name="dark-mode"><ThemeToggle/></Feature>
+        <Feature not name={"beta"}><Stable/></Feature>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function Checkout() {
+      const banner = null;
+      return <div>
+        <NewCheckout/>
+        <React.Fragment>{Boolean(flags.isEnabled("dark-mode")) ? <><ThemeToggle/></> : null}</React.Fragment>
+        <React.Fragment>{!flags.isEnabled("beta") ? <><Stable/></> : null}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    let features = HashMap::from([("checkout-v2".to_string(), true), ("promo".to_string(), false)]);
+    let config = Config {
+        features: features.clone(),
+        feature_lookup: Some("flags.isEnabled".into()),
+        ..Default::default()
+    };
+    test_transform_with_config(input, expected, config);
+
+    // 没有 featureLookup 时未列出的开关保持原样
+    let unlisted = r#"
+    const toggle = <Feature name="dark-mode"><ThemeToggle/></Feature>;
+    "#;
+    test_transform_with_config(unlisted, unlisted, Config { features, ..Default::default() });
+
+    assert!(Config::from_json(r#"{ "featureLookup": "flags." }"#).unwrap_err().starts_with("invalid featureLookup"));
+}
+
+#[test]
+fn test_platform_gating() {
+    let input = r#"
+    function Search({ onChange }) {
+      const hint = <Platform native><Hint/></Platform>;
+      return <Form>
+        <Platform web><input onChange={onChange}/></Platform>
+        <Platform ios android><TextInput onChangeText={onChange}/></Platform>
+      </Form>
+    }
+    "#;
+
+    let web = r#"
+    function Search({ onChange }) {
+      const hint = null;
+      return <Form>
+        <input onChange={onChange}/>
+      </Form>
+    }
+    "#;
+    test_transform_with_config(input, web, Config { platform: Some("web".into()), ..Default::default() });
+
+    let android = r#"
+    function Search({ onChange }) {
+      const hint = null;
+      return <Form>
+        <TextInput onChangeText={onChange}/>
+      </Form>
+    }
+    "#;
+    test_transform_with_config(input, android, Config { platform: Some("android".into()), ..Default::default() });
+
+    // 未配置 platform 时保持原样
+    test_transform(input, input);
+}
+
+#[cfg(feature = "defines")]
+#[test]
+fn test_define_constants_fold_at_build_time() {
+    let input = r#"
+    function App({ user }) {
+      return <div>
+        <Condition if={FLAGS.newUI}><NewNav/></Condition>
+        <Unless if={FLAGS["newUI"]}><OldNav/></Unless>
+        <Condition if={PLATFORM === "web" && user}><Web/></Condition>
+        <Condition if={FLAGS.beta}><Beta/></Condition>
+        <Switch shortCircuit>
+          <Switch.Case if={RETRIES > 2}><Retry/></Switch.Case>
+          <Switch.Case if={RETRIES === 3}><Last/></Switch.Case>
+          <Switch.Case else><Done/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return <div>
+        <NewNav/>
+        <React.Fragment>{Boolean(PLATFORM === "web" && user) ? <><Web/></> : null}</React.Fragment>
+        <React.Fragment>{Boolean(FLAGS.beta) ? <><Beta/></> : null}</React.Fragment>
+        <React.Fragment>{RETRIES > 2 ? <Retry/> : <Last/>}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    let define = HashMap::from([
+        ("FLAGS.newUI".to_string(), serde_json::json!(true)),
+        ("PLATFORM".to_string(), serde_json::json!("web")),
+        ("RETRIES".to_string(), serde_json::json!(3)),
+    ]);
+    test_transform_with_config(input, expected, Config { define, ..Default::default() });
+}
+
+#[cfg(feature = "defines")]
+#[test]
+fn test_env_conditions_fold_at_build_time() {
+    let input = r#"
+    function App({ user }) {
+      return <div>
+        <Condition if={process.env.FLAG === "on"}><NewNav/></Condition>
+        <Condition if={process.env["FLAG"] !== "on"}><OldNav/></Condition>
+        <Condition if={process.env.UNKNOWN}><Debug/></Condition>
+        <Switch shortCircuit>
+          <Switch.Case if={process.env.REGION === "eu"}><Gdpr/></Switch.Case>
+          <Switch.Case if={process.env.REGION === "us" && !process.env.BETA}><Us/></Switch.Case>
+          <Switch.Case if={user}><Other/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return <div>
+        <NewNav/>
+        <React.Fragment>{Boolean(process.env.UNKNOWN) ? <><Debug/></> : null}</React.Fragment>
+        <Us/>
+      </div>
+    }
+    "#;
+
+    let env = [("FLAG", "on"), ("REGION", "us"), ("BETA", "")]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    test_transform_with_config(input, expected, Config { env, ..Default::default() });
+}
+
+#[cfg(feature = "defines")]
+#[test]
+fn test_jest_preset_keeps_runtime_tags() {
+    let input = r#"
+    function App({ user }) {
+      return <main>
+        <Condition if={process.env.BETA === "on"}><Beta/></Condition>
+        <Switch shortCircuit>
+          <Switch.Case if={process.env.REGION !== "eu"}><Global/></Switch.Case>
+          <Switch.Case if={user}><Home/></Switch.Case>
+        </Switch>
+      </main>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return <main>
+        <Condition if={true}><Beta/></Condition>
+        <Switch shortCircuit>
+          <Switch.Case if={false}><Global/></Switch.Case>
+          <Switch.Case if={user}><Home/></Switch.Case>
+        </Switch>
+      </main>;
+    }
+    "#;
+
+    let config = Config {
+        preset: Some(Preset::Jest),
+        merge_guards: true,
+        env: HashMap::from([("BETA".to_string(), "on".to_string()), ("REGION".to_string(), "eu".to_string())]),
+        ..Default::default()
+    };
+    test_transform_with_config(input, expected, config);
+}
+
+#[cfg(feature = "defines")]
+#[test]
+fn test_env_folding_uses_host_env_and_can_be_disabled() {
+    let input = r#"
+    function App() {
+      return <Condition if={process.env.NODE_ENV === "development"}><DevTools/></Condition>
+    }
+    "#;
+
+    let run = |config: Config| {
+        let host = TransformHost {
+            env: [("NODE_ENV".to_string(), "production".to_string())].into_iter().collect(),
+            ..Default::default()
+        };
+        let program = transform_program_with_host(Program::Module(parse_module(input)), config, host);
+        emit_module(&program.expect_module())
+    };
+
+    assert!(run(Config::default()).contains("return null"));
+    let dynamic = run(Config { fold_env: false, ..Default::default() });
+    assert!(dynamic.contains(r#"process.env.NODE_ENV === "development" ? <><DevTools/></> : null"#), "{dynamic}");
+}
+
+#[test]
+fn test_transform_factory_serves_parallel_workers() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<TransformFactory>();
+
+    let factory = TransformFactory::new(Config { fragment: "Fragment".into(), ..Default::default() });
+    let outputs: Vec<String> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..4)
+            .map(|index| {
+                let factory = &factory;
+                scope.spawn(move || {
+                    let input = format!("\nconst el = <div><Condition if={{flag{index}}}>x</Condition></div>");
+                    let mut module = parse_module(&input);
+                    module.visit_mut_with(&mut factory.visitor());
+                    emit_module(&module)
+                })
+            })
+            .collect();
+        workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+    });
+
+    for (index, output) in outputs.iter().enumerate() {
+        assert!(output.contains(&format!("<Fragment>{{Boolean(flag{index}) ? <>x</> : null}}</Fragment>")), "{output}");
+    }
+}
+
+#[cfg(all(feature = "cli", feature = "defines"))]
+#[test]
+fn test_transform_batch_shares_config_and_reports_totals() {
+    let files = vec![
+        ("src/App.tsx".to_string(), Program::Module(parse_module(r#"
+    const App = ({ user }) => <main>
+      <Condition if={process.env.BETA === "on"}><Beta/></Condition>
+      <Switch shortCircuit><Switch.Case if={user}><Home/></Switch.Case><Switch.Case else><Login/></Switch.Case></Switch>
+    </main>;
+    "#))),
+        ("src/util.ts".to_string(), Program::Module(parse_module("\nexport const double = (n) => n * 2;"))),
+        ("src/List.tsx".to_string(), Program::Module(parse_module(r#"
+    const List = ({ items }) => <ul>{items.map((item) => <li><Condition if={item.visible}>{item.name}</Condition></li>)}</ul>;
+    "#))),
+    ];
+    let env = HashMap::from([("BETA".to_string(), "on".to_string())]);
+
+    let (outputs, report) = transform_batch(files, Config::default(), env, None);
+
+    let paths: Vec<_> = outputs.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(paths, ["src/App.tsx", "src/util.ts", "src/List.tsx"]);
+    let app = emit_module(outputs[0].1.as_module().unwrap());
+    assert!(app.contains("<main>\n      <Beta/>"), "{app}");
+    assert!(app.contains("{Boolean(user) ? <Home/> : <Login/>}"), "{app}");
+
+    assert_eq!(report, swc_condition_plugin::BatchReport {
+        files: 3,
+        transformed_files: 2,
+        sites: vec![
+            (ControlSiteKind::Condition, 2),
+            (ControlSiteKind::Switch, 1),
+            (ControlSiteKind::Case, 1),
+            (ControlSiteKind::Else, 1),
+        ],
+    });
+}
+
+#[test]
+fn test_files_without_control_tags_are_left_untouched() {
+    // With nothing to lower, the file is not rewritten at all, so not even
+    // `importFragment` adds an import for the user's own `<Fragment>`.
+    let input = r#"
+    const List = ({ items })=><Fragment>{items.map((item)=><Row item={item}/>)}</Fragment>;
+    "#;
+    let config = Config { fragment: "Fragment".into(), import_fragment: true, ..Default::default() };
+    test_transform_with_config(input, input, config.clone());
+
+    // Tags nested deep inside other expressions are still found.
+    let input = r#"
+    class Page {
+      render() {
+        return <ul>{items.map((item)=><li><Condition if={item.visible}><Row/></Condition></li>)}</ul>;
+      }
+    }
+    "#;
+    let expected = r#"
+    import { Fragment } from "react";
+    class Page {
+      render() {
+        return <ul>{items.map((item)=><li><Fragment>{Boolean(item.visible) ? <><Row/></> : null}</Fragment></li>)}</ul>;
+      }
+    }
+    "#;
+    test_transform_with_config(input, expected, config);
+}
+
+#[test]
+fn test_generated_nodes_keep_source_spans() {
+    let input = r#"
+    const App = ({ a, b }) => <div>
+      <Condition if={a}><p/></Condition>
+      <Switch shortCircuit={false}><Case if={b}><span/></Case></Switch>
+    </div>;
+    "#;
+    let program = transform_program(Program::Module(parse_module(input)), Config::default());
+
+    let mut spans = GeneratedSpans::default();
+    program.visit_with(&mut spans);
+    assert!(!spans.0.is_empty());
+    for (name, span) in spans.0 {
+        assert!(!span.is_dummy(), "{name} has no source span");
+        assert!(span.lo.0 > 0 && (span.hi.0 as usize) <= input.len(), "{name} span {span:?} is outside the input");
+    }
+}
+
+/// Spans of the `Boolean` calls, `null` fallbacks and fragment wrappers.
+#[derive(Default)]
+struct GeneratedSpans(Vec<(&'static str, Span)>);
+
+impl Visit for GeneratedSpans {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if &*ident.sym == "Boolean" {
+            self.0.push(("Boolean", ident.span));
+        }
+    }
+
+    fn visit_null(&mut self, null: &Null) {
+        self.0.push(("null", null.span));
+    }
+
+    fn visit_jsx_member_expr(&mut self, member: &JSXMemberExpr) {
+        if &*member.prop.sym == "Fragment" {
+            self.0.push(("React.Fragment", member.span));
+        }
+        member.visit_children_with(self);
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_analyze_lists_control_sites() {
+    let input = r#"
+    function App({ user, status, items }) {
+      return <div>
+        <Condition if={user && user.active}><Profile/></Condition>
+        <Switch value={status} shortCircuit>
+          <Switch.Case is="loading"><Spinner/></Switch.Case>
+          <Switch.Default><Content/></Switch.Default>
+        </Switch>
+        <For each={items}>{item => <Row item={item}/>}</For>
+      </div>
+    }
+    "#;
+
+    let module = parse_module(input);
+    let sites = analyze(&Program::Module(module), Config::default());
+    let summary: Vec<_> = sites.iter()
+        .map(|site| (site.kind, site.condition.as_deref(), site.mode))
+        .collect();
+
+    assert_eq!(summary, vec![
+        (ControlSiteKind::Condition, Some("user && user.active"), None),
+        (ControlSiteKind::Switch, None, Some(SwitchMode::ShortCircuit)),
+        (ControlSiteKind::Case, Some(r#"status === "loading""#), Some(SwitchMode::ShortCircuit)),
+        (ControlSiteKind::Else, None, Some(SwitchMode::ShortCircuit)),
+        (ControlSiteKind::For, None, None),
+    ]);
+    assert!(sites.iter().all(|site| !site.span.is_dummy()));
+}
+
+#[test]
+fn test_diagnostics_json_writes_lsp_ranges() {
+    let dir = std::env::temp_dir().join(format!("swc-condition-diagnostics-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("diagnostics.jsonl");
+
+    let input = r#"export function Condition(props) {
+  return props.children
+}
+
+function App({ ready }) {
+  return <Condition if={ready}>
+    <p>Ready</p>
+  </Condition>
+}"#;
+    let config = Config {
+        diagnostics_json: Some(file.to_str().unwrap().into()),
+        ..Default::default()
+    };
+    annotated_output(input, config.clone());
+    annotated_output(input, config);
+
+    let written = std::fs::read_to_string(&file).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // Every run appends, one JSON object per line.
+    let lines: Vec<serde_json::Value> = written.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines.len(), 2);
+    let diagnostic = &lines[0];
+    assert_eq!(diagnostic["file"], "App.tsx");
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["code"], "runtime-component");
+    assert_eq!(diagnostic["range"]["start"], serde_json::json!({ "line": 5, "character": 9 }));
+    assert_eq!(diagnostic["range"]["end"], serde_json::json!({ "line": 7, "character": 14 }));
+    assert!(diagnostic["message"].as_str().unwrap().contains("`<Condition>` is also defined in this file"));
+}
+
+#[test]
+fn test_condition_key_kept_in_map() {
+    let input = r#"
+    function App({ items }) {
+      return items.map((item) => <Condition key={item.id} if={item.visible}><Row item={item} /></Condition>);
+    }
+    "#;
+
+    let expected = r#"
+    function App({ items }) {
+      return items.map((item)=><React.Fragment key={item.id}>{item.visible ? <><Row item={item}/></> : null}</React.Fragment>);
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_condition_key_kept_in_return() {
+    let input = r#"
+    function Row({ item }) {
+      return <Condition key={item.id} if={item.visible}><Cell /></Condition>;
+    }
+    "#;
+
+    let expected = r#"
+    function Row({ item }) {
+      return <React.Fragment key={item.id}>{item.visible ? <><Cell/></> : null}</React.Fragment>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_case_conditions_parenthesized_without_post_transform() {
+    // 不依赖 postTransform 的修正，生成时就要加括号
+    let input = r#"
+    function App({ status, priority }) {
+      return (
+        <div>
+          <Switch>
+            <Switch.Case if={status === 'urgent' ? priority > 5 : priority > 8}><HighPriorityAlert /></Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ status, priority }) {
+      return (
+        <div>
+          <React.Fragment>{(status === 'urgent' ? priority > 5 : priority > 8) ? <><HighPriorityAlert/></> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    let config = Config {
+        stages: StagesConfig { post_transform: false, ..StagesConfig::default() },
+        ..Config::default()
+    };
+    test_transform_program(input, expected, config);
+}
+
+#[test]
+fn test_switch_key_moves_to_single_else_element() {
+    let input = r#"
+    function App({ items }) {
+      return <ul>{items.map((item) => <Switch key={item.id}><Switch.Case else><Row item={item} /></Switch.Case></Switch>)}</ul>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ items }) {
+      return <ul>{items.map((item)=><Row item={item} key={item.id}/>)}</ul>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_key_moves_to_single_else_element_in_jsx() {
+    let input = r#"
+    function App({ item }) {
+      return <ul><Switch key={item.id}><Switch.Case else><Row item={item} /></Switch.Case></Switch></ul>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ item }) {
+      return <ul><Row item={item} key={item.id}/></ul>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_parallel_case_keys_kept() {
+    let input = r#"
+    function App({ user }) {
+      return (
+        <div>
+          <Switch>
+            <Switch.Case if={user.isAdmin} key="admin"><AdminBadge /><AdminMenu /></Switch.Case>
+            <Switch.Case if={user.isPremium} key="premium"><PremiumBadge /></Switch.Case>
+            <Switch.Case if={user.isGuest}>Guest</Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return (
+        <div>
+          <React.Fragment>{user.isAdmin ? <React.Fragment key="admin"><AdminBadge/><AdminMenu/></React.Fragment> : null}{user.isPremium ? <React.Fragment key="premium"><PremiumBadge/></React.Fragment> : null}{user.isGuest ? <>Guest</> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_case_keys_generates_index_keys() {
+    let input = r#"
+    function App({ user }) {
+      return (
+        <div>
+          <Switch>
+            <Switch.Case if={user.isAdmin}><AdminBadge /></Switch.Case>
+            <Switch.Case if={user.isPremium} key="premium"><PremiumBadge /><PremiumMenu /></Switch.Case>
+            <Switch.Case else>Guest</Switch.Case>
+          </Switch>
+          <Switch shortCircuit>
+            <Switch.Case if={user.isAdmin}><AdminBadge /></Switch.Case>
+          </Switch>
+        </div>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user }) {
+      return (
+        <div>
+          <React.Fragment>{user.isAdmin ? <React.Fragment key="case-0"><AdminBadge/></React.Fragment> : null}{user.isPremium ? <React.Fragment key="premium"><PremiumBadge/><PremiumMenu/></React.Fragment> : null}{!user.isAdmin && !user.isPremium ? <>Guest</> : null}</React.Fragment>
+          <React.Fragment>{Boolean(user.isAdmin) ? <AdminBadge/> : null}</React.Fragment>
+        </div>
+      )
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { case_keys: true, ..Config::default() });
+}
+
+#[test]
+fn test_condition_as_element() {
+    let input = r#"
+    function App({ open, title }) {
+      const panel = <Condition if={open} as="section" className="panel" {...rest}><h2>{title}</h2><p>Body</p></Condition>;
+      return (
+        <div>
+          <Unless if={open} as="UI.Hint" tone="muted">Closed</Unless>
+          <Condition if={true} as="aside">Always</Condition>
+          {panel}
+        </div>
+      );
+    }
+    "#;
+
+    let expected = r#"
+    function App({ open, title }) {
+      const panel = Boolean(open) ? <section className="panel" {...rest}><h2>{title}</h2><p>Body</p></section> : null;
+      return (
+        <div>
+          <React.Fragment>{!open ? <UI.Hint tone="muted">Closed</UI.Hint> : null}</React.Fragment>
+          <aside>Always</aside>
+          {panel}
+        </div>
+      );
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_condition_key_in_map_callbacks() {
+    let input = r#"
+    function List({ items }) {
+      return (
+        <ul>
+          {items.map((item) => {
+            return <Condition key={item.id} if={item.show}><li>{item.name}</li></Condition>;
+          })}
+          {items.map((item) => <Condition key={item.id} if={item.show} as="li" className="row">{item.name}</Condition>)}
+          {items.map((item) => (
+            <Unless key={item.id} if={item.hidden}>{item.name}</Unless>
+          ))}
+        </ul>
+      );
+    }
+    "#;
+
+    let expected = r#"
+    function List({ items }) {
+      return (
+        <ul>
+          {items.map((item)=>{
+            return <React.Fragment key={item.id}>{item.show ? <><li>{item.name}</li></> : null}</React.Fragment>;
+          })}
+          {items.map((item)=><React.Fragment key={item.id}>{item.show ? <li className="row">{item.name}</li> : null}</React.Fragment>)}
+          {items.map((item)=>(<React.Fragment key={item.id}>{!item.hidden ? <>{item.name}</> : null}</React.Fragment>))}
+        </ul>
+      );
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_keep_mounted_hides_instead_of_unmounting() {
+    let input = r#"
+    function App({ tab, user }) {
+      return (
+        <div>
+          <Condition if={tab === 'editor'} keepMounted><Editor /></Condition>
+          <Unless if={user} keepMounted as="section" className="guest" style={{ padding: 4 }}><Login /></Unless>
+        </div>
+      );
+    }
+    "#;
+
+    let expected = r#"
+    function App({ tab, user }) {
+      return (
+        <div>
+          <div style={{ display: tab === 'editor' ? undefined : "none" }}><Editor/></div>
+          <section className="guest" style={{ padding: 4, display: !user ? undefined : "none" }}><Login/></section>
+        </div>
+      );
+    }
+    "#;
+
+    test_transform(input, expected);
+
+    let input = r#"
+    function Preview({ open }) {
+      return <Condition if={open} keepMounted key="preview"><Canvas /></Condition>;
+    }
+    "#;
+
+    let expected = r#"
+    function Preview({ open }) {
+      return <span style={{ display: open ? undefined : "none" }} key="preview"><Canvas/></span>;
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { keep_mounted_element: "span".into(), ..Config::default() });
+}
+
+#[test]
+fn test_keep_mounted_static_condition_is_not_folded() {
+    // 常量条件也不能卸载 keepMounted 的子树
+    let input = r#"
+    function App() {
+      return (
+        <div>
+          <Condition if={false} keepMounted><Editor /></Condition>
+          <Condition if={false}><Debug /></Condition>
+        </div>
+      );
+    }
+    "#;
+
+    let expected = r#"
+    function App() {
+      return (
+        <div>
+          <div style={{ display: false ? undefined : "none" }}><Editor/></div>
+        </div>
+      );
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_memo_wraps_in_use_memo() {
+    let input = r#"
+    function UserRow({ user, theme }) {
+      return (
+        <Switch memo deps={[user, theme]} shortCircuit>
+          <Switch.Case if={user.admin}><Admin theme={theme} /></Switch.Case>
+          <Switch.Case else><Member /></Switch.Case>
+        </Switch>
+      );
+    }
+    const useBadge = (user) => {
+      const badge = <Switch memo deps={[user]}><Switch.Case if={user.vip}><Vip /></Switch.Case><Switch.Case if={user.new}><New /></Switch.Case></Switch>;
+      return badge;
+    };
+    "#;
+
+    let expected = r#"
+    function UserRow({ user, theme }) {
+      return (React.useMemo(()=>user.admin ? <Admin theme={theme}/> : <Member/>, [
+        user,
+        theme
+      ]));
+    }
+    const useBadge = (user)=>{
+      const badge = React.useMemo(()=><React.Fragment>{user.vip ? <><Vip/></> : null}{user.new ? <><New/></> : null}</React.Fragment>, [
+        user
+      ]);
+      return badge;
+    };
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_memo_outside_component_warns() {
+    let input = r#"
+    function renderRows(rows) {
+      return rows.map((row) => <Switch memo deps={[row]}><Switch.Case if={row.ok}><Ok /></Switch.Case></Switch>);
+    }
+    function App({ user }) {
+      return <Switch memo><Switch.Case if={user}><Hi /></Switch.Case></Switch>;
+    }
+    "#;
+
+    let expected = r#"
+    function renderRows(rows) {
+      return rows.map((row)=>row.ok ? <Ok/> : null);
+    }
+    function App({ user }) {
+      return user ? <Hi/> : null;
+    }
+    "#;
+
+    test_transform(input, expected);
+
+    let messages: Vec<_> = collect_diagnostics(input, Config::default())
+        .into_iter()
+        .map(|(level, message)| (level, message.split(':').next().unwrap_or_default().to_string()))
+        .collect();
+    assert_eq!(messages, vec![
+        (Level::Warning, "`memo` on a Switch outside a component or hook".to_string()),
+        (Level::Warning, "`memo` on a Switch needs `deps={[…]}`; the Switch is left unmemoized".to_string()),
+    ]);
+}
+
+#[test]
+fn test_condition_render_prop_child() {
+    let input = r#"
+    function Profile({ user, debug }) {
+      return (
+        <div>
+          <Condition if={user}>{() => <Name value={user.name} />}</Condition>
+          <Unless if={!user}>
+            {() => {
+              const { avatar } = user.profile;
+              return <img src={avatar} />;
+            }}
+          </Unless>
+          <Condition if={true}>{() => (<Always />)}</Condition>
+          <Condition if={debug}>{(props) => <Debug {...props} />}</Condition>
+        </div>
+      );
+    }
+    "#;
+
+    // 带参数的函数不是 render prop，原样保留
+    let expected = r#"
+    function Profile({ user, debug }) {
+      return (
+        <div>
+          <React.Fragment>{Boolean(user) ? <><Name value={user.name}/></> : null}</React.Fragment>
+          <React.Fragment>{!!user ? <>{(()=>{
+            const { avatar } = user.profile;
+            return <img src={avatar}/>;
+          })()}</> : null}</React.Fragment>
+          <Always/>
+          <React.Fragment>{Boolean(debug) ? <>{(props)=><Debug {...props}/>}</> : null}</React.Fragment>
+        </div>
+      );
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_switch_on_error_case_wraps_error_boundary() {
+    let input = r#"
+    function Orders({ orders }) {
+      return (
+        <Switch shortCircuit>
+          <Switch.Case if={orders.length === 0}><Empty /></Switch.Case>
+          <Switch.Case else><OrderTable rows={orders} /></Switch.Case>
+          <Switch.Case onError><p>Could not load orders</p><Retry /></Switch.Case>
+        </Switch>
+      );
+    }
+    "#;
+
+    let expected = r#"
+    function Orders({ orders }) {
+      return (<ErrorBoundary fallback={<><p>Could not load orders</p><Retry/></>}>{orders.length === 0 ? <Empty/> : <OrderTable rows={orders}/>}</ErrorBoundary>);
+    }
+    "#;
+
+    let config = Config { error_boundary: Some("ErrorBoundary".into()), ..Config::default() };
+    test_transform_with_config(input, expected, config);
+
+    // 没有配置 errorBoundary 时 onError 分支被忽略并给出警告
+    let expected = r#"
+    function Orders({ orders }) {
+      return orders.length === 0 ? <Empty/> : <OrderTable rows={orders}/>;
+    }
+    "#;
+
+    test_transform(input, expected);
+    let diagnostics = collect_diagnostics(input, Config::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].0, Level::Warning);
+    assert!(diagnostics[0].1.starts_with("`onError` case ignored"));
+}
+
+#[test]
+fn test_map_callback_concise_body_uses_return_context() {
+    let input = r#"
+    function List({ items }) {
+      return (
+        <ul>
+          {items.map(i => <Condition if={i.ok}><li>{i.name}</li></Condition>)}
+          {items.map(i => (
+            <Switch shortCircuit>
+              <Switch.Case if={i.done}><Done/></Switch.Case>
+              <Switch.Case else><Pending/></Switch.Case>
+            </Switch>
+          ))}
+        </ul>
+      );
+    }
+    "#;
+
+    let expected = r#"
+    function List({ items }) {
+      return (
+        <ul>
+          {items.map((i)=>i.ok ? <><li>{i.name}</li></> : null)}
+          {items.map((i)=>i.done ? <Done/> : <Pending/>)}
+        </ul>
+      );
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_condition_in_attribute_value_lowers_to_expression() {
+    let input = r#"
+    function Page({ x, status }) {
+      return (
+        <Layout
+          sidebar={<Condition if={x}><Nav/></Condition>}
+          header=<Switch shortCircuit>
+            <Switch.Case if={status === "ok"}><Ok/></Switch.Case>
+            <Switch.Case else><Pending/></Switch.Case>
+          </Switch>
+        />
+      );
+    }
+    "#;
+
+    let expected = r#"
+    function Page({ x, status }) {
+      return (
+        <Layout
+          sidebar={x ? <><Nav/></> : null}
+          header={status === "ok" ? <Ok/> : <Pending/>}/>
+      );
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_generated_names_avoid_existing_identifiers() {
+    let input = r#"
+    const __branchShared1 = "taken";
+    function App({ a, __switchValue, __case1 }) {
+      return <div>
+        <Switch shortCircuit>
+          <Switch.Case if={a}><A/><footer><p>Shared</p><p>Footer</p></footer></Switch.Case>
+          <Switch.Case else><B/><footer><p>Shared</p><p>Footer</p></footer></Switch.Case>
+        </Switch>
+        <Switch value={getRole(__switchValue)}>
+          <Switch.Case is="a"><A/></Switch.Case>
+          <Switch.Case else><B/></Switch.Case>
+        </Switch>
+        <Switch>
+          <Switch.Case if={check(__case1)}><C/></Switch.Case>
+          <Switch.Case else><D/></Switch.Case>
+        </Switch>
+      </div>
+    }
+    "#;
+
+    let expected = r#"
+    const __branchShared1 = "taken";
+    function App({ a, __switchValue, __case1 }) {
+      const __branchShared2 = <footer><p>Shared</p><p>Footer</p></footer>;
+      return <div>
+        <React.Fragment>{Boolean(a) ? <><A/>{__branchShared2}</> : <><B/>{__branchShared2}</>}</React.Fragment>
+        <React.Fragment>{((__switchValue2)=>((__case2)=><>{__case2 ? <><A/></> : null}{!__case2 ? <><B/></> : null}</>)(__switchValue2 === "a"))(getRole(__switchValue))}</React.Fragment>
+        <React.Fragment>{((__case3)=><>{__case3 ? <><C/></> : null}{!__case3 ? <><D/></> : null}</>)(check(__case1))}</React.Fragment>
+      </div>
+    }
+    "#;
+
+    test_transform_with_config(input, expected, Config { hoist_shared_branches: true, hoist_case_conditions: true, ..Default::default() });
+}
+
+#[test]
+fn test_call_arguments_in_chains_and_constructors() {
+    let input = r#"
+    ReactDOM.createRoot(el).render(<Condition if={ready}><App/></Condition>);
+    renderer?.render(<Condition if={ready}><App/></Condition>);
+    const story = new Story(<Switch shortCircuit>
+      <Switch.Case if={dark}><Dark/></Switch.Case>
+      <Switch.Case else><Light/></Switch.Case>
+    </Switch>);
+    "#;
+
+    let expected = r#"
+    ReactDOM.createRoot(el).render(Boolean(ready) ? <><App/></> : null);
+    renderer?.render(Boolean(ready) ? <><App/></> : null);
+    const story = new Story(dark ? <Dark/> : <Light/>);
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_span_mappings_point_at_original_markup() {
+    let input = r#"
+function App({ a, b }) {
+  const badge = <Condition if={b}><Badge/></Condition>;
+  return <div><Unless if={a}><Empty/></Unless>{badge}</div>;
+}"#;
+
+    let (program, mappings) = transform_program_with_span_mappings(Program::Module(parse_module(input)), Config::default());
+    let source = |mapping: &SpanMapping, span: Span| {
+        assert!(span.lo <= span.hi, "{mapping:?}");
+        &input[span.lo.0 as usize..span.hi.0 as usize]
+    };
+
+    let originals: Vec<_> = mappings.iter().map(|mapping| source(mapping, mapping.original)).collect();
+    assert_eq!(originals, vec!["<Condition if={b}><Badge/></Condition>", "<Unless if={a}><Empty/></Unless>"]);
+    // 降级出的表达式沿用原标签的位置
+    for mapping in &mappings {
+        assert_eq!(mapping.generated, mapping.original);
+    }
+
+    let Program::Module(module) = program else { unreachable!() };
+    assert!(emit_module(&module).contains("const badge = Boolean(b) ? <><Badge/></> : null;"));
+
+    // 单独使用 visitor 时需要显式开启
+    let mut module = parse_module(input);
+    let mut visitor = TransformVisitor::default();
+    module.visit_mut_with(&mut visitor);
+    assert!(visitor.take_span_mappings().is_empty());
+
+    let mut module = parse_module(input);
+    let mut visitor = TransformVisitor::default().with_span_mappings();
+    module.visit_mut_with(&mut visitor);
+    assert_eq!(visitor.take_span_mappings(), mappings);
+}
+
+#[test]
+fn test_object_properties_and_array_elements_use_assignment_context() {
+    let input = r#"
+    export default [{ path: "/admin", element: <Condition if={auth}><Admin/></Condition> }];
+    function Tabs({ a, b }) {
+      return <div>{[<Condition key="a" if={a}><A/></Condition>, <Unless key="b" if={b}><B/></Unless>]}</div>;
+    }
+    "#;
+
+    let expected = r#"
+    export default [
+      { path: "/admin", element: Boolean(auth) ? <><Admin/></> : null }
+    ];
+    function Tabs({ a, b }) {
+      return <div>{[
+        <React.Fragment key="a">{Boolean(a) ? <><A/></> : null}</React.Fragment>,
+        <React.Fragment key="b">{!b ? <><B/></> : null}</React.Fragment>
+      ]}</div>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_nullish_condition_notes_falsy_values() {
+    let input = r#"
+    function Cart({ cart, items, id, count }) {
+      return <div>
+        <Condition if={cart?.total}><Total/></Condition>
+        <Condition if={items.find(item => item.id === id)}><Selected/></Condition>
+        <Switch>
+          <Switch.Case if={count ?? cart.count}><Count/></Switch.Case>
+        </Switch>
+        <Condition if={cart?.total != null}><Total/></Condition>
+      </div>;
+    }
+    "#;
+
+    let diagnostics = collect_diagnostics(input, Config::default());
+    assert_eq!(diagnostics.len(), 3, "{diagnostics:?}");
+    assert!(diagnostics.iter().all(|(level, message)| *level == Level::Note && message.contains("`!= null`")));
+
+    // 不做布尔转换时不提示
+    let config = Config { boolean_strategy: BooleanStrategy::None, ..Config::default() };
+    assert!(collect_diagnostics(input, config).is_empty());
+}
+
+#[test]
+fn test_export_default_uses_return_context() {
+    let input = r#"
+    export default <Condition if={auth}><Admin/></Condition>;
+    "#;
+
+    let expected = r#"
+    export default auth ? <><Admin/></> : null;
+    "#;
+
+    test_transform(input, expected);
+
+    let input = r#"
+    export default <Switch shortCircuit>
+      <Switch.Case if={role === "admin"}><Admin/></Switch.Case>
+      <Switch.Case else><Member/></Switch.Case>
+    </Switch>;
+    "#;
+
+    let expected = r#"
+    export default role === "admin" ? <Admin/> : <Member/>;
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_two_instances_with_different_vocabularies() {
+    let input = r#"
+const admin = <Condition if={a}><Admin/></Condition>;
+const legacy = <If if={b}><Legacy/></If>;
+"#;
+
+    let current = TransformFactory::new(Config::default());
+    let legacy = TransformFactory::new(Config {
+        tags: TagNames { condition: "If".into(), ..TagNames::default() },
+        ..Config::default()
+    });
+
+    let mut module = parse_module(input);
+    module.visit_mut_with(&mut current.visitor());
+    module.visit_mut_with(&mut legacy.visitor());
+
+    // 每个实例的后处理只展开自己的占位元素
+    module.visit_mut_with(&mut legacy.post_visitor());
+    assert!(format!("{:?}", module.body[0]).contains(r#"sym: "__CONDITION_PLACEHOLDER__""#));
+    let output = emit_module(&module);
+    assert!(output.contains("const legacy = Boolean(b) ? <><Legacy/></> : null;"), "{output}");
+
+    module.visit_mut_with(&mut current.post_visitor());
+    let output = emit_module(&module);
+    assert!(output.contains("const admin = Boolean(a) ? <><Admin/></> : null;"), "{output}");
+    assert!(!output.contains("PLACEHOLDER"), "{output}");
+}
+
+#[test]
+fn test_logical_and_conditional_operands_lower_as_expressions() {
+    let input = r#"
+    function App({ a, role, x, items }) {
+      return <div>
+        {a ? <Switch shortCircuit>
+          <Switch.Case if={role === "admin"}><Admin/></Switch.Case>
+          <Switch.Case else><Member/></Switch.Case>
+        </Switch> : null}
+        {items ?? <Condition if={x}><Empty/></Condition>}
+      </div>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a, role, x, items }) {
+      return <div>
+        {a ? role === "admin" ? <Admin/> : <Member/> : null}
+        {items ?? (Boolean(x) ? <><Empty/></> : null)}
+      </div>;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_misused_control_tags_are_diagnosed() {
+    let input = r#"
+    function App({ user, role }) {
+      return <div>
+        <Condition><A/></Condition>
+        <Condition if="user"><B/></Condition>
+        <Switch shortCircuit>
+          <Switch.Case if={role === "admin"} else><Admin/></Switch.Case>
+          <Switch.Case><Guest/></Switch.Case>
+          <Switch.Case else><Login/></Switch.Case>
+          <Switch.Case else><Other/></Switch.Case>
+        </Switch>
+      </div>;
+    }
+    "#;
+
+    let diagnostics = collect_diagnostics(input, Config::default());
+    let messages: Vec<_> = diagnostics.iter().map(|(level, message)| (*level, message.as_str())).collect();
+    assert_eq!(messages, [
+        (Level::Warning, "`<Condition>` has no `if` attribute; the tag is left as a runtime component"),
+        (Level::Warning, "`if` takes an expression, `if={…}`, not a string; the tag is left as a runtime component"),
+        (Level::Warning, "`<Switch.Case>` has both `if` and `else`; it is a conditional case and `else` is ignored"),
+        (Level::Warning, "`<Switch.Case>` has no `if` attribute; this case never renders"),
+        (Level::Warning, "else case ignored: this Switch renders another one (the lowest `priority` wins, later cases win ties)"),
+    ]);
+
+    // 每条规则可单独调整级别或关闭
+    let config = Config {
+        diagnostics: HashMap::from([
+            ("missing-condition".to_string(), DiagnosticLevel::Error),
+            ("string-condition".to_string(), DiagnosticLevel::Off),
+            ("case-if-and-else".to_string(), DiagnosticLevel::Off),
+            ("ignored-else".to_string(), DiagnosticLevel::Off),
+        ]),
+        ..Config::default()
+    };
+    let levels: Vec<_> = collect_diagnostics(input, config).into_iter().map(|(level, _)| level).collect();
+    assert_eq!(levels, [Level::Error, Level::Error]);
+    assert!(Config::from_json(r#"{ "diagnostics": { "missing-condition": "error" } }"#).is_ok());
+}
+
+/// Gives every node the same span, as some code generators do.
+#[cfg(feature = "instrumentation")]
+struct Respan(Span);
+
+#[cfg(feature = "instrumentation")]
+impl VisitMut for Respan {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = self.0;
+    }
+}
+
+#[cfg(feature = "instrumentation")]
+#[test]
+fn test_synthetic_spans() {
+    let input = r#"/** @jsx h */
+function App({ show, status }) {
+  return <div>
+    <Condition if={show}><p>Hi</p></Condition>
+    <Switch shortCircuit>
+      <Switch.Case if={status}><A/></Switch.Case>
+      <Switch.Case else><B/></Switch.Case>
+    </Switch>
+  </div>
+}"#;
+    let shared = Span::new(BytePos(20), BytePos(30));
+
+    // 生成代码：所有节点共用一个 span，注释另行分配位置并按序号标注
+    let config = Config { annotate_branches: true, synthetic_spans: true, ..Default::default() };
+    let output = GLOBALS.set(&Default::default(), || annotated_output_with(input, config, |module| module.visit_mut_with(&mut Respan(shared))));
+    assert!(output.contains("Boolean(show) ? /* condition-switch:branch(#1) */ (<><p>Hi</p></>) : null"), "{output}");
+    assert!(output.contains("Boolean(status) ? /* condition-switch:branch(#2) */ (<A/>) : /* condition-switch:branch(#3) */ (<B/>)"), "{output}");
+
+    // 不开 syntheticSpans 时重复位置只标注一次，全为 DUMMY 时不标注
+    let config = Config { annotate_branches: true, ..Default::default() };
+    let output = annotated_output_with(input, config.clone(), |module| module.visit_mut_with(&mut Respan(shared)));
+    assert_eq!(output.matches("condition-switch:branch").count(), 1, "{output}");
+    let output = annotated_output_with(input, config, |module| module.visit_mut_with(&mut Respan(DUMMY_SP)));
+    assert!(!output.contains("condition-switch:branch") && output.contains("Boolean(status) ? <A/> : <B/>"), "{output}");
+
+    // 文件本身的 span 是 DUMMY 时，pragma 从第一条语句前读取
+    let output = annotated_output_with(input, Config::default(), |module| module.span = DUMMY_SP);
+    assert!(output.contains("<Fragment>{Boolean(show)"), "{output}");
+}
+
+#[test]
+fn test_strict_attributes_reports_unknown_attributes() {
+    let input = r#"
+    function App({ user, role, props }) {
+      return <div>
+        <Condition iff={user} key="a"><A/></Condition>
+        <Condition if={true} className="debug"><B/></Condition>
+        <Switch shortCircuit {...props}>
+          <Switch.Case if={role === "admin"} onClick={track}><Admin/></Switch.Case>
+          <Switch.Default priority={1} els><Guest/></Switch.Default>
+        </Switch>
+      </div>;
+    }
+    "#;
+
+    let config = Config { strict_attributes: true, ..Config::default() };
+    let diagnostics = collect_diagnostics(input, config);
+    let messages: Vec<_> = diagnostics.iter().map(|(level, message)| (*level, message.as_str())).collect();
+    assert_eq!(messages, [
+        (Level::Error, "`<Condition>` has no `iff` attribute (did you mean `if`?); it is ignored"),
+        (Level::Warning, "`<Condition>` has no `if` attribute; the tag is left as a runtime component"),
+        (Level::Error, "`<Condition>` has no `className` attribute; it is ignored"),
+        (Level::Error, "spread attributes on `<Switch>` are ignored"),
+        (Level::Error, "`<Switch.Case>` has no `onClick` attribute; it is ignored"),
+        (Level::Error, "`<Switch.Default>` has no `els` attribute; it is ignored"),
+    ]);
+
+    // 默认不检查
+    assert!(collect_diagnostics(input, Config::default()).iter().all(|(level, _)| *level == Level::Warning));
+}
+
+#[test]
+fn test_case_order_sorts_short_circuit_cases() {
+    let input = r#"
+    function App({ user, flags }) {
+      return <div>
+        <Switch shortCircuit>
+          <Switch.Case if={flags.beta} order={20}><Beta/></Switch.Case>
+          <Switch.Case if={user.banned}><Banned/></Switch.Case>
+          <Switch.Case if={!user} order="10"><Login/></Switch.Case>
+          <Switch.Case else><Home/></Switch.Case>
+        </Switch>
+        <Switch>
+          <Switch.Case if={flags.a} order={2}><A/></Switch.Case>
+          <Switch.Case if={flags.b} order={1}><B/></Switch.Case>
+        </Switch>
+      </div>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ user, flags }) {
+      return <div>
+        <React.Fragment>{!user ? <Login/> : Boolean(flags.beta) ? <Beta/> : Boolean(user.banned) ? <Banned/> : <Home/>}</React.Fragment>
+        <React.Fragment>{flags.a ? <><A/></> : null}{flags.b ? <><B/></> : null}</React.Fragment>
+      </div>;
+    }
+    "#;
+
+    test_transform(input, expected);
+
+    let duplicated = r#"
+    function App({ a, b }) {
+      return <Switch shortCircuit>
+        <Switch.Case if={a} order={1}><A/></Switch.Case>
+        <Switch.Case if={b} order={1}><B/></Switch.Case>
+      </Switch>;
+    }
+    "#;
+    let diagnostics = collect_diagnostics(duplicated, Config::default());
+    assert_eq!(diagnostics, [(Level::Warning, "another case of this Switch also has `order={1}`; the two run in source order".to_string())]);
+}
+
+#[test]
+fn test_switch_values_must_be_exhaustive() {
+    let input = r#"
+    function App({ status }) {
+      return <Switch value={status} values={["idle", "loading", "error", "done"]}>
+        <Switch.Case is="idle"><Idle/></Switch.Case>
+        <Switch.Case in={["loading"]}><Spinner/></Switch.Case>
+        <Switch.Case when={isDone}><Done/></Switch.Case>
+      </Switch>;
+    }
+    "#;
+
+    let diagnostics = collect_diagnostics(input, Config::default());
+    assert_eq!(diagnostics, [
+        (Level::Error, r#"no case of this Switch matches "error" from `values`; add one or an else case"#.to_string()),
+        (Level::Error, r#"no case of this Switch matches "done" from `values`; add one or an else case"#.to_string()),
+    ]);
+
+    // 有 else 分支即视为穷尽，values 本身不出现在输出里
+    let input = r#"
+    function App({ status }) {
+      return <Switch value={status} values={["idle", "error"]} shortCircuit>
+        <Switch.Case is="idle"><Idle/></Switch.Case>
+        <Switch.Default><Error/></Switch.Default>
+      </Switch>;
+    }
+    "#;
+    assert!(collect_diagnostics(input, Config::default()).is_empty());
+
+    let expected = r#"
+    function App({ status }) {
+      return status === "idle" ? <Idle/> : <Error/>;
+    }
+    "#;
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_constant_condition_lint_is_opt_in() {
+    let input = r#"
+    function App({ user }) {
+      return <div>
+        <Condition if={true}><Debug/></Condition>
+        <Unless if={!1}><Hidden/></Unless>
+        <Condition if={DEBUG}><Panel/></Condition>
+        <Condition if={user}><Profile/></Condition>
+        <Switch>
+          <Switch.Case if={false}><Never/></Switch.Case>
+          <Switch.Default><Fallback/></Switch.Default>
+        </Switch>
+      </div>;
+    }
+    "#;
+    assert!(collect_diagnostics(input, Config::default()).is_empty());
+
+    let mut config = Config::default();
+    config.diagnostics.insert("constant-condition".to_string(), DiagnosticLevel::Warn);
+    config.define.insert("DEBUG".to_string(), serde_json::json!(false));
+    let diagnostics = collect_diagnostics(input, config);
+    assert_eq!(diagnostics, [
+        (Level::Warning, "this condition is always true, which usually means leftover debug code".to_string()),
+        (Level::Warning, "this condition is always false, which usually means leftover debug code".to_string()),
+        (Level::Warning, "this condition is always false, which usually means leftover debug code".to_string()),
+    ]);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_audit_counts_runtime_conditionals_and_control_tags() {
+    let files = vec![
+        ("src/Done.tsx".to_string(), Program::Module(parse_module(r#"
+    const Done = ({ user }) => <main>
+      <Condition if={user}><Home/></Condition>
+      <Switch><Switch.Case if={user.admin}><Admin/></Switch.Case></Switch>
+    </main>;
+    "#))),
+        ("src/util.ts".to_string(), Program::Module(parse_module("\nexport const pick = (a, b) => a ? b : null;"))),
+        ("src/Todo.tsx".to_string(), Program::Module(parse_module(r#"
+    const Todo = ({ items, error }) => <ul title={error ? "x" : "y"}>
+      {error && <Error/>}
+      {items.length ? items.map((item) => <li>{item.done ? <Check/> : null}</li>) : <Empty/>}
+      <Unless if={items.length}><Hint/></Unless>
+    </ul>;
+    "#))),
+    ];
+
+    let report = audit(&files, Config::default());
+    let summary: Vec<_> = report.files.iter().map(|file| (file.path.as_str(), file.control_sites, file.runtime_conditionals.len())).collect();
+    assert_eq!(summary, [("src/Todo.tsx", 1, 3), ("src/Done.tsx", 2, 0)]);
+    assert_eq!((report.control_sites, report.runtime_conditionals), (3, 3));
+    assert_eq!(report.adoption(), 0.5);
+}
+
+#[test]
+fn test_verify_output_reports_surviving_control_tags() {
+    let input = r#"
+    function App({ user, Switch }) {
+      return <main>
+        <Condition if={user}><Profile/></Condition>
+        <Condition><Orphan/></Condition>
+        <Switch><Switch.Case if={user}><Local/></Switch.Case></Switch>
+        <__CONDITION_PLACEHOLDER__>stray</__CONDITION_PLACEHOLDER__>
+      </main>;
+    }
+    "#;
+    let config = Config { verify_output: true, ..Config::default() };
+    let errors: Vec<_> = collect_diagnostics(input, config).into_iter().filter(|(level, _)| *level == Level::Error).collect();
+    assert_eq!(errors, [
+        (Level::Error, "`<Condition>` is still in the output; the transform did not lower it".to_string()),
+        (Level::Error, "placeholder `<__CONDITION_PLACEHOLDER__>` is still in the output; the postTransform stage did not unwrap it".to_string()),
+    ]);
+
+    // 关闭 postTransform 时不校验
+    let config = Config { verify_output: true, stages: StagesConfig { post_transform: false, ..StagesConfig::default() }, ..Config::default() };
+    assert!(collect_diagnostics(input, config).iter().all(|(level, _)| *level != Level::Error));
+}
+
+#[test]
+fn test_condition_ignore_directive_and_raw_attribute() {
+    let input = r#"
+    function App({ user, admin }) {
+      const banner = /* @condition-ignore */ <Condition if={user}><Banner/></Condition>;
+      return <main>
+        {/* @condition-ignore */}
+        <Condition if={user}><Profile/></Condition>
+        {/* unrelated */}
+        <Condition if={admin}><Admin/></Condition>
+        <Switch raw><Switch.Case if={admin}><Condition if={user}><Nested/></Condition></Switch.Case></Switch>
+      </main>;
+    }
+    "#;
+    let diagnostics = Arc::new(Mutex::new(Vec::new()));
+    let handler = Handler::with_emitter(true, false, Box::new(CollectingEmitter(diagnostics.clone())));
+    let output = HANDLER.set(&handler, || annotated_output(input, Config { verify_output: true, ..Config::default() }));
+    assert!(diagnostics.lock().unwrap().is_empty(), "{:?}", diagnostics.lock().unwrap());
+    // 注释形式不改动标签本身，也不会补上 raw 属性
+    assert!(output.contains("const banner = <Condition if={user}><Banner/></Condition>;"), "{output}");
+    assert!(output.contains("<Condition if={user}><Profile/></Condition>"), "{output}");
+    assert!(!output.contains("if={user} raw"), "{output}");
+    assert!(output.contains("{Boolean(admin) ? <><Admin/></> : null}"), "{output}");
+    // raw 只作用于标签本身，子节点里的控制标签照常降级
+    assert!(output.contains("<Switch raw><Switch.Case if={admin}><React.Fragment>{Boolean(user) ? <><Nested/></> : null}</React.Fragment></Switch.Case></Switch>"), "{output}");
+
+    // 没有注释存储时只认 raw 属性
+    let expected = r#"
+    function App({ user }) {
+      return <Condition if={user} raw><Profile/></Condition>;
+    }
+    "#;
+    test_transform(expected, expected);
+    assert!(collect_diagnostics(expected, Config { verify_output: true, ..Config::default() }).is_empty());
+}
+
+#[test]
+fn test_disable_directive_skips_the_file() {
+    let input = r#"// @swc-condition-disable
+import { Condition } from "react-condition";
+
+export function App({ user }) {
+  return <Condition if={user}><Profile/></Condition>;
+}"#;
+    let config = Config { verify_output: true, strip_imports: vec!["react-condition".to_string()], ..Config::default() };
+    let output = annotated_output(input, config);
+    assert!(output.contains(r#"import { Condition } from "react-condition";"#), "{output}");
+    assert!(output.contains("return <Condition if={user}><Profile/></Condition>;"), "{output}");
+
+    // 指令只在文件开头生效
+    let input = r#"import { Condition } from "react-condition";
+
+// @swc-condition-disable
+export function App({ user }) {
+  return <Condition if={user}><Profile/></Condition>;
+}"#;
+    let output = annotated_output(input, Config::default());
+    assert!(output.contains("return user ? <><Profile/></> : null;"), "{output}");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_include_and_exclude_filter_files_by_name() {
+    let input = r#"
+    const App = ({ user }) => <main><Condition if={user}><Home/></Condition></main>;
+    "#;
+    let config = Config {
+        include: vec!["**/src/**/*.tsx".to_string()],
+        exclude: vec!["**/node_modules/**".to_string(), "**/*.stories.*".to_string(), "**/__tests__/**".to_string()],
+        ..Config::default()
+    };
+    let run = |filename: Option<&str>| {
+        let host = TransformHost { filename: filename.map(str::to_string), ..Default::default() };
+        let program = transform_program_with_host(Program::Module(parse_module(input)), config.clone(), host);
+        !emit_module(&program.expect_module()).contains("<Condition")
+    };
+
+    assert!(run(Some("/app/src/App.tsx")));
+    assert!(run(Some("C:\\app\\src\\pages\\Home.tsx")));
+    assert!(run(None));
+    assert!(!run(Some("/app/src/App.ts")));
+    assert!(!run(Some("/app/scripts/App.tsx")));
+    assert!(!run(Some("/app/node_modules/ui/src/App.tsx")));
+    assert!(!run(Some("/app/src/App.stories.tsx")));
+    assert!(!run(Some("/app/src/__tests__/App.tsx")));
+
+    let files = vec![
+        ("/app/src/App.tsx".to_string(), Program::Module(parse_module(input))),
+        ("/app/src/App.stories.tsx".to_string(), Program::Module(parse_module(input))),
+    ];
+    let (outputs, report) = transform_batch(files, config.clone(), HashMap::new(), None);
+    assert!(!emit_module(outputs[0].1.as_module().unwrap()).contains("<Condition"));
+    assert!(emit_module(outputs[1].1.as_module().unwrap()).contains("<Condition"));
+    assert_eq!((report.files, report.transformed_files), (2, 1));
+
+    let files = vec![
+        ("/app/src/App.tsx".to_string(), Program::Module(parse_module(input))),
+        ("/app/scripts/App.tsx".to_string(), Program::Module(parse_module(input))),
+    ];
+    let config = Config { include: vec!["src/**".to_string()], ..Config::default() };
+    let (_, report) = transform_batch(files, config, HashMap::new(), Some("/app".to_string()));
+    assert_eq!((report.files, report.transformed_files), (2, 1));
+
+    // 相对路径的模式按项目目录匹配
+    let config = Config { include: vec!["src/**/*.tsx".to_string()], exclude: vec!["src/legacy/**".to_string()], ..Config::default() };
+    let run = |filename: &str, cwd: &str| {
+        let host = TransformHost { filename: Some(filename.to_string()), cwd: Some(cwd.to_string()), ..Default::default() };
+        let program = transform_program_with_host(Program::Module(parse_module(input)), config.clone(), host);
+        !emit_module(&program.expect_module()).contains("<Condition")
+    };
+    assert!(run("/home/u/app/src/a.tsx", "/home/u/app"));
+    assert!(run("/home/u/app/src/pages/a.tsx", "/home/u/app/"));
+    assert!(run("C:\\app\\src\\a.tsx", "C:\\app"));
+    assert!(!run("/home/u/app/src/legacy/a.tsx", "/home/u/app"));
+    assert!(!run("/home/u/app/packages/ui/src/a.tsx", "/home/u/app"));
+    assert!(!run("/home/u/other/src/a.tsx", "/home/u/app"));
+}
+
+#[test]
+fn test_relative_globs_without_cwd_match_the_path_as_given() {
+    // 没有 cwd 时不读取进程的工作目录，结果与运行环境无关
+    let input = r#"
+    const App = ({ user }) => <main><Condition if={user}><Home/></Condition></main>;
+    "#;
+    let config = Config { include: vec!["src/**/*.tsx".to_string()], ..Config::default() };
+    let run = |filename: &str| {
+        let host = TransformHost { filename: Some(filename.to_string()), ..Default::default() };
+        let program = transform_program_with_host(Program::Module(parse_module(input)), config.clone(), host);
+        !emit_module(&program.expect_module()).contains("<Condition")
+    };
+    assert!(run("src/App.tsx"));
+    assert!(!run(&format!("{}/src/App.tsx", std::env::current_dir().unwrap().display())));
+}
+
+#[test]
+fn test_for_key_params_avoid_names_of_the_file() {
+    let input = r#"
+    import _ from "lodash";
+    function List({ items, __index }) {
+      return (
+        <ul>
+          <For each={items}>
+            {() => <Row x={_.get(a, "b")} y={__index} />}
+          </For>
+        </ul>
+      )
+    }
+    "#;
+
+    let expected = r#"
+    import _ from "lodash";
+    function List({ items, __index }) {
+      return (
+        <ul>
+          <React.Fragment>{items.map((_2, __index2)=><Row x={_.get(a, "b")} y={__index} key={__index2}/>)}</React.Fragment>
+        </ul>
+      )
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_parenthesized_ternary_keeps_parens_before_member_and_call() {
+    let input = r#"
+    function App({ a, b, c, user }) {
+      const label = (a ? b : c).foo;
+      const run = (a ? b : c)();
+      const made = new (a ? b : c)();
+      const tagged = (a ? b : c)`x`;
+      return <Condition if={user}>{(a ? b : c)?.bar}</Condition>;
+    }
+    "#;
+
+    let expected = r#"
+    function App({ a, b, c, user }) {
+      const label = (a ? b : c).foo;
+      const run = (a ? b : c)();
+      const made = new (a ? b : c)();
+      const tagged = (a ? b : c)`x`;
+      return user ? <>{(a ? b : c)?.bar}</> : null;
+    }
+    "#;
+
+    test_transform(input, expected);
+}
+
+#[test]
+fn test_files_without_control_tags_skip_post_transform() {
+    let input = "\nconst label = (ready ? <Done/> : <Loading/>);\nconst value = (a ? b : c);";
+    let program = transform_program(Program::Module(parse_module(input)), Config::default());
+    let output = emit_module(&program.expect_module());
+    assert!(output.contains("const label = (ready ? <Done/> : <Loading/>);"), "{output}");
+    assert!(output.contains("const value = (a ? b : c);"), "{output}");
+}
+
+#[test]
+fn test_strip_imports_removes_lowered_feature_and_platform() {
+    let input = r#"
+    import { Feature, Platform } from "@/components/control";
+    function App() {
+      return <main>
+        <Feature name="beta"><Beta/></Feature>
+        <Platform web><Web/></Platform>
+      </main>
+    }
+    "#;
+
+    let expected = r#"
+    function App() {
+      return <main>
+        <Beta/>
+        <Web/>
+      </main>
+    }
+    "#;
+
+    test_transform_program(input, expected, Config {
+        strip_imports: vec!["@/components/control".into()],
+        features: HashMap::from([("beta".to_string(), true)]),
+        platform: Some("web".into()),
+        ..Default::default()
+    });
+}
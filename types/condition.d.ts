@@ -4,8 +4,21 @@ declare namespace JSX {
       if: any;
       children?: React.ReactNode;
     };
+    Unless: {
+      if: any;
+      children?: React.ReactNode;
+    };
+    For: {
+      each: readonly any[];
+      children: (item: any, index: number) => React.ReactNode;
+    };
+    With: {
+      [binding: string]: any;
+      children?: React.ReactNode;
+    };
     Switch: {
       shortCircuit?: boolean;
+      value?: any;
       children?: React.ReactNode;
     };
   }
@@ -13,7 +26,17 @@ declare namespace JSX {
 
 declare namespace Switch {
   interface Case {
-    if: any;
+    if?: any;
+    is?: any;
+    in?: readonly any[];
+    when?: (value: any) => unknown;
+    else?: boolean;
+    children?: React.ReactNode;
+  }
+  interface Default {
+    children?: React.ReactNode;
+  }
+  interface Else {
     children?: React.ReactNode;
   }
 }